@@ -0,0 +1,76 @@
+fn main() {
+    #[cfg(feature = "chains")]
+    chains::generate();
+}
+
+/// Generates the constants of the `chains` module from the vendored snapshot
+/// of the chain registry.
+#[cfg(feature = "chains")]
+mod chains {
+    use serde_json::Value;
+    use std::collections::HashSet;
+    use std::fmt::Write;
+    use std::path::Path;
+
+    const SNAPSHOT: &str = "chains/chains.json";
+
+    pub fn generate() {
+        println!("cargo:rerun-if-changed={}", SNAPSHOT);
+
+        let snapshot = std::fs::read_to_string(SNAPSHOT).unwrap();
+        let snapshot: Value = serde_json::from_str(&snapshot).unwrap();
+
+        let mut out = String::new();
+        let mut all = Vec::new();
+        let mut used = HashSet::new();
+        // The export has more fields, such as rpc and nativeCurrency, which
+        // are ignored
+        for chain in snapshot.as_array().unwrap() {
+            let name = chain["name"].as_str().unwrap();
+            let chain_id = chain["chainId"].as_u64().unwrap();
+            let short_name = chain["shortName"].as_str().unwrap();
+
+            let mut ident = constant_name(name);
+            if ident.is_empty() {
+                ident = format!("CHAIN_{}", chain_id);
+            }
+            if !used.insert(ident.clone()) {
+                ident = format!("{}_{}", ident, chain_id);
+                assert!(used.insert(ident.clone()), "Duplicate chain {}", ident);
+            }
+
+            writeln!(out, "/// {} ({})", name, chain_id).unwrap();
+            writeln!(
+                out,
+                "pub const {}: Chain = Chain {{ name: {:?}, chain_id: {}, short_name: {:?} }};",
+                ident, name, chain_id, short_name
+            )
+            .unwrap();
+            all.push(ident);
+        }
+        writeln!(out, "/// Every chain in the registry snapshot").unwrap();
+        writeln!(out, "pub const ALL: &[Chain] = &[{}];", all.join(", ")).unwrap();
+
+        let dest = Path::new(&std::env::var("OUT_DIR").unwrap()).join("chains.rs");
+        std::fs::write(dest, out).unwrap();
+    }
+
+    /// "Avalanche C-Chain" => "AVALANCHE_C_CHAIN". Characters other than
+    /// ASCII letters and digits separate words.
+    fn constant_name(name: &str) -> String {
+        let mut result = String::new();
+        for word in name.split(|c: char| !c.is_ascii_alphanumeric()) {
+            if word.is_empty() {
+                continue;
+            }
+            if !result.is_empty() {
+                result.push('_');
+            }
+            result.push_str(&word.to_ascii_uppercase());
+        }
+        if result.starts_with(|c: char| c.is_ascii_digit()) {
+            result.insert_str(0, "CHAIN_");
+        }
+        result
+    }
+}
@@ -0,0 +1,4 @@
+#!/bin/sh
+# Replaces the snapshot with the current export of the registry
+set -e
+curl -fsSL https://chainid.network/chains_mini.json -o "$(dirname "$0")/chains.json"
@@ -0,0 +1,373 @@
+//! Derive macros for eip-712-derive. Enable the `derive` feature of that crate
+//! rather than depending on this one directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Field, Fields, LitStr};
+
+/// Implements `StructType` with the struct's name as the type name and its
+/// fields as members, in declaration order.
+///
+/// Container attributes:
+/// - `#[eip712(rename_all = "camelCase")]` converts snake_case field names to
+///   camelCase member names.
+/// - `#[eip712(type_name = <expr>)]` uses a `&'static str` constant expression
+///   as the type name instead of the struct name. Generic structs use it to
+///   name each instantiation, eg: `#[eip712(type_name = T::ENVELOPE)]`, since
+///   two different types can not share a name in one message.
+///
+/// Field attributes:
+/// - `#[eip712(rename = "verifyingContract")]` uses the given member name
+///   instead of the field name.
+/// - `#[eip712(skip)]` leaves the field out of the type. Use it for local
+///   bookkeeping which is not part of the signed message.
+///
+/// With the `json` feature of eip-712-derive, the struct also implements
+/// `FromJson` and gets a `from_typed_data_message` constructor, which reads
+/// the `message` of eth_signTypedData_v4 JSON and rejects unknown members.
+/// Structs with skipped fields do not, since the JSON has no values for them.
+#[proc_macro_derive(StructType, attributes(eip712))]
+pub fn derive_struct_type(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    struct_type(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn struct_type(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "EIP-712 members must be named",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "StructType can only be derived for structs. For fieldless enums, derive \
+                 MemberType with #[eip712(repr = \"uint8\")]",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let container = container_attributes(input)?;
+    let type_name = match &container.type_name {
+        Some(type_name) => quote! { #type_name },
+        None => {
+            let type_name = name.to_string();
+            quote! { #type_name }
+        }
+    };
+    let mut visits = Vec::new();
+    let mut type_visits = Vec::new();
+    let mut members = Vec::new();
+    let mut initializers = Vec::new();
+    let mut skipped = false;
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let attributes = field_attributes(field)?;
+        if attributes.skip {
+            skipped = true;
+            continue;
+        }
+        let member = match attributes.rename {
+            Some(rename) => rename,
+            None => {
+                let name = ident.to_string();
+                let name = name.trim_start_matches("r#");
+                if container.camel_case {
+                    camel_case(name)
+                } else {
+                    name.to_owned()
+                }
+            }
+        };
+        if members.contains(&member) {
+            return Err(Error::new_spanned(
+                ident,
+                format!("duplicated member name {}", member),
+            ));
+        }
+        visits.push(quote! { visitor.visit(#member, &self.#ident); });
+        let ty = &field.ty;
+        type_visits.push(quote! { visitor.visit::<#ty>(#member); });
+        initializers.push(quote! {
+            #ident: ::eip_712_derive::__private::struct_member(__members, #type_name, #member)?
+        });
+        members.push(member);
+    }
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let from_json = if cfg!(feature = "json") && !skipped {
+        // Generic members must be read from JSON too
+        let mut generics = input.generics.clone();
+        let type_params: Vec<_> = generics.type_params().map(|p| p.ident.clone()).collect();
+        let predicates = &mut generics.make_where_clause().predicates;
+        for param in type_params {
+            predicates.push(syn::parse_quote! { #param: ::eip_712_derive::FromJson });
+        }
+        let (_, _, json_where_clause) = generics.split_for_impl();
+        quote! {
+            impl #impl_generics ::eip_712_derive::FromJson for #name #ty_generics #json_where_clause {
+                fn from_json(
+                    value: &::eip_712_derive::__private::Value,
+                ) -> ::std::result::Result<Self, ::eip_712_derive::Error> {
+                    let __members = ::eip_712_derive::__private::struct_members(
+                        value,
+                        #type_name,
+                        &[#(#members),*],
+                    )?;
+                    ::std::result::Result::Ok(Self { #(#initializers),* })
+                }
+            }
+
+            impl #impl_generics #name #ty_generics #json_where_clause {
+                /// Reads the `message` of eth_signTypedData_v4 JSON
+                pub fn from_typed_data_message(
+                    message: &::eip_712_derive::__private::Value,
+                ) -> ::std::result::Result<Self, ::eip_712_derive::Error> {
+                    <Self as ::eip_712_derive::FromJson>::from_json(message)
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::eip_712_derive::StructType for #name #ty_generics #where_clause {
+            const TYPE_NAME: &'static str = #type_name;
+            fn visit_members<__V: ::eip_712_derive::MemberVisitor>(&self, visitor: &mut __V) {
+                #(#visits)*
+            }
+            fn visit_member_types<__V: ::eip_712_derive::TypeVisitor>(
+                visitor: &mut __V,
+            ) -> ::std::result::Result<(), ::eip_712_derive::Error> {
+                #(#type_visits)*
+                ::std::result::Result::Ok(())
+            }
+        }
+
+        #from_json
+    })
+}
+
+/// Implements `MemberType` for a fieldless enum as a `uint8` of its
+/// discriminant, which is how Solidity ABI-encodes enums. The
+/// `#[eip712(repr = "uint8")]` attribute is required, to make the encoding
+/// explicit. Discriminants must fit in a uint8.
+///
+/// With the `json` feature of eip-712-derive, the enum also implements
+/// `FromJson`, reading the discriminant.
+#[proc_macro_derive(MemberType, attributes(eip712))]
+pub fn derive_member_type(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    enum_member_type(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn enum_member_type(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let name = &input.ident;
+    let variants =
+        match &input.data {
+            Data::Enum(data) => &data.variants,
+            _ => return Err(Error::new_spanned(
+                name,
+                "MemberType can only be derived for fieldless enums. Derive StructType for structs",
+            )),
+        };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "MemberType cannot be derived for generic enums",
+        ));
+    }
+    let mut repr = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("eip712"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("repr") {
+                let value: LitStr = meta.value()?.parse()?;
+                if value.value() != "uint8" {
+                    return Err(Error::new_spanned(
+                        value,
+                        "the only supported repr is \"uint8\"",
+                    ));
+                }
+                repr = Some(value);
+                Ok(())
+            } else {
+                Err(meta.error("unknown eip712 enum attribute"))
+            }
+        })?;
+    }
+    if repr.is_none() {
+        return Err(Error::new_spanned(
+            name,
+            "add #[eip712(repr = \"uint8\")] to encode the enum as its discriminant",
+        ));
+    }
+
+    let mut idents = Vec::new();
+    for variant in variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                variant,
+                "only enums without fields can be encoded as uint8",
+            ));
+        }
+        idents.push(&variant.ident);
+    }
+
+    let name_str = name.to_string();
+    let from_json = if cfg!(feature = "json") {
+        quote! {
+            impl ::eip_712_derive::FromJson for #name {
+                fn from_json(
+                    value: &::eip_712_derive::__private::Value,
+                ) -> ::std::result::Result<Self, ::eip_712_derive::Error> {
+                    let discriminant =
+                        <::eip_712_derive::Uint8 as ::eip_712_derive::FromJson>::from_json(value)?;
+                    let discriminant = discriminant.value().0[31];
+                    #(
+                        if discriminant == #name::#idents as u8 {
+                            return ::std::result::Result::Ok(#name::#idents);
+                        }
+                    )*
+                    ::std::result::Result::Err(::eip_712_derive::Error::InvalidTypedData(
+                        ::std::format!("{} has no variant {}", #name_str, discriminant),
+                    ))
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+    Ok(quote! {
+        const _: () = {
+            #(
+                ::std::assert!(
+                    (#name::#idents as i128) >= 0 && (#name::#idents as i128) <= 255,
+                    "discriminants must fit in a uint8",
+                );
+            )*
+        };
+
+        impl ::eip_712_derive::MemberType for #name {
+            fn type_name() -> ::std::borrow::Cow<'static, str> {
+                ::std::borrow::Cow::Borrowed("uint8")
+            }
+            fn encode_data(&self) -> ::eip_712_derive::Bytes32 {
+                let mut encoded = [0u8; 32];
+                encoded[31] = match self {
+                    #(#name::#idents => #name::#idents as u8,)*
+                };
+                encoded
+            }
+            fn add_members(&self, _builder: &mut ::eip_712_derive::__private::TypeHashBuilder) {}
+            fn summarize(
+                &self,
+                _hints: &::eip_712_derive::summary::Hints,
+            ) -> ::eip_712_derive::summary::SummaryValue {
+                ::eip_712_derive::summary::SummaryValue::Uint(
+                    ::std::string::ToString::to_string(&<Self as ::eip_712_derive::MemberType>::encode_data(self)[31]),
+                )
+            }
+        }
+
+        impl ::eip_712_derive::AtomicType for #name {}
+
+        #from_json
+    })
+}
+
+#[derive(Default)]
+struct ContainerAttributes {
+    camel_case: bool,
+    type_name: Option<syn::Expr>,
+}
+
+fn container_attributes(input: &DeriveInput) -> Result<ContainerAttributes, Error> {
+    let mut attributes = ContainerAttributes::default();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("eip712"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") {
+                let rule: LitStr = meta.value()?.parse()?;
+                if rule.value() != "camelCase" {
+                    return Err(Error::new_spanned(
+                        rule,
+                        "the only supported rename_all rule is \"camelCase\"",
+                    ));
+                }
+                attributes.camel_case = true;
+                Ok(())
+            } else if meta.path.is_ident("type_name") {
+                attributes.type_name = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown eip712 container attribute"))
+            }
+        })?;
+    }
+    Ok(attributes)
+}
+
+/// verifying_contract -> verifyingContract
+fn camel_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = !result.is_empty();
+        } else if upper {
+            result.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+#[derive(Default)]
+struct FieldAttributes {
+    rename: Option<String>,
+    skip: bool,
+}
+
+fn field_attributes(field: &Field) -> Result<FieldAttributes, Error> {
+    let mut attributes = FieldAttributes::default();
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("eip712"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                let name: LitStr = meta.value()?.parse()?;
+                attributes.rename = Some(name.value());
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                attributes.skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown eip712 field attribute"))
+            }
+        })?;
+    }
+    Ok(attributes)
+}
@@ -0,0 +1,95 @@
+//! Interop with alloy-primitives. Requires the `alloy` feature.
+//!
+//! The alloy types can be used directly as members, with the same encoding as
+//! their counterparts in this crate, and convert to and from them. The one
+//! exception is U256 to alloy's U256, which would be ambiguous with ruint's own
+//! conversions. Use `alloy_primitives::U256::from_be_bytes(value.0)` instead.
+
+use crate::prelude::*;
+use crate::summary::{Hints, SummaryValue};
+use crate::{Address, DomainSeparator, U256};
+use alloy_primitives as alloy;
+use std::borrow::Cow;
+
+impl MemberType for alloy::Address {
+    fn type_name() -> Cow<'static, str> {
+        Cow::Borrowed("address")
+    }
+    fn encode_data(&self) -> Bytes32 {
+        Address::from(*self).encode_data()
+    }
+    #[inline(always)]
+    fn add_members(&self, _builder: &mut TypeHashBuilder) {}
+    fn summarize(&self, hints: &Hints) -> SummaryValue {
+        Address::from(*self).summarize(hints)
+    }
+}
+
+impl AtomicType for alloy::Address {}
+
+impl MemberType for alloy::U256 {
+    fn type_name() -> Cow<'static, str> {
+        Cow::Borrowed("uint256")
+    }
+    fn encode_data(&self) -> Bytes32 {
+        self.to_be_bytes()
+    }
+    #[inline(always)]
+    fn add_members(&self, _builder: &mut TypeHashBuilder) {}
+    fn summarize(&self, _hints: &Hints) -> SummaryValue {
+        SummaryValue::Uint(self.to_string())
+    }
+}
+
+impl AtomicType for alloy::U256 {}
+
+/// bytes1 to bytes32. Other sizes fail to compile.
+impl<const N: usize> MemberType for alloy::FixedBytes<N> {
+    fn type_name() -> Cow<'static, str> {
+        const { assert!(N >= 1 && N <= 32, "EIP-712 has bytes1 to bytes32") };
+        Cow::Owned(format!("bytes{}", N))
+    }
+    fn encode_data(&self) -> Bytes32 {
+        const { assert!(N >= 1 && N <= 32, "EIP-712 has bytes1 to bytes32") };
+        let mut padded = [0u8; 32];
+        padded[..N].copy_from_slice(&self.0);
+        padded
+    }
+    #[inline(always)]
+    fn add_members(&self, _builder: &mut TypeHashBuilder) {}
+    fn summarize(&self, _hints: &Hints) -> SummaryValue {
+        SummaryValue::Bytes(self.to_string())
+    }
+}
+
+impl<const N: usize> AtomicType for alloy::FixedBytes<N> {}
+
+impl From<alloy::Address> for Address {
+    fn from(address: alloy::Address) -> Self {
+        Address(address.into_array())
+    }
+}
+
+impl From<Address> for alloy::Address {
+    fn from(address: Address) -> Self {
+        alloy::Address::new(address.0)
+    }
+}
+
+impl From<alloy::U256> for U256 {
+    fn from(value: alloy::U256) -> Self {
+        U256(value.to_be_bytes())
+    }
+}
+
+impl From<alloy::B256> for DomainSeparator {
+    fn from(hash: alloy::B256) -> Self {
+        DomainSeparator::from_bytes(&hash.0)
+    }
+}
+
+impl From<DomainSeparator> for alloy::B256 {
+    fn from(domain_separator: DomainSeparator) -> Self {
+        alloy::B256::new(*domain_separator.as_bytes())
+    }
+}
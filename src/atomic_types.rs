@@ -1,70 +1,535 @@
 use crate::prelude::*;
+use crate::summary::{Hints, SummaryValue};
+use crate::Error;
+use std::borrow::Cow;
+use std::fmt;
 
 macro_rules! impl_atomic {
-    ($T: ident, $name:expr, $this:ident $encode_data:tt) => {
+    ($T: ident, $name:expr, $this:ident $encode_data:tt $summarize:tt) => {
         impl MemberType for $T {
-            const TYPE_NAME: &'static str = $name;
+            fn type_name() -> Cow<'static, str> {
+                Cow::Borrowed($name)
+            }
             fn encode_data(&$this) -> Bytes32 $encode_data
             #[inline(always)]
             fn add_members(&self, _builder: &mut TypeHashBuilder) {}
+            fn summarize(&$this, _hints: &Hints) -> SummaryValue $summarize
         }
         impl AtomicType for $T {}
     };
 }
 
-#[derive(Default, Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct Address(pub Bytes20);
-#[derive(Default, Copy, Clone, PartialEq, Eq, Hash, Debug)]
+/// Big-endian, so the derived ordering is numeric.
+#[derive(Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct U256(pub Bytes32);
 
-impl_atomic!(Address, "address", self { self.0.encode_data() });
-impl_atomic!(U256, "uint256", self { self.0.encode_data() });
+impl_atomic!(Address, "address", self {
+    // Encoded like uint160, so unlike bytes20 padded at the start
+    let mut padded = [0u8; 32];
+    padded[12..].copy_from_slice(&self.0);
+    padded
+} {
+    SummaryValue::Address(self.to_checksum_string())
+});
+impl_atomic!(U256, "uint256", self { self.0.encode_data() } {
+    SummaryValue::Uint(self.to_decimal_string())
+});
 
-macro_rules! impl_bytes {
-    ($($T:ident: $size:expr => $name:expr,)+) => {
+impl_atomic!(bool, "bool", self {
+    let mut padded = [0u8; 32];
+    padded[31] = *self as u8;
+    padded
+} {
+    SummaryValue::Bool(*self)
+});
+
+// u8 is left out, because with it [u8; N] and Vec<u8> would be arrays rather
+// than bytesN and bytes. Use Uint8 instead.
+macro_rules! impl_primitive_int {
+    (@fill Uint, $value:expr) => { 0 };
+    (@fill Int, $value:expr) => { if $value < 0 { 0xff } else { 0 } };
+    ($($T:ident => $name:expr, $summary:ident;)+) => {
         $(
-            pub type $T = [u8; $size];
             impl_atomic!($T, $name, self {
-                let mut padded = [0u8; 32];
-                let section = &mut padded[32 - $size..];
-                section.copy_from_slice(&self[..]);
+                // Zero padded, or sign extended for the signed types
+                let fill = impl_primitive_int!(@fill $summary, *self);
+                let mut padded = [fill; 32];
+                let bytes = self.to_be_bytes();
+                padded[32 - bytes.len()..].copy_from_slice(&bytes);
                 padded
+            } {
+                SummaryValue::$summary(self.to_string())
             });
         )+
     }
 }
 
-impl_bytes! {
-    Bytes1: 1 => "bytes1",
-    Bytes2: 2 => "bytes2",
-    Bytes3: 3 => "bytes3",
-    Bytes4: 4 => "bytes4",
-    Bytes5: 5 => "bytes5",
-    Bytes6: 6 => "bytes6",
-    Bytes7: 7 => "bytes7",
-    Bytes8: 8 => "bytes8",
-    Bytes9: 9 => "bytes9",
-    Bytes10: 10 => "bytes10",
-    Bytes11: 11 => "bytes11",
-    Bytes12: 12 => "bytes12",
-    Bytes13: 13 => "bytes13",
-    Bytes14: 14 => "bytes14",
-    Bytes15: 15 => "bytes15",
-    Bytes16: 16 => "bytes16",
-    Bytes17: 17 => "bytes17",
-    Bytes18: 18 => "bytes18",
-    Bytes19: 19 => "bytes19",
-    Bytes20: 20 => "bytes20",
-    Bytes21: 21 => "bytes21",
-    Bytes22: 22 => "bytes22",
-    Bytes23: 23 => "bytes23",
-    Bytes24: 24 => "bytes24",
-    Bytes25: 25 => "bytes25",
-    Bytes26: 26 => "bytes26",
-    Bytes27: 27 => "bytes27",
-    Bytes28: 28 => "bytes28",
-    Bytes29: 29 => "bytes29",
-    Bytes30: 30 => "bytes30",
-    Bytes31: 31 => "bytes31",
-    Bytes32: 32 => "bytes32",
+impl_primitive_int! {
+    u16 => "uint16", Uint;
+    u32 => "uint32", Uint;
+    u64 => "uint64", Uint;
+    u128 => "uint128", Uint;
+    i8 => "int8", Int;
+    i16 => "int16", Int;
+    i32 => "int32", Int;
+    i64 => "int64", Int;
+    i128 => "int128", Int;
+}
+
+impl Address {
+    /// (EIP-55) The mixed-case checksum encoding, as shown by wallets and
+    /// explorers. This is also the Display form.
+    pub fn to_checksum_string(self) -> String {
+        let lower = encode_hex(&self.0);
+        let hash = keccak(lower.as_bytes());
+
+        let mut result = String::with_capacity(42);
+        result.push_str("0x");
+        for (i, c) in lower.chars().enumerate() {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0xf;
+            if nibble >= 8 {
+                result.push(c.to_ascii_uppercase());
+            } else {
+                result.push(c);
+            }
+        }
+        result
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.to_checksum_string())
+    }
 }
+
+impl U256 {
+    /// Parses decimal digits, without sign or separators. None on overflow.
+    pub fn from_dec_str(s: &str) -> Option<Self> {
+        parse_digits(s, 10).map(Self)
+    }
+
+    /// Parses hex digits, with or without a 0x prefix. None on overflow.
+    pub fn from_hex_str(s: &str) -> Option<Self> {
+        parse_digits(s.strip_prefix("0x").unwrap_or(s), 16).map(Self)
+    }
+
+    pub(crate) fn to_decimal_string(self) -> String {
+        // Repeated long division by 10 over the big-endian bytes
+        let mut value = self.0;
+        let mut digits = Vec::new();
+        while value != [0u8; 32] {
+            let mut remainder = 0u32;
+            for byte in value.iter_mut() {
+                let current = (remainder << 8) | *byte as u32;
+                *byte = (current / 10) as u8;
+                remainder = current % 10;
+            }
+            digits.push(b'0' + remainder as u8);
+        }
+        if digits.is_empty() {
+            return "0".to_owned();
+        }
+        digits.reverse();
+        String::from_utf8(digits).unwrap()
+    }
+}
+
+impl U256 {
+    pub const ZERO: Self = Self([0; 32]);
+    pub const ONE: Self = {
+        let mut bytes = [0; 32];
+        bytes[31] = 1;
+        Self(bytes)
+    };
+    pub const MAX: Self = Self([0xff; 32]);
+
+    /// Least significant first
+    fn to_limbs(self) -> [u64; 4] {
+        let mut limbs = [0u64; 4];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&self.0[24 - i * 8..32 - i * 8]);
+            *limb = u64::from_be_bytes(bytes);
+        }
+        limbs
+    }
+
+    fn from_limbs(limbs: [u64; 4]) -> Self {
+        let mut result = [0u8; 32];
+        for (i, limb) in limbs.iter().enumerate() {
+            result[24 - i * 8..32 - i * 8].copy_from_slice(&limb.to_be_bytes());
+        }
+        Self(result)
+    }
+
+    /// None on overflow
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let (a, b) = (self.to_limbs(), other.to_limbs());
+        let mut result = [0u64; 4];
+        let mut carry = 0u128;
+        for i in 0..4 {
+            let sum = a[i] as u128 + b[i] as u128 + carry;
+            result[i] = sum as u64;
+            carry = sum >> 64;
+        }
+        if carry != 0 {
+            return None;
+        }
+        Some(Self::from_limbs(result))
+    }
+
+    /// None if `other` is greater than `self`
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        if other > self {
+            return None;
+        }
+        let (a, b) = (self.to_limbs(), other.to_limbs());
+        let mut result = [0u64; 4];
+        let mut borrow = false;
+        for i in 0..4 {
+            let (difference, borrow_a) = a[i].overflowing_sub(b[i]);
+            let (difference, borrow_b) = difference.overflowing_sub(borrow as u64);
+            result[i] = difference;
+            borrow = borrow_a || borrow_b;
+        }
+        Some(Self::from_limbs(result))
+    }
+
+    /// None on overflow
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        let (a, b) = (self.to_limbs(), other.to_limbs());
+        let mut result = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                let product = a[i] as u128 * b[j] as u128 + result[i + j] as u128 + carry;
+                result[i + j] = product as u64;
+                carry = product >> 64;
+            }
+            result[i + 4] = carry as u64;
+        }
+        if result[4..].iter().any(|&limb| limb != 0) {
+            return None;
+        }
+        Some(Self::from_limbs([
+            result[0], result[1], result[2], result[3],
+        ]))
+    }
+}
+
+impl fmt::Display for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "", &self.to_decimal_string())
+    }
+}
+
+impl fmt::LowerHex for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex = encode_hex(&self.0);
+        let digits = hex.trim_start_matches('0');
+        f.pad_integral(true, "0x", if digits.is_empty() { "0" } else { digits })
+    }
+}
+
+impl fmt::UpperHex for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex = format!("{:x}", self).to_ascii_uppercase();
+        f.pad_integral(true, "0x", &hex)
+    }
+}
+
+impl From<u64> for U256 {
+    fn from(value: u64) -> Self {
+        Self::from(value as u128)
+    }
+}
+
+impl From<u128> for U256 {
+    fn from(value: u128) -> Self {
+        let mut result = [0u8; 32];
+        result[16..].copy_from_slice(&value.to_be_bytes());
+        Self(result)
+    }
+}
+
+/// Parses big-endian digits in `radix`, failing on overflow of 256 bits.
+fn parse_digits(s: &str, radix: u32) -> Option<Bytes32> {
+    if s.is_empty() {
+        return None;
+    }
+    let mut result = [0u8; 32];
+    for c in s.chars() {
+        let mut carry = c.to_digit(radix)?;
+        for byte in result.iter_mut().rev() {
+            let current = *byte as u32 * radix + carry;
+            *byte = current as u8;
+            carry = current >> 8;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    Some(result)
+}
+
+/// Hex conversion for [`FixedBytes`], which as plain arrays cannot have
+/// inherent methods.
+pub trait BytesHex: Sized {
+    /// Parses exactly two hex digits per byte, with or without a 0x prefix
+    fn from_hex(s: &str) -> Result<Self, Error>;
+
+    /// 0x-prefixed lowercase hex
+    fn to_hex(&self) -> String;
+}
+
+/// Lowercase hex, without a prefix
+pub(crate) fn encode_hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push(DIGITS[(byte >> 4) as usize] as char);
+        hex.push(DIGITS[(byte & 0xf) as usize] as char);
+    }
+    hex
+}
+
+pub(crate) fn decode_hex_into(s: &str, out: &mut [u8]) -> Result<(), Error> {
+    let invalid = || Error::InvalidHex(s.to_owned());
+    let digits = s.strip_prefix("0x").unwrap_or(s).as_bytes();
+    if digits.len() != out.len() * 2 {
+        return Err(invalid());
+    }
+    for (byte, pair) in out.iter_mut().zip(digits.chunks(2)) {
+        let digit = |c: u8| (c as char).to_digit(16).ok_or_else(invalid);
+        *byte = (digit(pair[0])? << 4 | digit(pair[1])?) as u8;
+    }
+    Ok(())
+}
+
+/// bytesN, as a plain array. EIP-712 has bytes1 to bytes32, and other sizes
+/// fail to compile when used as members. Bytes1 to Bytes32 name the sizes.
+///
+/// ```compile_fail
+/// use eip_712_derive::MemberType;
+/// let _ = <[u8; 33]>::type_name();
+/// ```
+pub type FixedBytes<const N: usize> = [u8; N];
+
+pub type Bytes1 = FixedBytes<1>;
+pub type Bytes2 = FixedBytes<2>;
+pub type Bytes3 = FixedBytes<3>;
+pub type Bytes4 = FixedBytes<4>;
+pub type Bytes5 = FixedBytes<5>;
+pub type Bytes6 = FixedBytes<6>;
+pub type Bytes7 = FixedBytes<7>;
+pub type Bytes8 = FixedBytes<8>;
+pub type Bytes9 = FixedBytes<9>;
+pub type Bytes10 = FixedBytes<10>;
+pub type Bytes11 = FixedBytes<11>;
+pub type Bytes12 = FixedBytes<12>;
+pub type Bytes13 = FixedBytes<13>;
+pub type Bytes14 = FixedBytes<14>;
+pub type Bytes15 = FixedBytes<15>;
+pub type Bytes16 = FixedBytes<16>;
+pub type Bytes17 = FixedBytes<17>;
+pub type Bytes18 = FixedBytes<18>;
+pub type Bytes19 = FixedBytes<19>;
+pub type Bytes20 = FixedBytes<20>;
+pub type Bytes21 = FixedBytes<21>;
+pub type Bytes22 = FixedBytes<22>;
+pub type Bytes23 = FixedBytes<23>;
+pub type Bytes24 = FixedBytes<24>;
+pub type Bytes25 = FixedBytes<25>;
+pub type Bytes26 = FixedBytes<26>;
+pub type Bytes27 = FixedBytes<27>;
+pub type Bytes28 = FixedBytes<28>;
+pub type Bytes29 = FixedBytes<29>;
+pub type Bytes30 = FixedBytes<30>;
+pub type Bytes31 = FixedBytes<31>;
+pub type Bytes32 = FixedBytes<32>;
+
+impl<const N: usize> MemberType for FixedBytes<N> {
+    fn type_name() -> Cow<'static, str> {
+        const { assert!(N >= 1 && N <= 32, "EIP-712 has bytes1 to bytes32") };
+        Cow::Owned(format!("bytes{}", N))
+    }
+    fn encode_data(&self) -> Bytes32 {
+        const { assert!(N >= 1 && N <= 32, "EIP-712 has bytes1 to bytes32") };
+        // (SPEC) bytes1 to bytes31 are arrays with a beginning (index 0)
+        // and an end (index length - 1), they are zero-padded at the end
+        // to bytes32
+        let mut padded = [0u8; 32];
+        padded[..N].copy_from_slice(&self[..]);
+        padded
+    }
+    #[inline(always)]
+    fn add_members(&self, _builder: &mut TypeHashBuilder) {}
+    fn summarize(&self, _hints: &Hints) -> SummaryValue {
+        SummaryValue::Bytes(self.to_hex())
+    }
+}
+
+impl<const N: usize> AtomicType for FixedBytes<N> {}
+
+impl<const N: usize> BytesHex for FixedBytes<N> {
+    fn from_hex(s: &str) -> Result<Self, Error> {
+        let mut result = [0u8; N];
+        decode_hex_into(s, &mut result)?;
+        Ok(result)
+    }
+
+    fn to_hex(&self) -> String {
+        format!("0x{}", encode_hex(self))
+    }
+}
+
+macro_rules! impl_uint {
+    ($($T:ident: $bits:expr => $name:expr,)+) => {
+        $(
+            #[doc = concat!("A ", $name, ", stored big-endian like U256.")]
+            ///
+            /// Contracts which declare a narrower integer have a different type
+            /// hash than with uint256, so these must be used to match them.
+            #[derive(Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+            pub struct $T(Bytes32);
+
+            impl $T {
+                pub const BITS: u32 = $bits;
+
+                /// None if `value` does not fit in the type
+                pub fn new(value: U256) -> Option<Self> {
+                    if value.0[..32 - $bits / 8].iter().any(|&b| b != 0) {
+                        return None;
+                    }
+                    Some(Self(value.0))
+                }
+
+                pub fn value(self) -> U256 {
+                    U256(self.0)
+                }
+            }
+
+            impl From<$T> for U256 {
+                fn from(value: $T) -> Self {
+                    value.value()
+                }
+            }
+
+            impl_atomic!($T, $name, self { self.0 } {
+                SummaryValue::Uint(self.value().to_decimal_string())
+            });
+        )+
+    }
+}
+
+impl_uint! {
+    Uint8: 8 => "uint8",
+    Uint16: 16 => "uint16",
+    Uint24: 24 => "uint24",
+    Uint32: 32 => "uint32",
+    Uint40: 40 => "uint40",
+    Uint48: 48 => "uint48",
+    Uint56: 56 => "uint56",
+    Uint64: 64 => "uint64",
+    Uint72: 72 => "uint72",
+    Uint80: 80 => "uint80",
+    Uint88: 88 => "uint88",
+    Uint96: 96 => "uint96",
+    Uint104: 104 => "uint104",
+    Uint112: 112 => "uint112",
+    Uint120: 120 => "uint120",
+    Uint128: 128 => "uint128",
+    Uint136: 136 => "uint136",
+    Uint144: 144 => "uint144",
+    Uint152: 152 => "uint152",
+    Uint160: 160 => "uint160",
+    Uint168: 168 => "uint168",
+    Uint176: 176 => "uint176",
+    Uint184: 184 => "uint184",
+    Uint192: 192 => "uint192",
+    Uint200: 200 => "uint200",
+    Uint208: 208 => "uint208",
+    Uint216: 216 => "uint216",
+    Uint224: 224 => "uint224",
+    Uint232: 232 => "uint232",
+    Uint240: 240 => "uint240",
+    Uint248: 248 => "uint248",
+}
+
+// (SPEC) The array values are encoded as the keccak256 hash of the
+// concatenated encodeData of their contents
+fn encode_elements<T: MemberType>(elements: &[T]) -> Bytes32 {
+    let mut hasher = Keccak::new();
+    for element in elements {
+        hasher.update(&element.encode_data());
+    }
+    hasher.finalize()
+}
+
+fn summarize_elements<T: MemberType>(elements: &[T], hints: &Hints) -> SummaryValue {
+    SummaryValue::Array(elements.iter().map(|e| e.summarize(hints)).collect())
+}
+
+fn dynamic_elements<T: MemberType>(elements: &[T]) -> Option<DynamicValue> {
+    elements
+        .iter()
+        .map(MemberType::dynamic_value)
+        .collect::<Option<_>>()
+        .map(DynamicValue::Array)
+}
+
+// (SPEC) Arrays are either fixed size or dynamic and denoted by Type[n] or
+// Type[] respectively.
+//
+// The struct types referenced by the element type are added without a value,
+// so struct elements must implement StructType::visit_member_types.
+impl<T: MemberType, const N: usize> MemberType for [T; N] {
+    fn type_name() -> Cow<'static, str> {
+        Cow::Owned(format!("{}[{}]", T::type_name(), N))
+    }
+    fn encode_data(&self) -> Bytes32 {
+        encode_elements(self)
+    }
+    fn add_members(&self, builder: &mut TypeHashBuilder) {
+        // From the element type rather than the elements, which may be none.
+        // The type hash is memoized by type.
+        Self::add_member_types(builder);
+    }
+    fn add_member_types(builder: &mut TypeHashBuilder) {
+        builder.member_type_of::<T>();
+    }
+    fn summarize(&self, hints: &Hints) -> SummaryValue {
+        summarize_elements(self, hints)
+    }
+    fn dynamic_value(&self) -> Option<DynamicValue> {
+        dynamic_elements(self)
+    }
+}
+
+impl<T: MemberType, const N: usize> ReferenceType for [T; N] {}
+
+impl<T: MemberType> MemberType for Vec<T> {
+    fn type_name() -> Cow<'static, str> {
+        Cow::Owned(format!("{}[]", T::type_name()))
+    }
+    fn encode_data(&self) -> Bytes32 {
+        encode_elements(self)
+    }
+    fn add_members(&self, builder: &mut TypeHashBuilder) {
+        Self::add_member_types(builder);
+    }
+    fn add_member_types(builder: &mut TypeHashBuilder) {
+        builder.member_type_of::<T>();
+    }
+    fn summarize(&self, hints: &Hints) -> SummaryValue {
+        summarize_elements(self, hints)
+    }
+    fn dynamic_value(&self) -> Option<DynamicValue> {
+        dynamic_elements(self)
+    }
+}
+
+impl<T: MemberType> ReferenceType for Vec<T> {}
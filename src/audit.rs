@@ -0,0 +1,170 @@
+//! A signer wrapper which logs every request before signing it.
+//!
+//! Records never contain key material. Each record includes the hash of the
+//! one before it, so a log which has been edited, truncated in the middle or
+//! reordered fails [`verify_chain`].
+
+use crate::prelude::*;
+use crate::*;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AuditRecord {
+    /// Starts at 0 for each AuditSigner
+    pub sequence: u64,
+    pub timestamp: SystemTime,
+    /// Identifies the caller, as given to [`AuditSigner::tagged`]
+    pub tag: String,
+    /// The fields of the signed [`SigningRequest`], which are all derived
+    /// from the typed message. The digest is checked by [`verify_chain`] to
+    /// be that of the domain separator and struct hash.
+    pub type_name: &'static str,
+    pub domain_separator: DomainSeparator,
+    pub struct_hash: Bytes32,
+    pub digest: Bytes32,
+    /// The hash of the previous record, or zero for the first
+    pub previous: Bytes32,
+    /// The hash of this record
+    pub hash: Bytes32,
+}
+
+impl AuditRecord {
+    /// keccak256 of every other field
+    pub fn compute_hash(&self) -> Bytes32 {
+        let nanos = self
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos());
+        let mut hasher = Keccak::new();
+        hasher.update(&self.previous);
+        hasher.update(&self.sequence.to_be_bytes());
+        hasher.update(&nanos.to_be_bytes());
+        // Length prefixes keep the variable length fields unambiguous
+        for text in &[&self.tag[..], self.type_name] {
+            hasher.update(&(text.len() as u64).to_be_bytes());
+            hasher.update(text.as_bytes());
+        }
+        hasher.update(self.domain_separator.as_bytes());
+        hasher.update(&self.struct_hash);
+        hasher.update(&self.digest);
+        hasher.finalize()
+    }
+}
+
+/// Checks that each record's hash is correct and links to the one before, and
+/// that each digest is the one signed for its domain and struct hash.
+pub fn verify_chain(records: &[AuditRecord]) -> bool {
+    let mut previous = None;
+    for record in records {
+        if let Some((sequence, hash)) = previous {
+            if record.sequence != sequence + 1 || record.previous != hash {
+                return false;
+            }
+        }
+        if record.digest != sign_hash_of(&record.domain_separator, &record.struct_hash) {
+            return false;
+        }
+        if record.compute_hash() != record.hash {
+            return false;
+        }
+        previous = Some((record.sequence, record.hash));
+    }
+    true
+}
+
+/// Where records are written, eg: a file or a remote log service.
+pub trait AuditSink: Send + Sync {
+    /// Signing is refused if this fails.
+    fn write(&self, record: &AuditRecord) -> Result<(), String>;
+}
+
+/// Keeps records in memory
+impl AuditSink for Mutex<Vec<AuditRecord>> {
+    fn write(&self, record: &AuditRecord) -> Result<(), String> {
+        self.lock().unwrap().push(record.clone());
+        Ok(())
+    }
+}
+
+impl<K: AuditSink + ?Sized> AuditSink for &K {
+    fn write(&self, record: &AuditRecord) -> Result<(), String> {
+        (**self).write(record)
+    }
+}
+
+/// A [`Signer`] which writes an [`AuditRecord`] to a sink before passing each
+/// request to the inner signer.
+pub struct AuditSigner<S, K> {
+    inner: S,
+    sink: K,
+    // Held while writing, so that records reach the sink in sequence
+    last: Mutex<Option<(u64, Bytes32)>>,
+}
+
+impl<S: Signer, K: AuditSink> AuditSigner<S, K> {
+    pub fn new(inner: S, sink: K) -> Self {
+        Self {
+            inner,
+            sink,
+            last: Mutex::new(None),
+        }
+    }
+
+    /// A signer whose records carry `tag`. Requests made directly through
+    /// this signer have an empty tag.
+    pub fn tagged<'a>(&'a self, tag: &'a str) -> Tagged<'a, S, K> {
+        Tagged { signer: self, tag }
+    }
+
+    fn sign_tagged(&self, tag: &str, request: &SigningRequest) -> Result<Signature, Error> {
+        {
+            let mut last = self.last.lock().unwrap();
+            let (sequence, previous) = match *last {
+                Some((sequence, hash)) => (sequence + 1, hash),
+                None => (0, [0u8; 32]),
+            };
+            let mut record = AuditRecord {
+                sequence,
+                timestamp: SystemTime::now(),
+                tag: tag.to_owned(),
+                type_name: request.type_name(),
+                domain_separator: *request.domain_separator(),
+                struct_hash: *request.struct_hash(),
+                digest: *request.digest(),
+                previous,
+                hash: [0u8; 32],
+            };
+            record.hash = record.compute_hash();
+            self.sink.write(&record).map_err(Error::AuditLog)?;
+            *last = Some((sequence, record.hash));
+        }
+        self.inner.sign(request)
+    }
+}
+
+impl<S: Signer, K: AuditSink> Signer for AuditSigner<S, K> {
+    fn address(&self) -> Address {
+        self.inner.address()
+    }
+
+    fn sign(&self, request: &SigningRequest) -> Result<Signature, Error> {
+        self.sign_tagged("", request)
+    }
+}
+
+/// See [`AuditSigner::tagged`]
+pub struct Tagged<'a, S, K> {
+    signer: &'a AuditSigner<S, K>,
+    tag: &'a str,
+}
+
+impl<S: Signer, K: AuditSink> Signer for Tagged<'_, S, K> {
+    fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    fn sign(&self, request: &SigningRequest) -> Result<Signature, Error> {
+        self.signer.sign_tagged(self.tag, request)
+    }
+}
@@ -0,0 +1,234 @@
+//! Offline hashing, signing, verification and signer recovery of
+//! eth_signTypedData_v4 JSON, and domain separators for deployment checks.
+//! Requires the `cli` feature.
+
+use clap::{Args, Parser, Subcommand};
+use eip_712_derive::{
+    hash_typed_json, verify_typed_json, Address, BytesHex, DomainSeparator, Eip712Domain, Error,
+    SecretKey, Signature, U256,
+};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use zeroize::Zeroize;
+
+#[derive(Parser)]
+#[command(
+    name = "eip712",
+    version,
+    about = "Hash, sign and verify EIP-712 typed data"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the digest which is signed
+    Hash {
+        #[command(flatten)]
+        payload: Payload,
+        /// Also print the type hash, struct hash and domain separator
+        #[arg(long)]
+        all: bool,
+    },
+    /// Sign, printing r ‖ s ‖ v as hex
+    Sign {
+        #[command(flatten)]
+        payload: Payload,
+        /// A file holding the private key, as hex or as a SEC1 or PKCS#8 PEM
+        #[arg(long)]
+        key_file: PathBuf,
+    },
+    /// Check that a signature was made by the expected signer
+    Verify {
+        #[command(flatten)]
+        payload: Payload,
+        /// r ‖ s ‖ v as hex, as returned by eth_signTypedData_v4
+        #[arg(long)]
+        signature: String,
+        /// The address expected to have signed
+        #[arg(long)]
+        signer: String,
+    },
+    /// Print the address which made a signature
+    Recover {
+        #[command(flatten)]
+        payload: Payload,
+        /// r ‖ s ‖ v as hex, as returned by eth_signTypedData_v4
+        #[arg(long)]
+        signature: String,
+        /// Also report whether the signer is this address, failing if not
+        #[arg(long, value_parser = parse_address)]
+        expect: Option<Address>,
+    },
+    /// Print the domain separator of a domain with the given fields, to
+    /// compare with a contract's DOMAIN_SEPARATOR()
+    Domain {
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        version: Option<String>,
+        /// Decimal, or hex with a 0x prefix
+        #[arg(long, value_parser = parse_u256)]
+        chain_id: Option<U256>,
+        #[arg(long, value_parser = parse_address)]
+        verifying_contract: Option<Address>,
+        /// 32 bytes as hex
+        #[arg(long, value_parser = parse_bytes32)]
+        salt: Option<[u8; 32]>,
+    },
+}
+
+#[derive(Args)]
+struct Payload {
+    /// The eth_signTypedData_v4 JSON, or - for stdin
+    #[arg(long, default_value = "-")]
+    payload: PathBuf,
+}
+
+impl Payload {
+    fn read(&self) -> Result<String, String> {
+        let mut json = String::new();
+        if self.payload == Path::new("-") {
+            std::io::stdin()
+                .read_to_string(&mut json)
+                .map_err(|e| format!("stdin: {}", e))?;
+        } else {
+            json = read_file(&self.payload)?;
+        }
+        Ok(json)
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn parse_u256(s: &str) -> Result<U256, String> {
+    match s.strip_prefix("0x") {
+        Some(_) => U256::from_hex_str(s),
+        None => U256::from_dec_str(s),
+    }
+    .ok_or_else(|| format!("not a uint256: {}", s))
+}
+
+fn parse_address(s: &str) -> Result<Address, String> {
+    BytesHex::from_hex(s)
+        .map(Address)
+        .map_err(|e: Error| e.to_string())
+}
+
+fn parse_bytes32(s: &str) -> Result<[u8; 32], String> {
+    BytesHex::from_hex(s).map_err(|e: Error| e.to_string())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn read_key(path: &Path) -> Result<SecretKey, String> {
+    let mut contents = read_file(path)?;
+    let key = if contents.contains("-----BEGIN") {
+        SecretKey::from_pem(&contents)
+    } else {
+        SecretKey::from_hex(contents.trim())
+    };
+    contents.zeroize();
+    key.map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn run(command: Command) -> Result<(), String> {
+    let error = |e: Error| e.to_string();
+    match command {
+        Command::Hash { payload, all } => {
+            let hashes = hash_typed_json(&payload.read()?).map_err(error)?;
+            if all {
+                println!("typeHash        {}", hex(&hashes.type_hash));
+                println!("structHash      {}", hex(&hashes.struct_hash));
+                println!(
+                    "domainSeparator {}",
+                    hex(hashes.domain_separator.as_bytes())
+                );
+                println!("digest          {}", hex(&hashes.digest));
+            } else {
+                println!("{}", hex(&hashes.digest));
+            }
+        }
+        Command::Sign { payload, key_file } => {
+            let key = read_key(&key_file)?;
+            let typed_data =
+                eip_712_derive::dynamic::TypedData::from_json(&payload.read()?).map_err(error)?;
+            let signature = typed_data.sign(&key).map_err(error)?;
+            println!("{}", hex(&signature.to_bytes()));
+        }
+        Command::Verify {
+            payload,
+            signature,
+            signer,
+        } => {
+            if !verify_typed_json(&payload.read()?, &signature, &signer).map_err(error)? {
+                return Err(format!("signature is not from {}", signer));
+            }
+            println!("OK");
+        }
+        Command::Recover {
+            payload,
+            signature,
+            expect,
+        } => {
+            let typed_data =
+                eip_712_derive::dynamic::TypedData::from_json(&payload.read()?).map_err(error)?;
+            let signature = ::hex::decode(signature.strip_prefix("0x").unwrap_or(&signature))
+                .map_err(|_| Error::MalformedSignature)
+                .and_then(|bytes| Signature::from_bytes(&bytes))
+                .map_err(error)?;
+            let signer = typed_data.recover(&signature).map_err(error)?;
+            println!("{}", signer);
+            match expect {
+                Some(expected) if expected == signer => println!("matches {}", expected),
+                Some(expected) => return Err(format!("does not match {}", expected)),
+                None => {}
+            }
+        }
+        Command::Domain {
+            name,
+            version,
+            chain_id,
+            verifying_contract,
+            salt,
+        } => {
+            // Only the fields given are part of the EIP712Domain type
+            let mut builder = Eip712Domain::builder();
+            if let Some(name) = name {
+                builder = builder.name(name);
+            }
+            if let Some(version) = version {
+                builder = builder.version(version);
+            }
+            if let Some(chain_id) = chain_id {
+                builder = builder.chain_id(chain_id);
+            }
+            if let Some(verifying_contract) = verifying_contract {
+                builder = builder.verifying_contract(verifying_contract);
+            }
+            if let Some(salt) = salt {
+                builder = builder.salt(salt);
+            }
+            let domain_separator = DomainSeparator::new(&builder.build());
+            println!("{}", hex(domain_separator.as_bytes()));
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse().command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! Public networks by name. Requires the `chains` feature.
+//!
+//! The constants are generated at build time from a snapshot of the
+//! ethereum-lists/chains registry vendored in the repository, so that domains
+//! can refer to a network without hand maintained chain ids. Domain builders
+//! take the `chain_id` directly, eg:
+//! `Eip712Domain::builder().chain_id(chains::ARBITRUM_ONE.chain_id)`.
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Chain {
+    pub name: &'static str,
+    pub chain_id: u64,
+    pub short_name: &'static str,
+}
+
+impl Chain {
+    pub fn by_chain_id(chain_id: u64) -> Option<&'static Chain> {
+        ALL.iter().find(|c| c.chain_id == chain_id)
+    }
+
+    pub fn by_short_name(short_name: &str) -> Option<&'static Chain> {
+        ALL.iter().find(|c| c.short_name == short_name)
+    }
+
+    /// By the name in the registry, eg: "Arbitrum One"
+    pub fn by_name(name: &str) -> Option<&'static Chain> {
+        ALL.iter().find(|c| c.name == name)
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/chains.rs"));
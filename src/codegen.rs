@@ -0,0 +1,865 @@
+//! Generates Rust message types, with their StructType impls, from other
+//! descriptions of the same structs so that the contract can remain the
+//! source of truth. Requires the `codegen` feature.
+//!
+//! Solidity source, contract ABI JSON and the `types` of eth_signTypedData
+//! JSON are supported as inputs. Inputs are
+//! parsed into [`StructDef`]s, which carry EIP-712 type names, and
+//! [`rust`] emits the code. The output refers to this crate by absolute path
+//! so that it can be `include!`d anywhere. [`Builder`] does all of this from a
+//! build script:
+//!
+//! ```no_run
+//! // build.rs
+//! eip_712_derive::codegen::Builder::new()
+//!     .solidity("contracts/Market.sol")
+//!     .abi("out/Market.sol/Market.json")
+//!     .write_to_out_dir("eip712.rs")
+//!     .unwrap();
+//! ```
+//!
+//! ```ignore
+//! // src/lib.rs
+//! include!(concat!(env!("OUT_DIR"), "/eip712.rs"));
+//! ```
+//!
+//! Going the other way, [`struct_defs`] describes a Rust message type so that
+//! [`typescript`] can emit matching definitions for a frontend, and
+//! [`solidity`] the hashing for a contract.
+
+use crate::dynamic::is_atomic_or_dynamic;
+use crate::type_hash::is_identifier;
+use crate::{Error, StructType, TypeDescriptor};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// A struct, as it appears in EIP-712 encodeType.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct StructDef {
+    pub name: String,
+    pub members: Vec<MemberDef>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MemberDef {
+    pub name: String,
+    /// The EIP-712 type, eg: "uint256" or "Person"
+    pub r#type: String,
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::Codegen(message.into())
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Format {
+    Solidity,
+    Abi,
+    TypedData,
+}
+
+/// Generates Rust from struct definitions in files, for build scripts.
+///
+/// Structs defined identically in several inputs are generated once, and
+/// conflicting definitions are an error.
+#[derive(Clone, Debug, Default)]
+pub struct Builder {
+    inputs: Vec<(PathBuf, Format)>,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Solidity source, as for [`parse_solidity`]
+    pub fn solidity(mut self, path: impl AsRef<Path>) -> Self {
+        self.inputs
+            .push((path.as_ref().to_owned(), Format::Solidity));
+        self
+    }
+
+    /// A contract ABI or compiler artifact, as for [`parse_abi`]
+    pub fn abi(mut self, path: impl AsRef<Path>) -> Self {
+        self.inputs.push((path.as_ref().to_owned(), Format::Abi));
+        self
+    }
+
+    /// eth_signTypedData JSON, as for [`parse_typed_data`]
+    pub fn typed_data(mut self, path: impl AsRef<Path>) -> Self {
+        self.inputs
+            .push((path.as_ref().to_owned(), Format::TypedData));
+        self
+    }
+
+    /// The structs of all inputs, in order
+    pub fn structs(&self) -> Result<Vec<StructDef>, Error> {
+        let mut structs: Vec<StructDef> = Vec::new();
+        for (path, format) in &self.inputs {
+            let contents = std::fs::read_to_string(path)
+                .map_err(|e| invalid(format!("{}: {}", path.display(), e)))?;
+            let parsed = match format {
+                Format::Solidity => parse_solidity(&contents),
+                Format::Abi => parse_abi(&contents),
+                Format::TypedData => parse_typed_data(&contents),
+            }
+            .map_err(|e| match e {
+                Error::Codegen(message) => invalid(format!("{}: {}", path.display(), message)),
+                e => e,
+            })?;
+            for s in parsed {
+                match structs.iter().find(|existing| existing.name == s.name) {
+                    Some(existing) if *existing == s => {}
+                    Some(_) => {
+                        return Err(invalid(format!(
+                            "{}: conflicting definitions of struct {}",
+                            path.display(),
+                            s.name
+                        )))
+                    }
+                    None => structs.push(s),
+                }
+            }
+        }
+        Ok(structs)
+    }
+
+    /// The Rust code for all inputs
+    pub fn generate(&self) -> Result<String, Error> {
+        rust(&self.structs()?)
+    }
+
+    /// Writes the code to `path`, and asks cargo to rerun the build script
+    /// when an input changes.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        let code = self.generate()?;
+        for (input, _) in &self.inputs {
+            println!("cargo:rerun-if-changed={}", input.display());
+        }
+        std::fs::write(path, code).map_err(|e| invalid(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Writes the code to `file_name` in the build script's OUT_DIR,
+    /// returning its path.
+    pub fn write_to_out_dir(&self, file_name: &str) -> Result<PathBuf, Error> {
+        let out_dir = std::env::var_os("OUT_DIR")
+            .ok_or_else(|| invalid("OUT_DIR is not set outside of build scripts"))?;
+        let path = Path::new(&out_dir).join(file_name);
+        self.write(&path)?;
+        Ok(path)
+    }
+}
+
+/// Generates Rust from the structs used by a contract's ABI.
+pub fn rust_from_abi(json: &str) -> Result<String, Error> {
+    rust(&parse_abi(json)?)
+}
+
+/// Extracts the structs which appear as tuple parameters anywhere in a
+/// contract ABI, outermost first.
+///
+/// Struct names come from `internalType` (eg: "struct Market.Order[]" is the
+/// struct `Order`), which solc has emitted since 0.5.11. For tuples without
+/// one the parameter name is used, in PascalCase.
+///
+/// Compiler artifacts from Foundry or Hardhat, with the ABI under `abi`, are
+/// accepted too.
+pub fn parse_abi(json: &str) -> Result<Vec<StructDef>, Error> {
+    let abi: Value = serde_json::from_str(json).map_err(|e| invalid(e.to_string()))?;
+    let items = abi
+        .get("abi")
+        .unwrap_or(&abi)
+        .as_array()
+        .ok_or_else(|| invalid("ABI must be an array"))?;
+    let mut structs = Vec::new();
+    for item in items {
+        for key in &["inputs", "outputs"] {
+            if let Some(params) = item.get(key) {
+                let params = params
+                    .as_array()
+                    .ok_or_else(|| invalid(format!("{} must be an array", key)))?;
+                for param in params {
+                    abi_type(param, &mut structs)?;
+                }
+            }
+        }
+    }
+    Ok(structs)
+}
+
+/// The EIP-712 type of an ABI parameter, adding any struct it names to
+/// `structs`.
+fn abi_type(param: &Value, structs: &mut Vec<StructDef>) -> Result<String, Error> {
+    let field = |key: &str| param.get(key).and_then(Value::as_str);
+    let r#type = field("type").ok_or_else(|| invalid("parameter without a type"))?;
+    let suffix = match r#type.strip_prefix("tuple") {
+        Some(suffix) => suffix,
+        None => return Ok(r#type.to_owned()),
+    };
+
+    let name = match field("internalType").and_then(|t| t.strip_prefix("struct ")) {
+        Some(internal) => {
+            let internal = &internal[..internal.find('[').unwrap_or(internal.len())];
+            internal.rsplit('.').next().unwrap().to_owned()
+        }
+        None => pascal_case(field("name").unwrap_or_default())
+            .ok_or_else(|| invalid("tuple without a struct name"))?,
+    };
+    let components = param
+        .get("components")
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid(format!("tuple {} without components", name)))?;
+
+    // Reserve the position before visiting members, so that structs are
+    // listed outermost first.
+    let position = structs.iter().position(|s| s.name == name);
+    let index = position.unwrap_or_else(|| {
+        structs.push(StructDef {
+            name: name.clone(),
+            members: Vec::new(),
+        });
+        structs.len() - 1
+    });
+    let mut members = Vec::with_capacity(components.len());
+    for component in components {
+        let member = component
+            .get("name")
+            .and_then(Value::as_str)
+            .filter(|n| !n.is_empty())
+            .ok_or_else(|| invalid(format!("unnamed component in {}", name)))?;
+        members.push(MemberDef {
+            name: member.to_owned(),
+            r#type: abi_type(component, structs)?,
+        });
+    }
+    if position.is_some() {
+        if structs[index].members != members {
+            return Err(invalid(format!("conflicting definitions of {}", name)));
+        }
+    } else {
+        structs[index].members = members;
+    }
+    Ok(format!("{}{}", name, suffix))
+}
+
+fn pascal_case(name: &str) -> Option<String> {
+    let mut chars = name.chars();
+    let first = chars.next()?;
+    Some(first.to_ascii_uppercase().to_string() + chars.as_str())
+}
+
+/// Generates Rust from the types of eth_signTypedData JSON.
+pub fn rust_from_typed_data(json: &str) -> Result<String, Error> {
+    rust(&parse_typed_data(json)?)
+}
+
+/// Extracts the struct types of eth_signTypedData JSON, given either the whole
+/// document or only its `types` object. The primary type comes first, if
+/// given, followed by the rest sorted by name. `EIP712Domain` is included,
+/// since domains differ in which members they have.
+pub fn parse_typed_data(json: &str) -> Result<Vec<StructDef>, Error> {
+    let json: Value = serde_json::from_str(json).map_err(|e| invalid(e.to_string()))?;
+    let types = json
+        .get("types")
+        .unwrap_or(&json)
+        .as_object()
+        .ok_or_else(|| invalid("types must be an object"))?;
+    let primary_type = json.get("primaryType").and_then(Value::as_str);
+
+    // Rust identifiers are EIP-712 identifiers without $
+    let check = |name: &str| {
+        if is_identifier(name) && !name.contains('$') {
+            Ok(name.to_owned())
+        } else {
+            Err(invalid(format!("{} is not a valid Rust identifier", name)))
+        }
+    };
+    let mut structs = Vec::with_capacity(types.len());
+    for (name, members) in types {
+        let members = members
+            .as_array()
+            .ok_or_else(|| invalid(format!("members of {} must be an array", name)))?
+            .iter()
+            .map(|member| {
+                let field = |key: &str| {
+                    member
+                        .get(key)
+                        .and_then(Value::as_str)
+                        .ok_or_else(|| invalid(format!("member of {} without a {}", name, key)))
+                };
+                Ok(MemberDef {
+                    name: check(field("name")?)?,
+                    r#type: field("type")?.to_owned(),
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+        let def = StructDef {
+            name: check(name)?,
+            members,
+        };
+        if Some(name.as_str()) == primary_type {
+            structs.insert(0, def);
+        } else {
+            structs.push(def);
+        }
+    }
+    Ok(structs)
+}
+
+/// Generates Rust from the struct declarations in Solidity source. Other
+/// declarations (contracts, functions, events...) are ignored.
+pub fn rust_from_solidity(source: &str) -> Result<String, Error> {
+    rust(&parse_solidity(source)?)
+}
+
+/// Extracts struct declarations from Solidity source, in order.
+///
+/// Solidity aliases are resolved (`uint` is `uint256`, `address payable` is
+/// `address`), enums become `uint8` as in the ABI, and qualified names such
+/// as `Lib.Order` refer to the struct `Order`.
+pub fn parse_solidity(source: &str) -> Result<Vec<StructDef>, Error> {
+    let source = strip_comments(source);
+    let tokens = tokenize(&source);
+    let enums: BTreeSet<&str> = tokens
+        .windows(2)
+        .filter(|w| w[0] == "enum")
+        .map(|w| w[1])
+        .collect();
+
+    let mut structs = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        if tokens[i] != "struct" {
+            i += 1;
+            continue;
+        }
+        let name = *tokens
+            .get(i + 1)
+            .ok_or_else(|| invalid("expected struct name"))?;
+        expect(&tokens, i + 2, "{")?;
+        i += 3;
+        let mut members = Vec::new();
+        loop {
+            match tokens.get(i) {
+                Some(&"}") => break,
+                Some(&"mapping") => {
+                    return Err(invalid(format!(
+                        "mapping in struct {} has no EIP-712 type",
+                        name
+                    )))
+                }
+                None => return Err(invalid(format!("unterminated struct {}", name))),
+                _ => {}
+            }
+            let mut r#type = solidity_type(tokens[i], &enums);
+            i += 1;
+            if tokens.get(i) == Some(&"payable") {
+                i += 1;
+            }
+            while tokens.get(i) == Some(&"[") {
+                r#type.push('[');
+                i += 1;
+                if tokens.get(i) != Some(&"]") {
+                    r#type.push_str(tokens.get(i).ok_or_else(|| invalid("expected ]"))?);
+                    i += 1;
+                }
+                expect(&tokens, i, "]")?;
+                r#type.push(']');
+                i += 1;
+            }
+            let member = *tokens
+                .get(i)
+                .ok_or_else(|| invalid("expected member name"))?;
+            expect(&tokens, i + 1, ";")?;
+            i += 2;
+            members.push(MemberDef {
+                name: member.to_owned(),
+                r#type,
+            });
+        }
+        structs.push(StructDef {
+            name: name.to_owned(),
+            members,
+        });
+        i += 1;
+    }
+    Ok(structs)
+}
+
+fn expect(tokens: &[&str], i: usize, token: &str) -> Result<(), Error> {
+    match tokens.get(i) {
+        Some(&t) if t == token => Ok(()),
+        Some(t) => Err(invalid(format!("expected {} but found {}", token, t))),
+        None => Err(invalid(format!(
+            "expected {} but found end of input",
+            token
+        ))),
+    }
+}
+
+fn solidity_type(name: &str, enums: &BTreeSet<&str>) -> String {
+    let name = name.rsplit('.').next().unwrap();
+    match name {
+        "uint" => "uint256".to_owned(),
+        "int" => "int256".to_owned(),
+        "byte" => "bytes1".to_owned(),
+        _ if enums.contains(name) => "uint8".to_owned(),
+        _ => name.to_owned(),
+    }
+}
+
+fn strip_comments(source: &str) -> String {
+    let mut result = String::with_capacity(source.len());
+    let mut rest = source;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("//") {
+            rest = after.find('\n').map_or("", |end| &after[end..]);
+        } else if let Some(after) = rest.strip_prefix("/*") {
+            rest = after.find("*/").map_or("", |end| &after[end + 2..]);
+            result.push(' ');
+        } else {
+            let c = rest.chars().next().unwrap();
+            result.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    result
+}
+
+/// Splits into identifiers (including qualified names and numbers) and
+/// single character punctuation.
+fn tokenize(source: &str) -> Vec<&str> {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$' || c == '.';
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (i, c) in source.char_indices() {
+        if is_ident(c) {
+            start.get_or_insert(i);
+            continue;
+        }
+        if let Some(s) = start.take() {
+            tokens.push(&source[s..i]);
+        }
+        if !c.is_whitespace() {
+            tokens.push(&source[i..i + c.len_utf8()]);
+        }
+    }
+    if let Some(s) = start {
+        tokens.push(&source[s..]);
+    }
+    tokens
+}
+
+/// Emits a Rust struct and StructType impl for each struct. Every struct
+/// member type must be one of `structs`.
+pub fn rust(structs: &[StructDef]) -> Result<String, Error> {
+    let names: BTreeSet<&str> = structs.iter().map(|s| s.name.as_str()).collect();
+    let mut out = String::from("// Generated by eip-712-derive. Do not edit.\n");
+    for s in structs {
+        let mut fields = Vec::with_capacity(s.members.len());
+        for member in &s.members {
+            let r#type = rust_type(&member.r#type, &names).ok_or_else(|| {
+                invalid(format!(
+                    "unsupported type {} for {}.{}",
+                    member.r#type, s.name, member.name
+                ))
+            })?;
+            fields.push((rust_field_name(&member.name), r#type, &member.name));
+        }
+
+        writeln!(out).unwrap();
+        writeln!(out, "#[derive(Clone, PartialEq, Eq, Debug, Default)]").unwrap();
+        writeln!(out, "pub struct {} {{", s.name).unwrap();
+        for (field, r#type, _) in &fields {
+            writeln!(out, "    pub {}: {},", field, r#type).unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "impl ::eip_712_derive::StructType for {} {{", s.name).unwrap();
+        writeln!(out, "    const TYPE_NAME: &'static str = {:?};", s.name).unwrap();
+        writeln!(
+            out,
+            "    fn visit_members<T: ::eip_712_derive::MemberVisitor>(&self, visitor: &mut T) {{"
+        )
+        .unwrap();
+        for (field, _, name) in &fields {
+            writeln!(out, "        visitor.visit({:?}, &self.{});", name, field).unwrap();
+        }
+        writeln!(out, "    }}").unwrap();
+        writeln!(
+            out,
+            "    fn visit_member_types<T: ::eip_712_derive::TypeVisitor>(\n        \
+             visitor: &mut T,\n    ) -> ::std::result::Result<(), ::eip_712_derive::Error> {{"
+        )
+        .unwrap();
+        for (_, r#type, name) in &fields {
+            writeln!(out, "        visitor.visit::<{}>({:?});", r#type, name).unwrap();
+        }
+        writeln!(out, "        ::std::result::Result::Ok(())").unwrap();
+        writeln!(out, "    }}").unwrap();
+        writeln!(out, "}}").unwrap();
+    }
+    Ok(out)
+}
+
+/// The struct types making up the type of `value`: its own first, then those
+/// it references, sorted by name.
+pub fn struct_defs<T: StructType>(value: &T) -> Result<Vec<StructDef>, Error> {
+    Ok(TypeDescriptor::of_value(value)?
+        .structs()
+        .map(|s| StructDef {
+            name: s.name.to_owned(),
+            members: s
+                .members
+                .iter()
+                .map(|m| MemberDef {
+                    name: m.name.to_owned(),
+                    r#type: m.r#type.clone().into_owned(),
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+/// Emits a TypeScript interface for each struct, followed by the `types`
+/// object to pass to ethers' `signTypedData`. Integers are `bigint`, as
+/// ethers v6 returns them, and addresses and bytes are hex strings.
+pub fn typescript(structs: &[StructDef]) -> Result<String, Error> {
+    let names: BTreeSet<&str> = structs.iter().map(|s| s.name.as_str()).collect();
+    let mut out = String::from("// Generated by eip-712-derive. Do not edit.\n");
+    for s in structs {
+        writeln!(out).unwrap();
+        writeln!(out, "export interface {} {{", s.name).unwrap();
+        for member in &s.members {
+            let r#type = typescript_type(&member.r#type, &names).ok_or_else(|| {
+                invalid(format!(
+                    "unsupported type {} for {}.{}",
+                    member.r#type, s.name, member.name
+                ))
+            })?;
+            writeln!(out, "  {}: {};", member.name, r#type).unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+    }
+
+    writeln!(out).unwrap();
+    writeln!(out, "export const types = {{").unwrap();
+    for s in structs {
+        writeln!(out, "  {}: [", s.name).unwrap();
+        for member in &s.members {
+            writeln!(
+                out,
+                "    {{ name: {:?}, type: {:?} }},",
+                member.name, member.r#type
+            )
+            .unwrap();
+        }
+        writeln!(out, "  ],").unwrap();
+    }
+    writeln!(out, "}};").unwrap();
+    Ok(out)
+}
+
+fn typescript_type(r#type: &str, structs: &BTreeSet<&str>) -> Option<String> {
+    if let Some(element) = r#type.strip_suffix(']') {
+        let open = element.rfind('[')?;
+        let len = &element[open + 1..];
+        if !len.is_empty() && len.parse::<usize>().is_err() {
+            return None;
+        }
+        let element = typescript_type(&element[..open], structs)?;
+        return Some(format!("{}[]", element));
+    }
+    if structs.contains(r#type) {
+        return Some(r#type.to_owned());
+    }
+    if !is_atomic_or_dynamic(r#type) {
+        return None;
+    }
+    Some(
+        match r#type {
+            "bool" => "boolean",
+            _ if r#type.starts_with("uint") || r#type.starts_with("int") => "bigint",
+            _ => "string",
+        }
+        .to_owned(),
+    )
+}
+
+/// Emits a Solidity file with each struct, its `TYPEHASH` constant and a
+/// `hashStruct` function, and a `verify` function for the first struct.
+///
+/// The type strings are encoded here rather than by hand, so they are
+/// byte-identical to those hashed in Rust. Members may not be arrays of arrays,
+/// which `abi.encodePacked` cannot hash.
+pub fn solidity(structs: &[StructDef]) -> Result<String, Error> {
+    let by_name: BTreeMap<&str, &StructDef> =
+        structs.iter().map(|s| (s.name.as_str(), s)).collect();
+    let mut out = String::from("// Generated by eip-712-derive. Do not edit.\n");
+    writeln!(out, "pragma solidity ^0.8.4;").unwrap();
+
+    // Arrays whose elements must be hashed one by one, by Solidity type
+    let mut array_hashers = BTreeMap::new();
+    let mut functions = String::new();
+    for s in structs {
+        writeln!(out).unwrap();
+        writeln!(out, "struct {} {{", s.name).unwrap();
+        for member in &s.members {
+            writeln!(out, "    {} {};", member.r#type, member.name).unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+
+        let constant = format!("{}_TYPEHASH", solidity_constant_name(&s.name));
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "bytes32 constant {} = keccak256({:?});",
+            constant,
+            solidity_encode_type(s, &by_name)
+        )
+        .unwrap();
+
+        let mut values = vec![constant];
+        for member in &s.members {
+            let value = format!("value.{}", member.name);
+            values.push(solidity_value(
+                &member.r#type,
+                &value,
+                &by_name,
+                &mut array_hashers,
+            )?);
+        }
+        writeln!(functions).unwrap();
+        writeln!(
+            functions,
+            "function hashStruct({} memory value) pure returns (bytes32) {{",
+            s.name
+        )
+        .unwrap();
+        writeln!(
+            functions,
+            "    return keccak256(abi.encode({}));",
+            values.join(", ")
+        )
+        .unwrap();
+        writeln!(functions, "}}").unwrap();
+    }
+
+    for (array_type, element_type) in &array_hashers {
+        let element = solidity_value(element_type, "values[i]", &by_name, &mut BTreeMap::new())?;
+        writeln!(functions).unwrap();
+        writeln!(
+            functions,
+            "function hashArray({} memory values) pure returns (bytes32) {{",
+            array_type
+        )
+        .unwrap();
+        writeln!(
+            functions,
+            "    bytes32[] memory hashes = new bytes32[](values.length);"
+        )
+        .unwrap();
+        writeln!(
+            functions,
+            "    for (uint256 i = 0; i < values.length; i++) {{"
+        )
+        .unwrap();
+        writeln!(functions, "        hashes[i] = {};", element).unwrap();
+        writeln!(functions, "    }}").unwrap();
+        writeln!(functions, "    return keccak256(abi.encodePacked(hashes));").unwrap();
+        writeln!(functions, "}}").unwrap();
+    }
+    out.push_str(&functions);
+
+    if let Some(primary) = structs.first() {
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "/// Whether the signature (v, r, s) of `value` under `domainSeparator` is by `signer`"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "function verify(bytes32 domainSeparator, {} memory value, address signer, uint8 v, bytes32 r, bytes32 s) pure returns (bool) {{",
+            primary.name
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "    bytes32 digest = keccak256(abi.encodePacked(\"\\x19\\x01\", domainSeparator, hashStruct(value)));"
+        )
+        .unwrap();
+        writeln!(out, "    address recovered = ecrecover(digest, v, r, s);").unwrap();
+        writeln!(
+            out,
+            "    return recovered != address(0) && recovered == signer;"
+        )
+        .unwrap();
+        writeln!(out, "}}").unwrap();
+    }
+    Ok(out)
+}
+
+/// The expression for the encodeData of `value`, of EIP-712 type `type`
+fn solidity_value(
+    r#type: &str,
+    value: &str,
+    structs: &BTreeMap<&str, &StructDef>,
+    array_hashers: &mut BTreeMap<String, String>,
+) -> Result<String, Error> {
+    let unsupported = || invalid(format!("unsupported type {}", r#type));
+    if let Some(element) = r#type.strip_suffix(']') {
+        let element = &element[..element.rfind('[').ok_or_else(unsupported)?];
+        if element.ends_with(']') {
+            return Err(unsupported());
+        }
+        if structs.contains_key(element) || element == "string" || element == "bytes" {
+            array_hashers.insert(r#type.to_owned(), element.to_owned());
+            return Ok(format!("hashArray({})", value));
+        }
+        if !is_atomic_or_dynamic(element) {
+            return Err(unsupported());
+        }
+        // Atomic elements are each padded to 32 bytes
+        return Ok(format!("keccak256(abi.encodePacked({}))", value));
+    }
+    Ok(match r#type {
+        "string" => format!("keccak256(bytes({}))", value),
+        "bytes" => format!("keccak256({})", value),
+        _ if structs.contains_key(r#type) => format!("hashStruct({})", value),
+        _ if is_atomic_or_dynamic(r#type) => value.to_owned(),
+        _ => return Err(unsupported()),
+    })
+}
+
+/// (SPEC) encodeType: the struct followed by the structs it references,
+/// sorted by name
+fn solidity_encode_type(s: &StructDef, structs: &BTreeMap<&str, &StructDef>) -> String {
+    fn add<'a>(
+        s: &'a StructDef,
+        structs: &BTreeMap<&str, &'a StructDef>,
+        found: &mut BTreeMap<&'a str, &'a StructDef>,
+    ) {
+        if found.insert(&s.name, s).is_some() {
+            return;
+        }
+        for member in &s.members {
+            let base = member.r#type.split('[').next().unwrap();
+            if let Some(referenced) = structs.get(base) {
+                add(referenced, structs, found);
+            }
+        }
+    }
+    let mut found = BTreeMap::new();
+    add(s, structs, &mut found);
+    found.remove(s.name.as_str());
+
+    let mut result = String::new();
+    for t in std::iter::once(s).chain(found.into_values()) {
+        let members: Vec<String> = t
+            .members
+            .iter()
+            .map(|m| format!("{} {}", m.r#type, m.name))
+            .collect();
+        write!(result, "{}({})", t.name, members.join(",")).unwrap();
+    }
+    result
+}
+
+/// MailBox -> MAIL_BOX
+fn solidity_constant_name(name: &str) -> String {
+    snake_case(name).to_ascii_uppercase()
+}
+
+/// The Rust type implementing MemberType for an EIP-712 type
+fn rust_type(r#type: &str, structs: &BTreeSet<&str>) -> Option<String> {
+    if let Some(element) = r#type.strip_suffix("[]") {
+        return Some(format!("Vec<{}>", rust_type(element, structs)?));
+    }
+    if let Some(element) = r#type.strip_suffix(']') {
+        let open = element.rfind('[')?;
+        let len: usize = element[open + 1..].parse().ok()?;
+        // Larger arrays do not implement Default
+        if len > 32 {
+            return None;
+        }
+        return Some(format!(
+            "[{}; {}]",
+            rust_type(&element[..open], structs)?,
+            len
+        ));
+    }
+    let atomic = match r#type {
+        "address" => "Address",
+        "uint256" => "U256",
+        "string" => return Some("String".to_owned()),
+        "bytes" => return Some("Vec<u8>".to_owned()),
+        "bool" => return Some("bool".to_owned()),
+        "int8" | "int16" | "int32" | "int64" | "int128" => {
+            return Some(format!("i{}", &r#type[3..]))
+        }
+        _ => {
+            if structs.contains(r#type) {
+                return Some(r#type.to_owned());
+            }
+            if let Some(bits) = r#type.strip_prefix("uint") {
+                let bits: usize = bits.parse().ok()?;
+                if !bits.is_multiple_of(8)
+                    || !(8..256).contains(&bits)
+                    || bits.to_string() != r#type[4..]
+                {
+                    return None;
+                }
+                return Some(format!("::eip_712_derive::Uint{}", bits));
+            }
+            let size: usize = r#type.strip_prefix("bytes")?.parse().ok()?;
+            if !(1..=32).contains(&size) {
+                return None;
+            }
+            return Some(format!("::eip_712_derive::Bytes{}", size));
+        }
+    };
+    Some(format!("::eip_712_derive::{}", atomic))
+}
+
+/// camelCase to snake_case
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut result = String::with_capacity(name.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+            if prev.is_ascii_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_ascii_uppercase() && next_lower)
+            {
+                result.push('_');
+            }
+        }
+        result.push(c.to_ascii_lowercase());
+    }
+    result
+}
+
+/// snake_case, escaping keywords. Those which cannot be raw identifiers get a
+/// trailing underscore instead; the member name is given to the visitor, so
+/// the field name is free.
+fn rust_field_name(name: &str) -> String {
+    let mut result = snake_case(name);
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "box", "break", "const", "continue", "dyn", "else", "enum",
+        "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "try", "type",
+        "unsafe", "use", "where", "while", "yield",
+    ];
+    if matches!(result.as_str(), "crate" | "self" | "super") {
+        result.push('_');
+    } else if KEYWORDS.contains(&result.as_str()) {
+        result.insert_str(0, "r#");
+    }
+    result
+}
@@ -0,0 +1,455 @@
+//! Spec conformance fixtures. Requires the `conformance` feature.
+//!
+//! A [`Fixture`] pairs a domain and message with the expected intermediate
+//! values of the encoding, as produced by reference implementations such as
+//! eth-sig-util. [`run_conformance`] checks each of them against this crate,
+//! panicking with a description of the first difference.
+//!
+//! Downstream crates can implement [`Fixture`] for their own messages to pin
+//! their encoding, and forks of this crate can run [`run_bundled`] to check
+//! that they still follow the spec.
+
+use crate::prelude::*;
+use crate::*;
+
+/// Expected values are lowercase hex without the 0x prefix.
+pub trait Fixture {
+    type Domain: StructType;
+    type Message: StructType;
+
+    const ENCODE_TYPE: &'static str;
+    const TYPE_HASH: &'static str;
+    const ENCODE_DATA: &'static str;
+    const HASH_STRUCT: &'static str;
+    const DOMAIN_SEPARATOR: &'static str;
+    const SIGN_HASH: &'static str;
+
+    fn domain() -> Self::Domain;
+    fn message() -> Self::Message;
+
+    /// A private key and the expected signature over SIGN_HASH (r ‖ s ‖ v,
+    /// with v being 27 or 28), if the fixture includes one.
+    fn signature() -> Option<(PrivateKey, &'static str)> {
+        None
+    }
+}
+
+fn check(fixture: &str, step: &str, actual: &str, expected: &str) {
+    assert!(
+        actual == expected,
+        "{} fixture: {} differs\n  expected: {}\n  actual:   {}",
+        fixture,
+        step,
+        expected,
+        actual
+    );
+}
+
+/// Checks every value of the fixture `T`, panicking on the first mismatch.
+pub fn run_conformance<T: Fixture>() {
+    let name = std::any::type_name::<T>();
+    let message = T::message();
+    let domain_separator = DomainSeparator::new(&T::domain());
+
+    check(name, "encodeType", &encode_type(&message), T::ENCODE_TYPE);
+    check(
+        name,
+        "typeHash",
+        &encode_hex(&type_hash(&message)),
+        T::TYPE_HASH,
+    );
+    check(
+        name,
+        "encodeData",
+        &encode_hex(&encode_data(&message)),
+        T::ENCODE_DATA,
+    );
+    check(
+        name,
+        "hashStruct",
+        &encode_hex(&hash_struct(&message)),
+        T::HASH_STRUCT,
+    );
+    check(
+        name,
+        "domainSeparator",
+        &encode_hex(domain_separator.as_bytes()),
+        T::DOMAIN_SEPARATOR,
+    );
+    check(
+        name,
+        "signHash",
+        &encode_hex(&sign_hash(&domain_separator, &message)),
+        T::SIGN_HASH,
+    );
+
+    if let Some((key, expected)) = T::signature() {
+        let key = SecretKey::new(key).unwrap();
+        let signature = sign_typed(&domain_separator, &message, &key).unwrap();
+        check(
+            name,
+            "signature",
+            &encode_hex(&signature.to_bytes()),
+            expected,
+        );
+    }
+}
+
+/// Runs all of the fixtures bundled with the crate.
+pub fn run_bundled() {
+    run_conformance::<fixtures::Mail>();
+    run_conformance::<fixtures::MailWithArrays>();
+    run_conformance::<fixtures::RecursiveMail>();
+    run_conformance::<fixtures::EdgeCases>();
+}
+
+/// The fixtures bundled with the crate
+pub mod fixtures {
+    use super::*;
+
+    fn address(hex: &str) -> Address {
+        let mut result = [0u8; 20];
+        for (i, byte) in result.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap();
+        }
+        Address(result)
+    }
+
+    pub struct MailDomain {
+        pub name: String,
+        pub version: String,
+        pub chain_id: U256,
+        pub verifying_contract: Address,
+    }
+
+    impl StructType for MailDomain {
+        const TYPE_NAME: &'static str = "EIP712Domain";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("name", &self.name);
+            visitor.visit("version", &self.version);
+            visitor.visit("chainId", &self.chain_id);
+            visitor.visit("verifyingContract", &self.verifying_contract);
+        }
+    }
+
+    pub struct Person {
+        pub name: String,
+        pub wallet: Address,
+    }
+
+    impl StructType for Person {
+        const TYPE_NAME: &'static str = "Person";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("name", &self.name);
+            visitor.visit("wallet", &self.wallet);
+        }
+        fn visit_member_types<T: TypeVisitor>(visitor: &mut T) -> Result<(), Error> {
+            visitor.visit::<String>("name");
+            visitor.visit::<Address>("wallet");
+            Ok(())
+        }
+    }
+
+    pub struct MailMessage {
+        pub from: Person,
+        pub to: Person,
+        pub contents: String,
+    }
+
+    impl StructType for MailMessage {
+        const TYPE_NAME: &'static str = "Mail";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("from", &self.from);
+            visitor.visit("to", &self.to);
+            visitor.visit("contents", &self.contents);
+        }
+    }
+
+    /// The example from the EIP-712 specification, which is also the basic
+    /// case of eth-sig-util's signTypedData V3 and V4.
+    pub struct Mail;
+
+    impl Fixture for Mail {
+        type Domain = MailDomain;
+        type Message = MailMessage;
+
+        const ENCODE_TYPE: &'static str =
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)";
+        const TYPE_HASH: &'static str =
+            "a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2";
+        const ENCODE_DATA: &'static str = "a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2fc71e5fa27ff56c350aa531bc129ebdf613b772b6604664f5d8dbe21b85eb0c8cd54f074a4af31b4411ff6a60c9719dbd559c221c8ac3492d9d872b041d703d1b5aadf3154a261abdd9086fc627b61efca26ae5702701d05cd2305f7c52a2fc8";
+        const HASH_STRUCT: &'static str =
+            "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e";
+        const DOMAIN_SEPARATOR: &'static str =
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f";
+        const SIGN_HASH: &'static str =
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2";
+
+        fn domain() -> MailDomain {
+            let mut chain_id = U256::default();
+            chain_id.0[31] = 1;
+            MailDomain {
+                name: "Ether Mail".to_owned(),
+                version: "1".to_owned(),
+                chain_id,
+                verifying_contract: address("cccccccccccccccccccccccccccccccccccccccc"),
+            }
+        }
+
+        fn message() -> MailMessage {
+            MailMessage {
+                from: Person {
+                    name: "Cow".to_owned(),
+                    wallet: address("cd2a3d9f938e13cd947ec05abc7fe734df8dd826"),
+                },
+                to: Person {
+                    name: "Bob".to_owned(),
+                    wallet: address("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"),
+                },
+                contents: "Hello, Bob!".to_owned(),
+            }
+        }
+
+        fn signature() -> Option<(PrivateKey, &'static str)> {
+            Some((PrivateKey::from(keccak("cow")), "4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b915621c"))
+        }
+    }
+    pub struct PersonWallets {
+        pub name: String,
+        pub wallets: Vec<Address>,
+    }
+
+    impl StructType for PersonWallets {
+        const TYPE_NAME: &'static str = "Person";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("name", &self.name);
+            visitor.visit("wallets", &self.wallets);
+        }
+        fn visit_member_types<T: TypeVisitor>(visitor: &mut T) -> Result<(), Error> {
+            visitor.visit::<String>("name");
+            visitor.visit::<Vec<Address>>("wallets");
+            Ok(())
+        }
+    }
+
+    pub struct MailToMany {
+        pub from: PersonWallets,
+        pub to: Vec<PersonWallets>,
+        pub contents: String,
+    }
+
+    impl StructType for MailToMany {
+        const TYPE_NAME: &'static str = "Mail";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("from", &self.from);
+            visitor.visit("to", &self.to);
+            visitor.visit("contents", &self.contents);
+        }
+    }
+
+    /// eth-sig-util's signTypedData V4 example with arrays of addresses and
+    /// of structs, which V3 does not support.
+    pub struct MailWithArrays;
+
+    impl Fixture for MailWithArrays {
+        type Domain = MailDomain;
+        type Message = MailToMany;
+
+        const ENCODE_TYPE: &'static str =
+            "Mail(Person from,Person[] to,string contents)Person(string name,address[] wallets)";
+        const TYPE_HASH: &'static str =
+            "4bd8a9a2b93427bb184aca81e24beb30ffa3c747e2a33d4225ec08bf12e2e753";
+        const ENCODE_DATA: &'static str = "4bd8a9a2b93427bb184aca81e24beb30ffa3c747e2a33d4225ec08bf12e2e7539b4846dd48b866f0ac54d61b9b21a9e746f921cefa4ee94c4c0a1c49c774f67fca322beec85be24e374d18d582a6f2997f75c54e7993ab5bc07404ce176ca7cdb5aadf3154a261abdd9086fc627b61efca26ae5702701d05cd2305f7c52a2fc8";
+        const HASH_STRUCT: &'static str =
+            "eb4221181ff3f1a83ea7313993ca9218496e424604ba9492bb4052c03d5c3df8";
+        const DOMAIN_SEPARATOR: &'static str =
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f";
+        const SIGN_HASH: &'static str =
+            "a85c2e2b118698e88db68a8105b794a8cc7cec074e89ef991cb4f5f533819cc2";
+
+        fn domain() -> MailDomain {
+            Mail::domain()
+        }
+
+        fn message() -> MailToMany {
+            MailToMany {
+                from: PersonWallets {
+                    name: "Cow".to_owned(),
+                    wallets: vec![
+                        address("cd2a3d9f938e13cd947ec05abc7fe734df8dd826"),
+                        address("deadbeefdeadbeefdeadbeefdeadbeefdeadbeef"),
+                    ],
+                },
+                to: vec![PersonWallets {
+                    name: "Bob".to_owned(),
+                    wallets: vec![
+                        address("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"),
+                        address("b0bdabea57b0bdabea57b0bdabea57b0bdabea57"),
+                        address("b0b0b0b0b0b0b000000000000000000000000000"),
+                    ],
+                }],
+                contents: "Hello, Bob!".to_owned(),
+            }
+        }
+
+        fn signature() -> Option<(PrivateKey, &'static str)> {
+            Some((PrivateKey::from(keccak("cow")), "65cbd956f2fae28a601bebc9b906cea0191744bd4c4247bcd27cd08f8eb6b71c78efdf7a31dc9abee78f492292721f362d296cf86b4538e07b51303b67f749061b"))
+        }
+    }
+
+    pub struct Thread {
+        pub from: Person,
+        pub to: Person,
+        pub contents: String,
+        pub replies: Vec<Thread>,
+    }
+
+    impl StructType for Thread {
+        const TYPE_NAME: &'static str = "Mail";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("from", &self.from);
+            visitor.visit("to", &self.to);
+            visitor.visit("contents", &self.contents);
+            visitor.visit("replies", &self.replies);
+        }
+        fn visit_member_types<T: TypeVisitor>(visitor: &mut T) -> Result<(), Error> {
+            visitor.visit::<Person>("from");
+            visitor.visit::<Person>("to");
+            visitor.visit::<String>("contents");
+            visitor.visit::<Vec<Thread>>("replies");
+            Ok(())
+        }
+    }
+
+    /// A type which refers to itself, which encodeType lists once, and whose
+    /// innermost array is empty. The expected values follow eth-sig-util's V4
+    /// rules.
+    pub struct RecursiveMail;
+
+    impl Fixture for RecursiveMail {
+        type Domain = MailDomain;
+        type Message = Thread;
+
+        const ENCODE_TYPE: &'static str =
+            "Mail(Person from,Person to,string contents,Mail[] replies)Person(string name,address wallet)";
+        const TYPE_HASH: &'static str =
+            "3fc83a1e401d52b1cae9249a08fa2e58cfac829d5405de64b51614b780303923";
+        const ENCODE_DATA: &'static str = "3fc83a1e401d52b1cae9249a08fa2e58cfac829d5405de64b51614b780303923fc71e5fa27ff56c350aa531bc129ebdf613b772b6604664f5d8dbe21b85eb0c8cd54f074a4af31b4411ff6a60c9719dbd559c221c8ac3492d9d872b041d703d1b5aadf3154a261abdd9086fc627b61efca26ae5702701d05cd2305f7c52a2fc880ded9685711efa1cb40d489b59dfb2551407d73b57d2fa4b9e47bcdc3655f07";
+        const HASH_STRUCT: &'static str =
+            "5aeab9e0eece84587d93efc32f65bf866448228b3984fb07801ce3561ce9bf92";
+        const DOMAIN_SEPARATOR: &'static str =
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f";
+        const SIGN_HASH: &'static str =
+            "ae8d858dd41165ab09c0a5399774b8718813f36a0a3e0f42bd6389a9d5f949bf";
+
+        fn domain() -> MailDomain {
+            Mail::domain()
+        }
+
+        fn message() -> Thread {
+            let mail = Mail::message();
+            Thread {
+                from: Person {
+                    name: mail.from.name.clone(),
+                    wallet: mail.from.wallet,
+                },
+                to: Person {
+                    name: mail.to.name.clone(),
+                    wallet: mail.to.wallet,
+                },
+                contents: mail.contents,
+                replies: vec![Thread {
+                    from: mail.to,
+                    to: mail.from,
+                    contents: "Hello, Cow!".to_owned(),
+                    replies: Vec::new(),
+                }],
+            }
+        }
+    }
+
+    /// A domain without version or verifyingContract, with a salt
+    pub struct SaltedDomain {
+        pub name: String,
+        pub chain_id: U256,
+        pub salt: Bytes32,
+    }
+
+    impl StructType for SaltedDomain {
+        const TYPE_NAME: &'static str = "EIP712Domain";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("name", &self.name);
+            visitor.visit("chainId", &self.chain_id);
+            visitor.visit("salt", &self.salt);
+        }
+    }
+
+    pub struct Edges {
+        pub data: Vec<u8>,
+        pub selector: Bytes4,
+        pub flag: bool,
+        pub delta: i64,
+        pub amount: U256,
+        pub note: String,
+        pub wallets: Vec<Address>,
+        pub pairs: Vec<[u64; 2]>,
+    }
+
+    impl StructType for Edges {
+        const TYPE_NAME: &'static str = "Edges";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("data", &self.data);
+            visitor.visit("selector", &self.selector);
+            visitor.visit("flag", &self.flag);
+            visitor.visit("delta", &self.delta);
+            visitor.visit("amount", &self.amount);
+            visitor.visit("note", &self.note);
+            visitor.visit("wallets", &self.wallets);
+            visitor.visit("pairs", &self.pairs);
+        }
+    }
+
+    /// Encodings which implementations commonly get wrong: empty bytes,
+    /// strings and arrays hash to keccak256(""), bytes4 is padded at the end,
+    /// negative ints are sign extended, and nested arrays hash each inner
+    /// array. The expected values follow eth-sig-util's V4 rules, which agree
+    /// with V3 for everything but the arrays.
+    pub struct EdgeCases;
+
+    impl Fixture for EdgeCases {
+        type Domain = SaltedDomain;
+        type Message = Edges;
+
+        const ENCODE_TYPE: &'static str = "Edges(bytes data,bytes4 selector,bool flag,int64 delta,uint256 amount,string note,address[] wallets,uint64[2][] pairs)";
+        const TYPE_HASH: &'static str =
+            "738dc2a6c936a20e469aa93df29ebf25ec8887b1b5f4f781888e0cdf44ad34db";
+        const ENCODE_DATA: &'static str = "738dc2a6c936a20e469aa93df29ebf25ec8887b1b5f4f781888e0cdf44ad34dbc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470a9059cbb000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000001ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470a9bb8c3f1f12e9aa903a50c47f314b57610a3ab32f2d463293f58836def38d36";
+        const HASH_STRUCT: &'static str =
+            "61507696ef47b6da3956efc96562806d9aae0a1a4f325658247eaa60987a9aec";
+        const DOMAIN_SEPARATOR: &'static str =
+            "0a2b2f429e12c8f8cdd41ffa645a842dd8d2501c633445400153bd2e068718dc";
+        const SIGN_HASH: &'static str =
+            "674e2dbda20ccca8205381eb3c9cfa8df547bb94ea9567b53b7b8334d7070b64";
+
+        fn domain() -> SaltedDomain {
+            SaltedDomain {
+                name: "Edges".to_owned(),
+                chain_id: U256::from(137u64),
+                salt: [0xab; 32],
+            }
+        }
+
+        fn message() -> Edges {
+            Edges {
+                data: Vec::new(),
+                selector: [0xa9, 0x05, 0x9c, 0xbb],
+                flag: true,
+                delta: -1,
+                amount: U256::MAX,
+                note: String::new(),
+                wallets: Vec::new(),
+                pairs: vec![[1, 2], [3, 4]],
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use crate::type_hash::{try_collect_types, try_collect_types_of, EncodedType};
+use crate::{Error, StructType};
+use std::borrow::Cow;
+use std::fmt;
+
+/// The structure of a type, as encodeType describes it, for tooling which
+/// needs more than the encodeType string. Nothing is hashed.
+///
+/// The Display impl writes the encodeType.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct TypeDescriptor {
+    pub primary: StructDescriptor,
+    /// Every struct type referenced by the primary type, directly or through
+    /// other structs, sorted by name. The primary type is not included, even
+    /// if it references itself.
+    pub referenced: Vec<StructDescriptor>,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct StructDescriptor {
+    pub name: &'static str,
+    /// In declaration order
+    pub members: Vec<MemberDescriptor>,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct MemberDescriptor {
+    pub name: &'static str,
+    /// The EIP-712 type, eg: "uint256", "Person" or "Person[]"
+    pub r#type: Cow<'static, str>,
+}
+
+impl TypeDescriptor {
+    /// Describes `T` without a value. See [`encode_type_of`](crate::encode_type_of).
+    pub fn of<T: StructType>() -> Result<Self, Error> {
+        Ok(Self::new(try_collect_types_of::<T>()?))
+    }
+
+    /// Describes the type of `value`
+    pub fn of_value<T: StructType>(value: &T) -> Result<Self, Error> {
+        Ok(Self::new(try_collect_types(value)?))
+    }
+
+    fn new(types: Vec<EncodedType>) -> Self {
+        let mut types = types.into_iter().map(|t| StructDescriptor {
+            name: t.name,
+            members: t
+                .members
+                .into_iter()
+                .map(|m| MemberDescriptor {
+                    name: m.name,
+                    r#type: m.r#type,
+                })
+                .collect(),
+        });
+        Self {
+            // There is always an outer type
+            primary: types.next().unwrap(),
+            referenced: types.collect(),
+        }
+    }
+
+    /// The primary type followed by the referenced types, as in encodeType
+    pub fn structs(&self) -> impl Iterator<Item = &StructDescriptor> {
+        std::iter::once(&self.primary).chain(&self.referenced)
+    }
+
+    /// The referenced struct type with the given name
+    pub fn get(&self, name: &str) -> Option<&StructDescriptor> {
+        self.structs().find(|s| s.name == name)
+    }
+}
+
+impl fmt::Display for TypeDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for s in self.structs() {
+            write!(f, "{}", s)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for StructDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}(", self.name)?;
+        for (i, member) in self.members.iter().enumerate() {
+            if i != 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{} {}", member.r#type, member.name)?;
+        }
+        f.write_str(")")
+    }
+}
@@ -0,0 +1,164 @@
+use crate::prelude::*;
+use crate::{Address, DomainSeparator, Eip712Domain, MemberVisitor, StructType, U256};
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::RwLock;
+
+/// An EIP712Domain with only some of the recommended fields. Fields which are
+/// `None` are left out of the type, eg: a domain with only a name and chain id
+/// is encoded as `EIP712Domain(string name,uint256 chainId)`.
+///
+/// Build one with [`Eip712Domain::builder`]. Since the members depend on the
+/// value, the type hash of a PartialDomain, and of any struct containing one,
+/// is not memoized.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Hash)]
+pub struct PartialDomain {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub chain_id: Option<U256>,
+    pub verifying_contract: Option<Address>,
+    pub salt: Option<Bytes32>,
+}
+
+impl StructType for PartialDomain {
+    const TYPE_NAME: &'static str = "EIP712Domain";
+
+    fn visit_members<T: MemberVisitor>(&self, v: &mut T) {
+        if let Some(name) = &self.name {
+            v.visit("name", name);
+        }
+        if let Some(version) = &self.version {
+            v.visit("version", version);
+        }
+        if let Some(chain_id) = &self.chain_id {
+            v.visit("chainId", chain_id);
+        }
+        if let Some(verifying_contract) = &self.verifying_contract {
+            v.visit("verifyingContract", verifying_contract);
+        }
+        if let Some(salt) = &self.salt {
+            v.visit("salt", salt);
+        }
+    }
+
+    fn fixed_members() -> bool {
+        false
+    }
+}
+
+impl From<Eip712Domain> for PartialDomain {
+    fn from(domain: Eip712Domain) -> Self {
+        Self {
+            name: Some(domain.name),
+            version: Some(domain.version),
+            chain_id: Some(domain.chain_id),
+            verifying_contract: Some(domain.verifying_contract),
+            salt: Some(domain.salt),
+        }
+    }
+}
+
+/// See [`Eip712Domain::builder`]
+#[derive(Clone, Debug, Default)]
+pub struct DomainBuilder {
+    domain: PartialDomain,
+}
+
+impl DomainBuilder {
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.domain.name = Some(name.into());
+        self
+    }
+
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.domain.version = Some(version.into());
+        self
+    }
+
+    pub fn chain_id(mut self, chain_id: impl Into<U256>) -> Self {
+        self.domain.chain_id = Some(chain_id.into());
+        self
+    }
+
+    pub fn verifying_contract(mut self, verifying_contract: Address) -> Self {
+        self.domain.verifying_contract = Some(verifying_contract);
+        self
+    }
+
+    /// Accepts a [`Salt`](crate::Salt) or raw bytes
+    pub fn salt(mut self, salt: impl Into<Bytes32>) -> Self {
+        self.domain.salt = Some(salt.into());
+        self
+    }
+
+    pub fn build(self) -> PartialDomain {
+        self.domain
+    }
+}
+
+impl Eip712Domain {
+    /// Builds a domain with only the fields which are set, for contracts
+    /// which do not use all of the recommended fields, eg:
+    /// `Eip712Domain::builder().name("Ether Mail").version("1").chain_id(1u64).build()`
+    pub fn builder() -> DomainBuilder {
+        DomainBuilder::default()
+    }
+}
+
+/// Memoizes the domain separators of domains, keyed by their contents, for
+/// services which sign many messages under a few domains.
+///
+/// Entries are never evicted, so only use a registry for a bounded set of
+/// domains, not for domains taken from requests.
+#[derive(Debug)]
+pub struct DomainRegistry<D = Eip712Domain> {
+    separators: RwLock<HashMap<D, DomainSeparator>>,
+}
+
+impl<D> Default for DomainRegistry<D> {
+    fn default() -> Self {
+        Self {
+            separators: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl<D: StructType + Eq + Hash + Clone> DomainRegistry<D> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The domain separator of `domain`, computed on first use
+    pub fn domain_separator(&self, domain: &D) -> DomainSeparator {
+        if let Some(separator) = self.separators.read().unwrap().get(domain) {
+            return *separator;
+        }
+        let separator = DomainSeparator::new(domain);
+        self.separators
+            .write()
+            .unwrap()
+            .insert(domain.clone(), separator);
+        separator
+    }
+
+    /// The number of domains memoized
+    pub fn len(&self) -> usize {
+        self.separators.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+lazy_static! {
+    static ref GLOBAL: DomainRegistry = DomainRegistry::new();
+}
+
+impl DomainRegistry {
+    /// A registry shared by the whole process
+    pub fn global() -> &'static Self {
+        &GLOBAL
+    }
+}
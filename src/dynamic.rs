@@ -0,0 +1,514 @@
+//! Typed data with types known only at runtime.
+//!
+//! Services which receive arbitrary schemas, like wallet backends and
+//! relayers, cannot have a Rust struct for every type they hash. Here the
+//! types are [`TypeDefinition`]s collected in [`Types`], and values are
+//! [`Value`] trees which are checked against them while hashing. A
+//! [`TypedData`] bundles both with a domain, like an eth_signTypedData_v4
+//! request.
+
+use crate::prelude::*;
+use crate::type_hash::is_identifier;
+use crate::{Address, DomainSeparator, Eip712Hashes, Error};
+use std::collections::{BTreeMap, BTreeSet};
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidTypedData(message.into())
+}
+
+/// A value of any EIP-712 type. Which type is decided by the type definition
+/// it is hashed with.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Value {
+    /// uint8 to uint256
+    Uint(U256),
+    /// int8 to int256, as 256 bit two's complement
+    Int(U256),
+    Address(Address),
+    /// bytes, or bytes1 to bytes32 of the exact length
+    Bytes(Vec<u8>),
+    String(String),
+    Bool(bool),
+    /// Type[] or Type[n]
+    Array(Vec<Value>),
+    /// Members by name
+    Struct(BTreeMap<String, Value>),
+}
+
+impl Value {
+    pub fn int(value: i128) -> Self {
+        let fill = if value < 0 { 0xff } else { 0 };
+        let mut bytes = [fill; 32];
+        bytes[16..].copy_from_slice(&value.to_be_bytes());
+        Value::Int(U256(bytes))
+    }
+
+    /// Decodes the encodeData of a value of an atomic type. None for other
+    /// types, whose encodeData is a hash.
+    pub(crate) fn from_atomic(r#type: &str, encoded: &Bytes32) -> Option<Self> {
+        match r#type {
+            "bool" => Some(Value::Bool(encoded[31] != 0)),
+            "address" => {
+                let mut address = Address::default();
+                address.0.copy_from_slice(&encoded[12..]);
+                Some(Value::Address(address))
+            }
+            _ => {
+                if let Some(size) = r#type.strip_prefix("bytes") {
+                    let size = parse_size(size, 1, 32)?;
+                    Some(Value::Bytes(encoded[..size].to_vec()))
+                } else if let Some(bits) = r#type.strip_prefix("uint") {
+                    parse_size(bits, 8, 256)?;
+                    Some(Value::Uint(U256(*encoded)))
+                } else if let Some(bits) = r#type.strip_prefix("int") {
+                    parse_size(bits, 8, 256)?;
+                    Some(Value::Int(U256(*encoded)))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// The members of `value`, for serializing it. None if any of them is
+    /// known only by its hash, like HashedBytes.
+    pub(crate) fn of_struct<T: StructType>(value: &T) -> Option<Self> {
+        struct Members(Option<BTreeMap<String, Value>>);
+
+        impl MemberVisitor for Members {
+            fn visit<T: MemberType>(&mut self, name: &'static str, value: &T) {
+                if let Some(members) = &mut self.0 {
+                    match value.dynamic_value() {
+                        Some(member) => {
+                            members.insert(name.to_owned(), member);
+                        }
+                        None => self.0 = None,
+                    }
+                }
+            }
+        }
+
+        let mut members = Members(Some(BTreeMap::new()));
+        value.visit_members(&mut members);
+        members.0.map(Value::Struct)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(value: u64) -> Self {
+        Value::Uint(value.into())
+    }
+}
+
+impl From<U256> for Value {
+    fn from(value: U256) -> Self {
+        Value::Uint(value)
+    }
+}
+
+impl From<Address> for Value {
+    fn from(value: Address) -> Self {
+        Value::Address(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct MemberDefinition {
+    pub name: String,
+    /// eg: uint256, Person or Person[]
+    pub r#type: String,
+}
+
+/// A struct type, eg: `Mail(Person from,Person to,string contents)`
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct TypeDefinition {
+    pub name: String,
+    pub members: Vec<MemberDefinition>,
+}
+
+impl TypeDefinition {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            members: Vec::new(),
+        }
+    }
+
+    /// Appends a member
+    pub fn member(mut self, name: impl Into<String>, r#type: impl Into<String>) -> Self {
+        self.members.push(MemberDefinition {
+            name: name.into(),
+            r#type: r#type.into(),
+        });
+        self
+    }
+}
+
+/// A set of struct types which may reference each other by name.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Types {
+    types: BTreeMap<String, TypeDefinition>,
+}
+
+impl Types {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds `definition`, checking its names. The types of its members are
+    /// checked when hashing, since they may be defined later.
+    pub fn insert(&mut self, definition: TypeDefinition) -> Result<(), Error> {
+        if !is_identifier(&definition.name) {
+            return Err(Error::InvalidIdentifier(definition.name));
+        }
+        let mut names = BTreeSet::new();
+        for member in &definition.members {
+            if !is_identifier(&member.name) {
+                return Err(Error::InvalidIdentifier(format!(
+                    "{}.{}",
+                    definition.name, member.name
+                )));
+            }
+            if !names.insert(&member.name) {
+                return Err(Error::DuplicateMember {
+                    type_name: definition.name.clone(),
+                    member: member.name.clone(),
+                });
+            }
+        }
+        if self.types.contains_key(&definition.name) {
+            return Err(Error::DuplicateTypeName(definition.name));
+        }
+        self.types.insert(definition.name.clone(), definition);
+        Ok(())
+    }
+
+    /// Parses an encodeType string, eg:
+    /// `Mail(Person from,Person to,string contents)Person(string name,address wallet)`,
+    /// into the name of its primary type and its definitions. The definitions
+    /// are checked as by [`insert`](Self::insert), and may be in any order.
+    pub fn parse_encode_type(encoded: &str) -> Result<(String, Self), Error> {
+        let malformed = || invalid(format!("Malformed encodeType {:?}", encoded));
+        let mut types = Types::new();
+        let mut primary = None;
+        let mut rest = encoded;
+        while !rest.is_empty() {
+            let open = rest.find('(').ok_or_else(malformed)?;
+            let close = rest.find(')').ok_or_else(malformed)?;
+            if close < open {
+                return Err(malformed());
+            }
+            let mut definition = TypeDefinition::new(&rest[..open]);
+            let members = &rest[open + 1..close];
+            if !members.is_empty() {
+                for member in members.split(',') {
+                    let (r#type, name) = member.split_once(' ').ok_or_else(malformed)?;
+                    definition = definition.member(name, r#type);
+                }
+            }
+            primary.get_or_insert_with(|| definition.name.clone());
+            types.insert(definition)?;
+            rest = &rest[close + 1..];
+        }
+        Ok((primary.ok_or_else(malformed)?, types))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&TypeDefinition> {
+        self.types.get(name)
+    }
+
+    /// The names of all types, in order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.types.keys().map(String::as_str)
+    }
+
+    fn definition(&self, name: &str) -> Result<&TypeDefinition, Error> {
+        self.get(name)
+            .ok_or_else(|| invalid(format!("Undefined type {}", name)))
+    }
+
+    /// Adds all struct types referenced by `type_name`, including itself,
+    /// checking that every member type exists.
+    fn dependencies<'a>(
+        &'a self,
+        type_name: &str,
+        found: &mut BTreeSet<&'a str>,
+    ) -> Result<(), Error> {
+        let definition = self.definition(type_name)?;
+        if !found.insert(&definition.name) {
+            return Ok(());
+        }
+        for member in &definition.members {
+            let base = member.r#type.split('[').next().unwrap();
+            if self.types.contains_key(base) {
+                self.dependencies(base, found)?;
+            } else if !is_atomic_or_dynamic(base) {
+                return Err(invalid(format!("Undefined type {}", member.r#type)));
+            }
+        }
+        Ok(())
+    }
+
+    /// (SPEC) encodeType of the struct type named `type_name`
+    pub fn encode_type(&self, type_name: &str) -> Result<String, Error> {
+        let mut dependencies = BTreeSet::new();
+        self.dependencies(type_name, &mut dependencies)?;
+        dependencies.remove(type_name);
+
+        let mut result = String::new();
+        for t in std::iter::once(type_name).chain(dependencies) {
+            let members: Vec<String> = self
+                .definition(t)?
+                .members
+                .iter()
+                .map(|m| format!("{} {}", m.r#type, m.name))
+                .collect();
+            result.push_str(&format!("{}({})", t, members.join(",")));
+        }
+        Ok(result)
+    }
+
+    pub fn type_hash(&self, type_name: &str) -> Result<Bytes32, Error> {
+        Ok(keccak(self.encode_type(type_name)?))
+    }
+
+    /// hashStruct of `value` as the struct type named `type_name`. Members of
+    /// `value` which the type does not have are ignored.
+    pub fn hash_struct(&self, type_name: &str, value: &Value) -> Result<Bytes32, Error> {
+        let members = match value {
+            Value::Struct(members) => members,
+            _ => return Err(invalid(format!("Expected a struct for {}", type_name))),
+        };
+        let mut buffer = Vec::new();
+        buffer.extend_from_slice(&self.type_hash(type_name)?);
+        for member in &self.definition(type_name)?.members {
+            let value = members
+                .get(&member.name)
+                .ok_or_else(|| invalid(format!("Missing member {}.{}", type_name, member.name)))?;
+            buffer.extend_from_slice(&self.encode_value(&member.r#type, value)?);
+        }
+        Ok(keccak(buffer))
+    }
+
+    fn encode_value(&self, r#type: &str, value: &Value) -> Result<Bytes32, Error> {
+        let mismatch = || invalid(format!("Invalid value for {}: {:?}", r#type, value));
+
+        // Arrays: keccak of the concatenated encodings of the elements
+        if let Some(inner) = r#type.strip_suffix(']') {
+            let open = inner
+                .rfind('[')
+                .ok_or_else(|| invalid(format!("Undefined type {}", r#type)))?;
+            let (element_type, length) = (&inner[..open], &inner[open + 1..]);
+            let elements = match value {
+                Value::Array(elements) => elements,
+                _ => return Err(mismatch()),
+            };
+            if !length.is_empty() && parse_digits(length) != Some(elements.len()) {
+                return Err(mismatch());
+            }
+            let mut hasher = Keccak::new();
+            for element in elements {
+                hasher.update(&self.encode_value(element_type, element)?);
+            }
+            return Ok(hasher.finalize());
+        }
+
+        if self.types.contains_key(r#type) {
+            return self.hash_struct(r#type, value);
+        }
+
+        let mut result = [0u8; 32];
+        match (r#type, value) {
+            ("string", Value::String(s)) => return Ok(keccak(s)),
+            ("bytes", Value::Bytes(bytes)) => return Ok(keccak(bytes)),
+            ("bool", Value::Bool(b)) => result[31] = *b as u8,
+            ("address", Value::Address(address)) => result = address.encode_data(),
+            (_, Value::Bytes(bytes)) if r#type.starts_with("bytes") => {
+                // Left aligned, padded with zeros at the end
+                let size = parse_size(&r#type[5..], 1, 32).ok_or_else(mismatch)?;
+                if bytes.len() != size {
+                    return Err(mismatch());
+                }
+                result[..size].copy_from_slice(bytes);
+            }
+            (_, Value::Uint(n)) if r#type.starts_with("uint") => {
+                let bits = parse_size(&r#type[4..], 8, 256).ok_or_else(mismatch)?;
+                if n.0[..32 - bits / 8].iter().any(|&b| b != 0) {
+                    return Err(mismatch());
+                }
+                result = n.0;
+            }
+            (_, Value::Int(n)) if r#type.starts_with("int") => {
+                let bits = parse_size(&r#type[3..], 8, 256).ok_or_else(mismatch)?;
+                // The bytes above the width must all be copies of the sign bit
+                let fill = if n.0[32 - bits / 8] & 0x80 != 0 {
+                    0xff
+                } else {
+                    0
+                };
+                if n.0[..32 - bits / 8].iter().any(|&b| b != fill) {
+                    return Err(mismatch());
+                }
+                result = n.0;
+            }
+            _ if is_atomic_or_dynamic(r#type) => return Err(mismatch()),
+            _ => return Err(invalid(format!("Undefined type {}", r#type))),
+        }
+        Ok(result)
+    }
+
+    /// The domain separator of `domain`, hashed as the `EIP712Domain` type,
+    /// which must be defined.
+    pub fn domain_separator(&self, domain: &Value) -> Result<DomainSeparator, Error> {
+        Ok(DomainSeparator::from_bytes(
+            &self.hash_struct("EIP712Domain", domain)?,
+        ))
+    }
+
+    /// The digest to sign for `value` as the struct type named `type_name`
+    pub fn sign_hash(
+        &self,
+        domain_separator: &DomainSeparator,
+        type_name: &str,
+        value: &Value,
+    ) -> Result<Bytes32, Error> {
+        Ok(crate::sign_hash_of(
+            domain_separator,
+            &self.hash_struct(type_name, value)?,
+        ))
+    }
+
+    #[cfg(feature = "sign")]
+    pub fn sign_typed(
+        &self,
+        domain_separator: &DomainSeparator,
+        type_name: &str,
+        value: &Value,
+        key: &crate::SecretKey,
+    ) -> Result<crate::Signature, Error> {
+        crate::sign_digest(&self.sign_hash(domain_separator, type_name, value)?, key)
+    }
+}
+
+/// A message with its types and domain: everything needed to sign or verify
+/// it.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TypedData {
+    /// Must include `EIP712Domain`
+    pub types: Types,
+    pub primary_type: String,
+    pub domain: Value,
+    pub message: Value,
+}
+
+impl TypedData {
+    pub fn domain_separator(&self) -> Result<DomainSeparator, Error> {
+        self.types.domain_separator(&self.domain)
+    }
+
+    /// hashStruct of the message. When the primary type is `EIP712Domain` the
+    /// domain itself is being signed, and this is the domain separator.
+    pub fn struct_hash(&self) -> Result<Bytes32, Error> {
+        if self.is_domain() {
+            return Ok(*self.domain_separator()?.as_bytes());
+        }
+        self.types.hash_struct(&self.primary_type, &self.message)
+    }
+
+    /// The digest to sign. Like eth-sig-util, signing the domain alone omits
+    /// the struct hash.
+    pub fn digest(&self) -> Result<Bytes32, Error> {
+        Ok(self.hashes()?.digest)
+    }
+
+    /// Every intermediate value of the encoding
+    pub fn hashes(&self) -> Result<Eip712Hashes, Error> {
+        let domain_separator = self.domain_separator()?;
+        let struct_hash = self.struct_hash()?;
+        let digest = if self.is_domain() {
+            let mut buffer = Vec::with_capacity(34);
+            buffer.extend_from_slice(b"\x19\x01");
+            buffer.extend_from_slice(domain_separator.as_bytes());
+            keccak(buffer)
+        } else {
+            crate::sign_hash_of(&domain_separator, &struct_hash)
+        };
+        Ok(Eip712Hashes {
+            type_hash: self.types.type_hash(&self.primary_type)?,
+            struct_hash,
+            domain_separator,
+            digest,
+        })
+    }
+
+    #[cfg(feature = "sign")]
+    pub fn sign(&self, key: &crate::SecretKey) -> Result<crate::Signature, Error> {
+        crate::sign_digest(&self.digest()?, key)
+    }
+
+    /// The address which produced `signature` over this message
+    #[cfg(feature = "verify")]
+    pub fn recover(&self, signature: &crate::Signature) -> Result<Address, Error> {
+        signature.recover(&self.digest()?)
+    }
+
+    fn is_domain(&self) -> bool {
+        self.primary_type == "EIP712Domain"
+    }
+}
+
+/// Parses decimal digits only, unlike `str::parse`, which allows a sign
+fn parse_digits(s: &str) -> Option<usize> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Parses the N of bytesN, uintN or intN, which is a multiple of 8 for the
+/// integers
+pub(crate) fn parse_size(s: &str, min: usize, max: usize) -> Option<usize> {
+    if s.starts_with('0') {
+        return None;
+    }
+    let size = parse_digits(s)?;
+    if size < min || size > max || (min == 8 && size % 8 != 0) {
+        return None;
+    }
+    Some(size)
+}
+
+pub(crate) fn is_atomic_or_dynamic(r#type: &str) -> bool {
+    match r#type {
+        "address" | "bool" | "string" | "bytes" => true,
+        _ => {
+            if let Some(size) = r#type.strip_prefix("bytes") {
+                parse_size(size, 1, 32).is_some()
+            } else if let Some(bits) = r#type.strip_prefix("uint") {
+                parse_size(bits, 8, 256).is_some()
+            } else if let Some(bits) = r#type.strip_prefix("int") {
+                parse_size(bits, 8, 256).is_some()
+            } else {
+                false
+            }
+        }
+    }
+}
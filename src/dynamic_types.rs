@@ -1,14 +1,190 @@
 use crate::prelude::*;
+use crate::summary::{Hints, SummaryValue};
+use std::borrow::Cow;
+use std::io::{self, Read};
 
-impl DynamicType for String {}
+// Borrowed strings allow messages to be built from constants without
+// allocating.
+macro_rules! impl_string {
+    ($($T:ty),+) => {
+        $(
+            impl DynamicType for $T {}
 
-impl MemberType for String {
-    const TYPE_NAME: &'static str = "string";
+            impl MemberType for $T {
+                fn type_name() -> Cow<'static, str> {
+                    Cow::Borrowed("string")
+                }
+                fn encode_data(&self) -> Bytes32 {
+                    keccak(&**self)
+                }
+                #[inline(always)]
+                fn add_members(&self, _builder: &mut TypeHashBuilder) {}
+                fn summarize(&self, _hints: &Hints) -> SummaryValue {
+                    SummaryValue::Text((**self).to_owned())
+                }
+                fn dynamic_value(&self) -> Option<DynamicValue> {
+                    Some(DynamicValue::String((**self).to_owned()))
+                }
+            }
+        )+
+    };
+}
+
+impl_string!(String, &'static str, Cow<'static, str>);
+
+/// A `string` member which is normalized to Unicode Normalization Form C
+/// before hashing. Requires the `nfc` feature.
+///
+/// Strings which look identical may be made of different code points, eg: "é"
+/// as one precomposed character or as "e" followed by a combining accent, and
+/// hash differently as plain Strings. Use this type for members entered by
+/// people, so that signer and verifier agree regardless of platform.
+#[cfg(feature = "nfc")]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct NfcString(pub String);
+
+#[cfg(feature = "nfc")]
+impl NfcString {
+    pub fn normalized(&self) -> String {
+        use unicode_normalization::UnicodeNormalization;
+        self.0.nfc().collect()
+    }
+}
+
+#[cfg(feature = "nfc")]
+impl DynamicType for NfcString {}
+
+#[cfg(feature = "nfc")]
+impl MemberType for NfcString {
+    fn type_name() -> Cow<'static, str> {
+        Cow::Borrowed("string")
+    }
+    fn encode_data(&self) -> Bytes32 {
+        keccak(self.normalized())
+    }
+    #[inline(always)]
+    fn add_members(&self, _builder: &mut TypeHashBuilder) {}
+    fn summarize(&self, _hints: &Hints) -> SummaryValue {
+        SummaryValue::Text(self.normalized())
+    }
+    fn dynamic_value(&self) -> Option<DynamicValue> {
+        Some(DynamicValue::String(self.normalized()))
+    }
+}
+
+/// A `bytes` member which is known only by its length and hash.
+///
+/// The encodeData of bytes is just their keccak256, so the contents can be
+/// streamed through the hasher once and discarded. This allows messages to
+/// commit to blobs far larger than memory.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct HashedBytes {
+    len: u64,
+    hash: Bytes32,
+}
+
+impl HashedBytes {
+    /// Reads `reader` to the end
+    pub fn from_reader<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut hasher = Keccak::new();
+        let mut buffer = [0u8; 8192];
+        let mut len = 0;
+        loop {
+            let read = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            hasher.update(&buffer[..read]);
+            len += read as u64;
+        }
+        Ok(Self {
+            len,
+            hash: hasher.finalize(),
+        })
+    }
+
+    /// Hashes the concatenation of `chunks`
+    pub fn from_chunks<I>(chunks: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let mut hasher = Keccak::new();
+        let mut len = 0;
+        for chunk in chunks {
+            hasher.update(chunk.as_ref());
+            len += chunk.as_ref().len() as u64;
+        }
+        Self {
+            len,
+            hash: hasher.finalize(),
+        }
+    }
+
+    /// The number of bytes hashed
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// keccak256 of the contents
+    pub fn hash(&self) -> &Bytes32 {
+        &self.hash
+    }
+}
+
+impl DynamicType for HashedBytes {}
+
+impl MemberType for HashedBytes {
+    fn type_name() -> Cow<'static, str> {
+        Cow::Borrowed("bytes")
+    }
     fn encode_data(&self) -> Bytes32 {
-        keccak(self)
+        self.hash
     }
     #[inline(always)]
     fn add_members(&self, _builder: &mut TypeHashBuilder) {}
+    fn summarize(&self, _hints: &Hints) -> SummaryValue {
+        SummaryValue::Text(format!(
+            "{} bytes with keccak256 0x{}",
+            self.len,
+            encode_hex(&self.hash)
+        ))
+    }
+}
+
+fn summarize_bytes(bytes: &[u8]) -> SummaryValue {
+    SummaryValue::Bytes(format!("0x{}", encode_hex(bytes)))
+}
+
+macro_rules! impl_bytes {
+    ($($T:ty),+) => {
+        $(
+            impl DynamicType for $T {}
+
+            impl MemberType for $T {
+                fn type_name() -> Cow<'static, str> {
+                    Cow::Borrowed("bytes")
+                }
+                fn encode_data(&self) -> Bytes32 {
+                    keccak(self)
+                }
+                #[inline(always)]
+                fn add_members(&self, _builder: &mut TypeHashBuilder) {}
+                fn summarize(&self, _hints: &Hints) -> SummaryValue {
+                    summarize_bytes(self)
+                }
+                fn dynamic_value(&self) -> Option<DynamicValue> {
+                    Some(DynamicValue::Bytes(self.to_vec()))
+                }
+            }
+        )+
+    };
 }
 
-// TODO: Vec<u8>
+impl_bytes!(Vec<u8>, &'static [u8]);
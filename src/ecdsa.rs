@@ -0,0 +1,129 @@
+// The secp256k1 operations behind signing and recovery, implemented with
+// libsecp256k1 or, with the `k256` feature, with k256. Both make low-s
+// RFC 6979 signatures, so the backend does not change any output.
+
+#[cfg(not(any(feature = "libsecp256k1", feature = "k256")))]
+compile_error!("The sign and verify features need a backend: enable libsecp256k1 or k256");
+
+/// An uncompressed public key without the leading 0x04 tag: x ‖ y
+pub(crate) type PublicKey = [u8; 64];
+
+pub(crate) use backend::*;
+
+#[cfg(not(feature = "k256"))]
+mod backend {
+    use super::PublicKey;
+    use crate::prelude::*;
+    use crate::Error;
+    use libsecp256k1::Message;
+
+    fn serialize(public_key: &libsecp256k1::PublicKey) -> PublicKey {
+        let mut result = [0u8; 64];
+        result.copy_from_slice(&public_key.serialize()[1..]);
+        result
+    }
+
+    /// r ‖ s and the parity of the signature over `digest`
+    #[cfg(feature = "sign")]
+    pub(crate) fn sign(digest: &Bytes32, key: &Bytes32) -> Result<([u8; 64], u8), Error> {
+        // The parsed copy of the key is cleared as soon as it has been used
+        let mut secret_key =
+            libsecp256k1::SecretKey::parse(key).map_err(|_| Error::InvalidPrivateKey)?;
+        let (signature, recovery_id) = libsecp256k1::sign(&Message::parse(digest), &secret_key);
+        secret_key.clear();
+        Ok((signature.serialize(), recovery_id.serialize()))
+    }
+
+    #[cfg(feature = "sign")]
+    pub(crate) fn public_key(key: &Bytes32) -> Result<PublicKey, Error> {
+        let mut secret_key =
+            libsecp256k1::SecretKey::parse(key).map_err(|_| Error::InvalidPrivateKey)?;
+        let public_key = libsecp256k1::PublicKey::from_secret_key(&secret_key);
+        secret_key.clear();
+        Ok(serialize(&public_key))
+    }
+
+    pub(crate) fn recover(digest: &Bytes32, rs: &[u8; 64], parity: u8) -> Result<PublicKey, Error> {
+        let signature =
+            libsecp256k1::Signature::parse_standard(rs).map_err(|_| Error::MalformedSignature)?;
+        let recovery_id = libsecp256k1::RecoveryId::parse(parity)
+            .map_err(|_| Error::InvalidRecoveryId(parity))?;
+        let public_key = libsecp256k1::recover(&Message::parse(digest), &signature, &recovery_id)
+            .map_err(|_| Error::RecoveryFailed)?;
+        Ok(serialize(&public_key))
+    }
+
+    /// A compressed or uncompressed SEC1 public key
+    #[cfg(feature = "sign")]
+    pub(crate) fn parse_public_key(bytes: &[u8]) -> Option<PublicKey> {
+        libsecp256k1::PublicKey::parse_slice(bytes, None)
+            .ok()
+            .map(|public_key| serialize(&public_key))
+    }
+}
+
+#[cfg(feature = "k256")]
+mod backend {
+    use super::PublicKey;
+    use crate::prelude::*;
+    use crate::Error;
+    #[cfg(feature = "sign")]
+    use k256::ecdsa::SigningKey;
+    use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+    fn serialize(public_key: &VerifyingKey) -> PublicKey {
+        let mut result = [0u8; 64];
+        result.copy_from_slice(&public_key.to_encoded_point(false).as_bytes()[1..]);
+        result
+    }
+
+    /// r ‖ s and the parity of the signature over `digest`
+    #[cfg(feature = "sign")]
+    pub(crate) fn sign(digest: &Bytes32, key: &Bytes32) -> Result<([u8; 64], u8), Error> {
+        // SigningKey zeroizes itself when dropped
+        let signing_key =
+            SigningKey::from_bytes(key.into()).map_err(|_| Error::InvalidPrivateKey)?;
+        // The signature is normalized to low-s
+        let (signature, recovery_id) = signing_key
+            .sign_prehash_recoverable(digest)
+            .map_err(|_| Error::InvalidPrivateKey)?;
+        let mut rs = [0u8; 64];
+        rs.copy_from_slice(&signature.to_bytes());
+        Ok((rs, recovery_id.is_y_odd() as u8))
+    }
+
+    #[cfg(feature = "sign")]
+    pub(crate) fn public_key(key: &Bytes32) -> Result<PublicKey, Error> {
+        let signing_key =
+            SigningKey::from_bytes(key.into()).map_err(|_| Error::InvalidPrivateKey)?;
+        Ok(serialize(signing_key.verifying_key()))
+    }
+
+    pub(crate) fn recover(digest: &Bytes32, rs: &[u8; 64], parity: u8) -> Result<PublicKey, Error> {
+        let mut signature = Signature::from_slice(rs).map_err(|_| Error::MalformedSignature)?;
+        let mut is_y_odd = match parity {
+            0 => false,
+            1 => true,
+            _ => return Err(Error::InvalidRecoveryId(parity)),
+        };
+        // k256 only recovers from low-s signatures. The high-s form of the
+        // same signature recovers to the same key with the other parity, and
+        // libsecp256k1 accepts it, so normalize it.
+        if let Some(normalized) = signature.normalize_s() {
+            signature = normalized;
+            is_y_odd = !is_y_odd;
+        }
+        let recovery_id = RecoveryId::new(is_y_odd, false);
+        let public_key = VerifyingKey::recover_from_prehash(digest, &signature, recovery_id)
+            .map_err(|_| Error::RecoveryFailed)?;
+        Ok(serialize(&public_key))
+    }
+
+    /// A compressed or uncompressed SEC1 public key
+    #[cfg(feature = "sign")]
+    pub(crate) fn parse_public_key(bytes: &[u8]) -> Option<PublicKey> {
+        VerifyingKey::from_sec1_bytes(bytes)
+            .ok()
+            .map(|public_key| serialize(&public_key))
+    }
+}
@@ -0,0 +1,153 @@
+//! ERC-7730 clear-signing descriptors. Requires the `erc7730` feature.
+//!
+//! A descriptor tells wallets how to display the fields of a message, instead
+//! of showing raw typed data. Generating it from the same types that are hashed
+//! keeps the display definitions in sync with the message code.
+
+use crate::prelude::*;
+use crate::Address;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+const SCHEMA: &str = "https://eips.ethereum.org/assets/eip-7730/erc7730-v1.schema.json";
+
+#[derive(Clone, Debug)]
+struct FieldHint {
+    label: String,
+    format: String,
+}
+
+/// User-provided display information that cannot be derived from the types.
+///
+/// Fields are addressed by their path from the primary type, using the member
+/// names joined with dots (eg: "from.wallet"). Fields without a hint are
+/// labeled with their member name and use the "raw" format.
+#[derive(Clone, Debug, Default)]
+pub struct DisplayHints {
+    owner: Option<String>,
+    intent: Option<String>,
+    deployments: Vec<(u64, Address)>,
+    fields: HashMap<String, FieldHint>,
+}
+
+impl DisplayHints {
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// The display name of the contract owner, eg: "The Graph"
+    pub fn owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = Some(owner.into());
+        self
+    }
+    /// A short description of what signing the message does
+    pub fn intent(mut self, intent: impl Into<String>) -> Self {
+        self.intent = Some(intent.into());
+        self
+    }
+    /// A verifying contract to which the descriptor applies
+    pub fn deployment(mut self, chain_id: u64, address: Address) -> Self {
+        self.deployments.push((chain_id, address));
+        self
+    }
+    /// `format` is one of the ERC-7730 field formats, eg: "addressName",
+    /// "tokenAmount", "date".
+    pub fn field(
+        mut self,
+        path: impl Into<String>,
+        label: impl Into<String>,
+        format: impl Into<String>,
+    ) -> Self {
+        let hint = FieldHint {
+            label: label.into(),
+            format: format.into(),
+        };
+        self.fields.insert(path.into(), hint);
+        self
+    }
+}
+
+/// Generates the ERC-7730 descriptor for messages of the type of `message`
+/// signed under domains of the type of `domain`.
+pub fn descriptor<D: StructType, T: StructType>(
+    domain: &D,
+    message: &T,
+    hints: &DisplayHints,
+) -> Value {
+    let message_types = collect_types(message);
+
+    let mut types = Map::new();
+    for t in collect_types(domain).iter().chain(message_types.iter()) {
+        let members: Vec<Value> = t
+            .members
+            .iter()
+            .map(|m| json!({ "name": m.name, "type": m.r#type }))
+            .collect();
+        types.insert(t.name.to_owned(), Value::Array(members));
+    }
+
+    let deployments: Vec<Value> = hints
+        .deployments
+        .iter()
+        .map(|(chain_id, address)| {
+            json!({ "chainId": chain_id, "address": address.to_checksum_string() })
+        })
+        .collect();
+
+    let mut fields = Vec::new();
+    add_fields(&message_types, &message_types[0], "", hints, &mut fields);
+
+    let mut format = Map::new();
+    if let Some(intent) = &hints.intent {
+        format.insert("intent".to_owned(), json!(intent));
+    }
+    format.insert("fields".to_owned(), Value::Array(fields));
+
+    let mut metadata = Map::new();
+    if let Some(owner) = &hints.owner {
+        metadata.insert("owner".to_owned(), json!(owner));
+    }
+
+    // Formats for EIP-712 messages are keyed by the encodeType of the
+    // primary type.
+    let mut formats = Map::new();
+    formats.insert(encode_type(message), Value::Object(format));
+
+    json!({
+        "$schema": SCHEMA,
+        "context": {
+            "eip712": {
+                "deployments": deployments,
+                "schemas": [{
+                    "primaryType": T::TYPE_NAME,
+                    "types": types,
+                }],
+            },
+        },
+        "metadata": metadata,
+        "display": {
+            "formats": formats,
+        },
+    })
+}
+
+/// Lists the leaf fields of `t`, descending into nested structs.
+fn add_fields(
+    types: &[EncodedType],
+    t: &EncodedType,
+    prefix: &str,
+    hints: &DisplayHints,
+    fields: &mut Vec<Value>,
+) {
+    for member in &t.members {
+        let path = format!("{}{}", prefix, member.name);
+        if let Some(inner) = types.iter().find(|t| t.name == member.r#type) {
+            add_fields(types, inner, &format!("{}.", path), hints, fields);
+            continue;
+        }
+        let (label, format) = match hints.fields.get(&path) {
+            Some(hint) => (hint.label.as_str(), hint.format.as_str()),
+            None => (member.name, "raw"),
+        };
+        fields.push(json!({ "path": path, "label": label, "format": format }));
+    }
+}
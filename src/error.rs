@@ -0,0 +1,197 @@
+use crate::prelude::*;
+use std::fmt;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Error), uniffi(flat_error))]
+#[non_exhaustive]
+pub enum Error {
+    /// The signature is not 65 bytes long, or r or s are out of range.
+    MalformedSignature,
+    /// The v byte of a signature is neither 27/28 nor 0/1.
+    InvalidRecoveryId(u8),
+    /// The private key is zero or not less than the curve order.
+    InvalidPrivateKey,
+    /// A PEM or DER private key which could not be parsed, or is not a
+    /// secp256k1 key.
+    InvalidKeyEncoding(String),
+    /// No key is available to sign as this address.
+    UnknownSigner(crate::Address),
+    /// No public key could be recovered from the signature and digest.
+    RecoveryFailed,
+    /// A domain salt of zero, which separates nothing.
+    InvalidSalt,
+    /// A message was signed under a different domain than expected.
+    DomainMismatch,
+    /// Distinct struct types with the same name are referenced by one type.
+    DuplicateTypeName(String),
+    /// A type or member name which is not a valid Solidity identifier. Member
+    /// names are given as Type.member.
+    InvalidIdentifier(String),
+    /// A struct type has two members with the same name.
+    DuplicateMember { type_name: String, member: String },
+    /// A struct type which does not implement visit_member_types, so its
+    /// type is only known given a value.
+    TypeNeedsValue(String),
+    /// A message of a different primary type than expected.
+    TypeMismatch { expected: String, found: String },
+    /// A valid signature, but from a different signer than expected.
+    SignerMismatch {
+        expected: crate::Address,
+        recovered: crate::Address,
+    },
+    /// A string which should have been a hex encoded address.
+    InvalidAddress(String),
+    /// A string which should have been hex encoded bytes of a given length.
+    InvalidHex(String),
+    /// Typed data which does not follow the structure of eth_signTypedData_v4,
+    /// or whose values do not match their declared types.
+    InvalidTypedData(String),
+    /// Input to code generation which could not be parsed, or which uses types
+    /// that have no Rust equivalent.
+    Codegen(String),
+    /// Fewer allowed signers have signed than required.
+    ThresholdNotMet { approvals: usize, threshold: usize },
+    /// A signer backend (eg: a remote KMS) failed to produce a signature.
+    Signer(String),
+    /// The operating system's credential store failed.
+    Keychain(String),
+    /// A signer refused to sign a message which its policy does not allow.
+    PolicyViolation(String),
+    /// A session key was used outside of its validity window.
+    SessionExpired,
+    /// A request could not be written to the audit log, so was not signed.
+    AuditLog(String),
+    /// A signer did not respond in time.
+    Timeout,
+    /// A signer was not called because it has been failing repeatedly.
+    CircuitOpen,
+    /// The user declined to sign on a hardware device.
+    Rejected,
+    /// A JSON-RPC provider failed to perform a call.
+    Provider(String),
+    /// A contract returned something which could not be decoded.
+    MalformedResponse(Vec<u8>),
+    /// A contract's ERC-5267 domain uses extensions, which define fields this
+    /// crate does not know how to hash.
+    UnsupportedExtensions(Vec<crate::U256>),
+    /// A contract's domain separator differs from the local one.
+    /// `differences` is empty unless the contract also implements ERC-5267,
+    /// which is the only way to read its domain fields.
+    DomainSeparatorMismatch {
+        contract: crate::Address,
+        local: crate::DomainSeparator,
+        on_chain: crate::DomainSeparator,
+        differences: Vec<FieldDifference>,
+    },
+}
+
+/// A domain field whose value differs between the local and the on-chain
+/// domain, formatted for display. `None` when the field is not in the domain.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FieldDifference {
+    pub field: &'static str,
+    pub local: Option<String>,
+    pub on_chain: Option<String>,
+}
+
+impl fmt::Display for FieldDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |value: &Option<String>| value.clone().unwrap_or_else(|| "(absent)".to_owned());
+        write!(
+            f,
+            "{}: local {}, on-chain {}",
+            self.field,
+            value(&self.local),
+            value(&self.on_chain)
+        )
+    }
+}
+
+impl Error {
+    /// Whether the same operation may succeed if attempted again.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Error::Signer(_) | Error::Timeout)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MalformedSignature => f.write_str("Malformed signature"),
+            Error::InvalidRecoveryId(v) => write!(f, "Invalid recovery id: {}", v),
+            Error::InvalidPrivateKey => f.write_str("Invalid private key"),
+            Error::InvalidKeyEncoding(s) => write!(f, "Invalid key encoding: {}", s),
+            Error::UnknownSigner(a) => write!(f, "No key for signer {}", a.to_checksum_string()),
+            Error::RecoveryFailed => f.write_str("Failed to recover public key from signature"),
+            Error::InvalidSalt => f.write_str("Domain salt must not be zero"),
+            Error::DomainMismatch => f.write_str("Signed under an unexpected domain"),
+            Error::DuplicateTypeName(name) => write!(f, "Types with duplicated name: {}", name),
+            Error::InvalidIdentifier(name) => write!(f, "Invalid identifier: {:?}", name),
+            Error::DuplicateMember { type_name, member } => {
+                write!(f, "Duplicated member {} in {}", member, type_name)
+            }
+            Error::TypeNeedsValue(name) => {
+                write!(f, "The type of {} can only be encoded given a value", name)
+            }
+            Error::TypeMismatch { expected, found } => {
+                write!(f, "Expected a {} message but found {}", expected, found)
+            }
+            Error::SignerMismatch {
+                expected,
+                recovered,
+            } => write!(
+                f,
+                "Signature is from {} but expected {}",
+                recovered.to_checksum_string(),
+                expected.to_checksum_string()
+            ),
+            Error::InvalidAddress(s) => write!(f, "Invalid address: {}", s),
+            Error::InvalidHex(s) => write!(f, "Invalid hex: {}", s),
+            Error::InvalidTypedData(s) => write!(f, "Invalid typed data: {}", s),
+            Error::ThresholdNotMet {
+                approvals,
+                threshold,
+            } => write!(f, "{} of {} required approvals", approvals, threshold),
+            Error::Codegen(s) => write!(f, "Code generation failed: {}", s),
+            Error::Signer(s) => write!(f, "Signer error: {}", s),
+            Error::Keychain(s) => write!(f, "Keychain error: {}", s),
+            Error::PolicyViolation(s) => write!(f, "Refused by signing policy: {}", s),
+            Error::SessionExpired => f.write_str("Session key is not valid at this time"),
+            Error::AuditLog(s) => write!(f, "Audit log failed: {}", s),
+            Error::Timeout => f.write_str("Signer timed out"),
+            Error::CircuitOpen => f.write_str("Signer unavailable after repeated failures"),
+            Error::Rejected => f.write_str("Signing was rejected on the device"),
+            Error::Provider(s) => write!(f, "Provider error: {}", s),
+            Error::MalformedResponse(data) => {
+                write!(
+                    f,
+                    "Malformed response from contract: 0x{}",
+                    encode_hex(data)
+                )
+            }
+            Error::UnsupportedExtensions(extensions) => {
+                f.write_str("Unsupported eip712Domain() extensions:")?;
+                for extension in extensions {
+                    write!(f, " {}", extension.to_decimal_string())?;
+                }
+                Ok(())
+            }
+            Error::DomainSeparatorMismatch {
+                contract,
+                local,
+                on_chain,
+                differences,
+            } => {
+                writeln!(f, "DOMAIN_SEPARATOR() mismatch for {}", contract.0.to_hex())?;
+                writeln!(f, "  local:    {}", local.as_bytes().to_hex())?;
+                write!(f, "  on-chain: {}", on_chain.as_bytes().to_hex())?;
+                for difference in differences {
+                    write!(f, "\n  {}", difference)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,63 @@
+//! Interop with ethers-rs. Requires the `ethers` feature.
+//!
+//! [`typed_data`] builds the ethers [`TypedData`] document for a message, for
+//! handing to ethers providers and signers, and [`Eip712Message`] implements
+//! ethers' [`Eip712`] trait with this crate's hashing.
+
+use crate::prelude::*;
+use crate::{DomainSeparator, Error};
+use ethers_core::types::transaction::eip712::{EIP712Domain, Eip712, TypedData};
+use serde_json::Value;
+
+/// The ethers TypedData for `message` signed under `domain`.
+///
+/// Fails if either type is invalid, if `domain` has members other than those of
+/// ethers' EIP712Domain, or if the message holds values known only by their
+/// hash, like HashedBytes.
+pub fn typed_data<D: StructType, T: StructType>(
+    domain: &D,
+    message: &T,
+) -> Result<TypedData, Error> {
+    let mut json = crate::json::typed_data_json(domain, message)?;
+    // ethers reads the salt as an array of numbers rather than as hex
+    if let Some(Value::String(salt)) = json["domain"].get("salt") {
+        let bytes: Vec<Value> = (2..salt.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&salt[i..i + 2], 16).unwrap().into())
+            .collect();
+        json["domain"]["salt"] = Value::Array(bytes);
+    }
+    serde_json::from_value(json).map_err(|e| Error::InvalidTypedData(e.to_string()))
+}
+
+/// A message with its ethers domain, implementing ethers' Eip712 trait.
+///
+/// The type hash is computed from `T::default()`, since ethers asks for it
+/// without a value.
+#[derive(Clone, Debug)]
+pub struct Eip712Message<T> {
+    pub domain: EIP712Domain,
+    pub message: T,
+}
+
+impl<T: StructType + Default> Eip712 for Eip712Message<T> {
+    type Error = Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Error> {
+        Ok(self.domain.clone())
+    }
+
+    fn type_hash() -> Result<[u8; 32], Error> {
+        Ok(type_hash(&T::default()))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Error> {
+        Ok(crate::hash_struct(&self.message))
+    }
+}
+
+impl From<&EIP712Domain> for DomainSeparator {
+    fn from(domain: &EIP712Domain) -> Self {
+        DomainSeparator::from_bytes(&domain.separator())
+    }
+}
@@ -0,0 +1,184 @@
+//! Typed values from eth_signTypedData_v4 JSON. Requires the `json` feature.
+//!
+//! With the `derive` feature, `#[derive(StructType)]` also implements
+//! [`FromJson`] and adds a `from_typed_data_message` constructor, so that a
+//! message signed by a wallet can be read straight into the Rust struct which
+//! hashes it.
+
+use crate::json::{decode_hex, encode_integer};
+use crate::prelude::*;
+use crate::{Address, Error};
+use serde_json::{Map, Value};
+use std::convert::TryFrom;
+
+/// A member type which can be read from its eth_signTypedData_v4 JSON form:
+/// integers as numbers or decimal or 0x-prefixed hex strings, and addresses
+/// and bytes as hex strings.
+pub trait FromJson: MemberType + Sized {
+    fn from_json(value: &Value) -> Result<Self, Error>;
+}
+
+fn mismatch<T: MemberType>(value: &Value) -> Error {
+    Error::InvalidTypedData(format!("Expected {}, found {}", T::type_name(), value))
+}
+
+impl FromJson for String {
+    fn from_json(value: &Value) -> Result<Self, Error> {
+        value
+            .as_str()
+            .map(str::to_owned)
+            .ok_or_else(|| mismatch::<Self>(value))
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: &Value) -> Result<Self, Error> {
+        value.as_bool().ok_or_else(|| mismatch::<Self>(value))
+    }
+}
+
+impl FromJson for Address {
+    fn from_json(value: &Value) -> Result<Self, Error> {
+        let bytes = <[u8; 20]>::try_from(Vec::<u8>::from_json(value)?)
+            .map_err(|_| mismatch::<Self>(value))?;
+        Ok(Address(bytes))
+    }
+}
+
+impl FromJson for Vec<u8> {
+    fn from_json(value: &Value) -> Result<Self, Error> {
+        value
+            .as_str()
+            .and_then(decode_hex)
+            .ok_or_else(|| mismatch::<Self>(value))
+    }
+}
+
+impl<const N: usize> FromJson for [u8; N]
+where
+    [u8; N]: MemberType,
+{
+    fn from_json(value: &Value) -> Result<Self, Error> {
+        Self::try_from(Vec::<u8>::from_json(value)?).map_err(|_| mismatch::<Self>(value))
+    }
+}
+
+impl FromJson for U256 {
+    fn from_json(value: &Value) -> Result<Self, Error> {
+        encode_integer(value, 256, false)
+            .map(U256)
+            .ok_or_else(|| mismatch::<Self>(value))
+    }
+}
+
+macro_rules! impl_from_json_int {
+    ($($T:ident: $bits:expr, $signed:expr;)+) => {
+        $(
+            impl FromJson for $T {
+                fn from_json(value: &Value) -> Result<Self, Error> {
+                    // In range, so the low bytes are the two's complement
+                    let bytes = encode_integer(value, $bits, $signed)
+                        .ok_or_else(|| mismatch::<Self>(value))?;
+                    let mut low = [0u8; $bits / 8];
+                    low.copy_from_slice(&bytes[32 - $bits / 8..]);
+                    Ok($T::from_be_bytes(low))
+                }
+            }
+        )+
+    }
+}
+
+impl_from_json_int! {
+    u16: 16, false;
+    u32: 32, false;
+    u64: 64, false;
+    u128: 128, false;
+    i8: 8, true;
+    i16: 16, true;
+    i32: 32, true;
+    i64: 64, true;
+    i128: 128, true;
+}
+
+macro_rules! impl_from_json_uint {
+    ($($T:ident)+) => {
+        $(
+            impl FromJson for crate::$T {
+                fn from_json(value: &Value) -> Result<Self, Error> {
+                    encode_integer(value, crate::$T::BITS as usize, false)
+                        .and_then(|bytes| crate::$T::new(U256(bytes)))
+                        .ok_or_else(|| mismatch::<Self>(value))
+                }
+            }
+        )+
+    }
+}
+
+impl_from_json_uint! {
+    Uint8 Uint16 Uint24 Uint32 Uint40 Uint48 Uint56 Uint64
+    Uint72 Uint80 Uint88 Uint96 Uint104 Uint112 Uint120 Uint128
+    Uint136 Uint144 Uint152 Uint160 Uint168 Uint176 Uint184 Uint192
+    Uint200 Uint208 Uint216 Uint224 Uint232 Uint240 Uint248
+}
+
+fn elements<T: FromJson>(value: &Value) -> Result<Vec<T>, Error> {
+    value
+        .as_array()
+        .ok_or_else(|| Error::InvalidTypedData(format!("Expected an array, found {}", value)))?
+        .iter()
+        .map(T::from_json)
+        .collect()
+}
+
+impl<T: FromJson + Default> FromJson for Vec<T> {
+    fn from_json(value: &Value) -> Result<Self, Error> {
+        elements(value)
+    }
+}
+
+impl<T: FromJson, const N: usize> FromJson for [T; N]
+where
+    [T; N]: MemberType,
+{
+    fn from_json(value: &Value) -> Result<Self, Error> {
+        Self::try_from(elements(value)?).map_err(|_| mismatch::<Self>(value))
+    }
+}
+
+/// The members of a struct, checking that `value` has no others. Used by the
+/// derive.
+#[doc(hidden)]
+pub fn struct_members<'a>(
+    value: &'a Value,
+    type_name: &str,
+    members: &[&str],
+) -> Result<&'a Map<String, Value>, Error> {
+    let object = value
+        .as_object()
+        .ok_or_else(|| Error::InvalidTypedData(format!("Expected an object for {}", type_name)))?;
+    if let Some(unknown) = object.keys().find(|k| !members.contains(&k.as_str())) {
+        return Err(Error::InvalidTypedData(format!(
+            "Unknown member {}.{}",
+            type_name, unknown
+        )));
+    }
+    Ok(object)
+}
+
+/// Reads one member of a struct. Used by the derive.
+#[doc(hidden)]
+pub fn struct_member<T: FromJson>(
+    members: &Map<String, Value>,
+    type_name: &str,
+    member: &str,
+) -> Result<T, Error> {
+    let value = members.get(member).ok_or_else(|| {
+        Error::InvalidTypedData(format!("Missing member {}.{}", type_name, member))
+    })?;
+    T::from_json(value).map_err(|e| match e {
+        Error::InvalidTypedData(message) => {
+            Error::InvalidTypedData(format!("{}.{}: {}", type_name, member, message))
+        }
+        e => e,
+    })
+}
@@ -0,0 +1,161 @@
+//! Fuzzing harnesses. Requires the `fuzzing` feature.
+//!
+//! Each function takes arbitrary bytes from the fuzzer, drives one of the
+//! crate's input surfaces with them and panics if an invariant is broken. The
+//! cargo-fuzz targets in the repository's `fuzz` directory are thin wrappers
+//! around these, and downstream crates may call them from their own targets.
+
+use crate::dynamic::{Types, Value};
+use crate::prelude::*;
+use crate::*;
+
+struct FuzzMessage {
+    contents: String,
+    tag: Bytes32,
+}
+
+impl StructType for FuzzMessage {
+    const TYPE_NAME: &'static str = "FuzzMessage";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("contents", &self.contents);
+        visitor.visit("tag", &self.tag);
+    }
+}
+
+fn message(data: &[u8]) -> FuzzMessage {
+    let mut tag = [0u8; 32];
+    let len = data.len().min(32);
+    tag[..len].copy_from_slice(&data[..len]);
+    FuzzMessage {
+        contents: String::from_utf8_lossy(data).into_owned(),
+        tag,
+    }
+}
+
+/// Hashes a message built from `data`, checking that the runtime types of
+/// the [`dynamic`](crate::dynamic) module, parsed from the message's
+/// encodeType, hash it the same way.
+pub fn hash_struct(data: &[u8]) {
+    let message = message(data);
+    let (primary, types) = Types::parse_encode_type(&crate::encode_type(&message)).unwrap();
+    let mut members = std::collections::BTreeMap::new();
+    members.insert(
+        "contents".to_owned(),
+        Value::String(message.contents.clone()),
+    );
+    members.insert("tag".to_owned(), Value::Bytes(message.tag.to_vec()));
+    assert_eq!(
+        types.hash_struct(&primary, &Value::Struct(members)),
+        Ok(crate::hash_struct(&message))
+    );
+}
+
+/// Parses `data` as an encodeType string. Whatever parses and encodes must
+/// encode to itself when parsed again, with the same definitions.
+pub fn encode_type(data: &[u8]) {
+    let encoded = match std::str::from_utf8(data) {
+        Ok(encoded) => encoded,
+        Err(_) => return,
+    };
+    let (primary, types) = match Types::parse_encode_type(encoded) {
+        Ok(parsed) => parsed,
+        Err(_) => return,
+    };
+    let canonical = match types.encode_type(&primary) {
+        Ok(canonical) => canonical,
+        Err(_) => return,
+    };
+    let (reparsed_primary, reparsed) = Types::parse_encode_type(&canonical).unwrap();
+    assert_eq!(reparsed_primary, primary);
+    assert_eq!(reparsed.encode_type(&primary), Ok(canonical));
+    for name in reparsed.names() {
+        assert_eq!(reparsed.get(name), types.get(name));
+    }
+}
+
+/// Uses the first 32 bytes of `data` as a private key and signs a message
+/// built from the rest. Keys out of range must be rejected with an error.
+pub fn sign_typed(data: &[u8]) {
+    if data.len() < 32 {
+        return;
+    }
+    let (key, rest) = data.split_at(32);
+    let mut private_key = [0u8; 32];
+    private_key.copy_from_slice(key);
+    let domain_separator = DomainSeparator::from_bytes(&keccak(rest));
+
+    let message = message(rest);
+    let result = SecretKey::from_bytes(&private_key)
+        .and_then(|key| crate::sign_typed(&domain_separator, &message, &key));
+    // secp256k1 secret keys must be in [1, n)
+    let zero = [0u8; 32];
+    if private_key == zero {
+        assert!(result.is_err());
+    }
+    // A successful signature must be recoverable
+    if let Ok(signature) = result {
+        let digest = sign_hash(&domain_separator, &message);
+        signature.recover(&digest).unwrap();
+    }
+}
+
+/// Parses the first 65 bytes of `data` as a signature and recovers the signer
+/// of the keccak of the rest. Parsing and recovery may fail, but not panic, and
+/// a parsed signature must round-trip.
+pub fn recover(data: &[u8]) {
+    if data.len() < 65 {
+        assert!(Signature::from_bytes(data).is_err());
+        return;
+    }
+    let (signature, rest) = data.split_at(65);
+    if let Ok(signature) = Signature::from_bytes(signature) {
+        assert_eq!(
+            Signature::from_bytes(&signature.to_bytes()).unwrap(),
+            signature
+        );
+        let _ = signature.recover(&keccak(rest));
+    }
+}
+
+/// Treats `data` as eth_signTypedData_v4 JSON and verifies a fixed signature
+/// against it. Malformed documents must be rejected with an error.
+pub fn verify_typed_json(data: &[u8]) {
+    if let Ok(json) = std::str::from_utf8(data) {
+        let signature = format!("0x{}1b", "11".repeat(64));
+        let signer = format!("0x{}", "22".repeat(20));
+        let _ = crate::verify_typed_json(json, &signature, &signer);
+    }
+}
+
+/// Treats `data` as eth_signTypedData_v4 JSON. Whatever it hashes to, the
+/// digest must be derived from the domain separator and struct hash.
+pub fn hash_typed_json(data: &[u8]) {
+    let json = match std::str::from_utf8(data) {
+        Ok(json) => json,
+        Err(_) => return,
+    };
+    if let Ok(hashes) = crate::hash_typed_json(json) {
+        if hashes.struct_hash != *hashes.domain_separator.as_bytes() {
+            assert_eq!(
+                hashes.digest,
+                sign_hash_of(&hashes.domain_separator, &hashes.struct_hash)
+            );
+        }
+    }
+}
+
+/// Treats `data` as Solidity source and generates Rust from its structs.
+/// Malformed source must be rejected with an error.
+pub fn codegen_solidity(data: &[u8]) {
+    if let Ok(source) = std::str::from_utf8(data) {
+        let _ = crate::codegen::rust_from_solidity(source);
+    }
+}
+
+/// Treats `data` as a contract ABI and generates Rust from its structs.
+/// Malformed ABIs must be rejected with an error.
+pub fn codegen_abi(data: &[u8]) {
+    if let Ok(json) = std::str::from_utf8(data) {
+        let _ = crate::codegen::rust_from_abi(json);
+    }
+}
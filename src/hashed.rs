@@ -0,0 +1,91 @@
+use crate::prelude::*;
+use crate::summary::{Hints, SummaryValue};
+use crate::*;
+use std::any::TypeId;
+use std::borrow::Cow;
+use std::ops::Deref;
+
+/// A message with its struct hash computed once, up front.
+///
+/// The message cannot be modified, so the hash can be reused for every
+/// digest, signature and recovery, eg: when many parties sign the same
+/// message. As a member of another struct it is also not re-hashed.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Hashed<T> {
+    value: T,
+    struct_hash: Bytes32,
+}
+
+impl<T: StructType> Hashed<T> {
+    pub fn new(value: T) -> Self {
+        let struct_hash = hash_struct(&value);
+        Self { value, struct_hash }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// hashStruct(value)
+    pub fn struct_hash(&self) -> &Bytes32 {
+        &self.struct_hash
+    }
+
+    pub fn sign_hash(&self, domain_separator: &DomainSeparator) -> Bytes32 {
+        sign_hash_of(domain_separator, &self.struct_hash)
+    }
+
+    pub fn signing_request(&self, domain_separator: &DomainSeparator) -> SigningRequest {
+        SigningRequest::from_struct_hash(domain_separator, &self.value, self.struct_hash)
+    }
+
+    pub fn sign<S: Signer>(
+        &self,
+        signer: &S,
+        domain_separator: &DomainSeparator,
+    ) -> Result<Signature, Error> {
+        signer.sign(&self.signing_request(domain_separator))
+    }
+
+    #[cfg(feature = "verify")]
+    pub fn recover(
+        &self,
+        domain_separator: &DomainSeparator,
+        signature: &Signature,
+    ) -> Result<Address, Error> {
+        signature.recover(&self.sign_hash(domain_separator))
+    }
+}
+
+impl<T> Deref for Hashed<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: StructType> MemberType for Hashed<T> {
+    fn type_name() -> Cow<'static, str> {
+        Cow::Borrowed(T::TYPE_NAME)
+    }
+    fn encode_data(&self) -> Bytes32 {
+        self.struct_hash
+    }
+    fn add_members(&self, builder: &mut TypeHashBuilder) {
+        self.value.add_members(builder)
+    }
+    fn add_member_types(builder: &mut TypeHashBuilder) {
+        T::add_member_types(builder)
+    }
+    fn summarize(&self, hints: &Hints) -> SummaryValue {
+        self.value.summarize(hints)
+    }
+    fn dynamic_value(&self) -> Option<DynamicValue> {
+        self.value.dynamic_value()
+    }
+    fn type_identity() -> TypeId {
+        T::struct_identity()
+    }
+}
+
+impl<T: StructType> ReferenceType for Hashed<T> {}
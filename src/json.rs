@@ -0,0 +1,352 @@
+//! eth_signTypedData_v4 JSON. Requires the `json` feature.
+//!
+//! Wallets sign typed data described at runtime by a JSON document of the form
+//! `{types, primaryType, domain, message}`. This module parses such documents
+//! into a [`TypedData`] to hash them without a corresponding Rust struct, and
+//! builds them from Rust structs for sending to wallets.
+
+use crate::dynamic::{parse_size, TypeDefinition, TypedData, Types, Value as DynamicValue};
+use crate::prelude::*;
+use crate::type_hash::try_collect_types;
+use crate::{Address, DomainSeparator, Eip712Hashes, Error, Signature};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidTypedData(message.into())
+}
+
+/// Whether `signature` over the typed data in `json` was produced by
+/// `expected_signer`.
+///
+/// A valid signature from someone else gives `Ok(false)`, while malformed
+/// inputs are errors. The signature is the 0x-prefixed hex of r ‖ s ‖ v, as
+/// returned by eth_signTypedData_v4.
+pub fn verify_typed_json(
+    json: &str,
+    signature: &str,
+    expected_signer: &str,
+) -> Result<bool, Error> {
+    let digest = TypedData::from_json(json)?.digest()?;
+
+    let signature = decode_hex(signature).ok_or(Error::MalformedSignature)?;
+    let signature = Signature::from_bytes(&signature)?;
+
+    let expected = decode_hex(expected_signer)
+        .filter(|bytes| bytes.len() == 20)
+        .ok_or_else(|| Error::InvalidAddress(expected_signer.to_owned()))?;
+    let mut expected_signer = Address::default();
+    expected_signer.0.copy_from_slice(&expected);
+
+    Ok(signature.recover(&digest)? == expected_signer)
+}
+
+/// Hashes the typed data in `json`, as sent to eth_signTypedData_v4.
+///
+/// When the primary type is `EIP712Domain` the domain itself is being signed,
+/// so the struct hash is the domain separator and, like eth-sig-util, the
+/// digest omits it.
+pub fn hash_typed_json(json: &str) -> Result<Eip712Hashes, Error> {
+    TypedData::from_json(json)?.hashes()
+}
+
+/// The domain separator of a domain given as JSON, eg:
+/// `{"name": "Ether Mail", "version": "1", "chainId": 1}`.
+///
+/// As with [`PartialDomain`](crate::PartialDomain), the EIP712Domain type
+/// holds only the fields which are present.
+pub fn domain_separator_json(domain: &str) -> Result<DomainSeparator, Error> {
+    let domain: Map<String, Value> =
+        serde_json::from_str(domain).map_err(|e| invalid(e.to_string()))?;
+    let fields = [
+        ("name", "string"),
+        ("version", "string"),
+        ("chainId", "uint256"),
+        ("verifyingContract", "address"),
+        ("salt", "bytes32"),
+    ];
+    if let Some(unknown) = domain.keys().find(|k| fields.iter().all(|(f, _)| f != k)) {
+        return Err(invalid(format!("Unknown domain field {}", unknown)));
+    }
+    let members: Vec<Value> = fields
+        .iter()
+        .filter(|(name, _)| domain.contains_key(*name))
+        .map(|(name, r#type)| json!({ "name": name, "type": r#type }))
+        .collect();
+    let typed_data = json!({
+        "types": { "EIP712Domain": members },
+        "primaryType": "EIP712Domain",
+        "domain": domain,
+        "message": {},
+    });
+    TypedData::from_json_value(&typed_data)?.domain_separator()
+}
+
+/// The eth_signTypedData_v4 JSON for `message` signed under `domain`, which
+/// hashes to the same digest as [`sign_hash`](crate::sign_hash).
+///
+/// Fails if either type is invalid, or if a value is known only by its hash,
+/// like HashedBytes.
+pub fn typed_data_json<D: StructType, T: StructType>(
+    domain: &D,
+    message: &T,
+) -> Result<Value, Error> {
+    let mut types = Map::new();
+    for t in try_collect_types(domain)?
+        .iter()
+        .chain(&try_collect_types(message)?)
+    {
+        let members: Vec<Value> = t
+            .members
+            .iter()
+            .map(|m| json!({ "name": m.name, "type": m.r#type }))
+            .collect();
+        types.insert(t.name.to_owned(), Value::Array(members));
+    }
+    // Values known only by their hash, like HashedBytes, have no JSON form
+    let value = |value: Option<DynamicValue>| {
+        value.map(|value| to_json(&value)).ok_or_else(|| {
+            invalid(format!(
+                "{} has values which cannot be represented in JSON",
+                T::TYPE_NAME
+            ))
+        })
+    };
+    let json = json!({
+        "types": types,
+        "primaryType": T::TYPE_NAME,
+        "domain": value(DynamicValue::of_struct(domain))?,
+        "message": value(DynamicValue::of_struct(message))?,
+    });
+    Ok(json)
+}
+
+impl TypedData {
+    /// Parses eth_signTypedData_v4 JSON
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let json: Value = serde_json::from_str(json).map_err(|e| invalid(e.to_string()))?;
+        Self::from_json_value(&json)
+    }
+
+    pub fn from_json_value(json: &Value) -> Result<Self, Error> {
+        let mut types = Types::new();
+        for (name, members) in json["types"]
+            .as_object()
+            .ok_or_else(|| invalid("Missing types"))?
+        {
+            let members = members
+                .as_array()
+                .ok_or_else(|| invalid(format!("Malformed type {}", name)))?;
+            let mut definition = TypeDefinition::new(name.as_str());
+            for member in members {
+                match (member["name"].as_str(), member["type"].as_str()) {
+                    (Some(name), Some(r#type)) => definition = definition.member(name, r#type),
+                    _ => return Err(invalid(format!("Malformed member of {}", name))),
+                }
+            }
+            types.insert(definition)?;
+        }
+        let primary_type = json["primaryType"]
+            .as_str()
+            .ok_or_else(|| invalid("Missing primaryType"))?;
+
+        let domain = from_json(&types, "EIP712Domain", &json["domain"])?;
+        // When signing the domain alone the message is unused
+        let message = if primary_type == "EIP712Domain" {
+            DynamicValue::Struct(Default::default())
+        } else {
+            from_json(&types, primary_type, &json["message"])?
+        };
+        Ok(Self {
+            types,
+            primary_type: primary_type.to_owned(),
+            domain,
+            message,
+        })
+    }
+
+    /// The eth_signTypedData_v4 JSON. Integers are decimal strings, and
+    /// addresses and bytes are 0x-prefixed hex.
+    pub fn to_json(&self) -> Value {
+        let mut types = Map::new();
+        for name in self.types.names() {
+            let members: Vec<Value> = self
+                .types
+                .get(name)
+                .unwrap()
+                .members
+                .iter()
+                .map(|m| json!({ "name": m.name, "type": m.r#type }))
+                .collect();
+            types.insert(name.to_owned(), Value::Array(members));
+        }
+        json!({
+            "types": types,
+            "primaryType": self.primary_type,
+            "domain": to_json(&self.domain),
+            "message": to_json(&self.message),
+        })
+    }
+}
+
+/// Converts `value` as an instance of `type`, checking its shape but not
+/// integer ranges or byte lengths, which are checked when hashing.
+fn from_json(types: &Types, r#type: &str, value: &Value) -> Result<DynamicValue, Error> {
+    let mismatch = || invalid(format!("Invalid value for {}: {}", r#type, value));
+
+    if let Some(inner) = r#type.strip_suffix(']') {
+        let open = inner
+            .rfind('[')
+            .ok_or_else(|| invalid(format!("Undefined type {}", r#type)))?;
+        let element_type = &inner[..open];
+        let elements = value.as_array().ok_or_else(mismatch)?;
+        return elements
+            .iter()
+            .map(|element| from_json(types, element_type, element))
+            .collect::<Result<_, _>>()
+            .map(DynamicValue::Array);
+    }
+
+    if let Some(definition) = types.get(r#type) {
+        let value = value
+            .as_object()
+            .ok_or_else(|| invalid(format!("Expected an object for {}", r#type)))?;
+        let mut members = BTreeMap::new();
+        for member in &definition.members {
+            let member_value = value
+                .get(&member.name)
+                .ok_or_else(|| invalid(format!("Missing member {}.{}", r#type, member.name)))?;
+            members.insert(
+                member.name.clone(),
+                from_json(types, &member.r#type, member_value)?,
+            );
+        }
+        return Ok(DynamicValue::Struct(members));
+    }
+
+    Ok(match r#type {
+        "string" => DynamicValue::String(value.as_str().ok_or_else(mismatch)?.to_owned()),
+        "bool" => DynamicValue::Bool(value.as_bool().ok_or_else(mismatch)?),
+        "address" => {
+            let bytes = value.as_str().and_then(decode_hex).ok_or_else(mismatch)?;
+            if bytes.len() != 20 {
+                return Err(mismatch());
+            }
+            let mut address = Address::default();
+            address.0.copy_from_slice(&bytes);
+            DynamicValue::Address(address)
+        }
+        _ if r#type.starts_with("bytes") => {
+            DynamicValue::Bytes(value.as_str().and_then(decode_hex).ok_or_else(mismatch)?)
+        }
+        _ => {
+            if let Some(bits) = r#type.strip_prefix("uint") {
+                let bits = parse_size(bits, 8, 256).ok_or_else(mismatch)?;
+                DynamicValue::Uint(U256(
+                    encode_integer(value, bits, false).ok_or_else(mismatch)?,
+                ))
+            } else if let Some(bits) = r#type.strip_prefix("int") {
+                let bits = parse_size(bits, 8, 256).ok_or_else(mismatch)?;
+                DynamicValue::Int(U256(
+                    encode_integer(value, bits, true).ok_or_else(mismatch)?,
+                ))
+            } else {
+                return Err(invalid(format!("Undefined type {}", r#type)));
+            }
+        }
+    })
+}
+
+fn to_json(value: &DynamicValue) -> Value {
+    match value {
+        DynamicValue::Uint(n) => Value::String(n.to_string()),
+        DynamicValue::Int(n) if n.0[0] & 0x80 != 0 => {
+            Value::String(format!("-{}", U256(negate(n.0))))
+        }
+        DynamicValue::Int(n) => Value::String(n.to_string()),
+        DynamicValue::Address(address) => Value::String(address.to_checksum_string()),
+        DynamicValue::Bytes(bytes) => Value::String(format!("0x{}", hex::encode(bytes))),
+        DynamicValue::String(s) => Value::String(s.clone()),
+        DynamicValue::Bool(b) => Value::Bool(*b),
+        DynamicValue::Array(elements) => Value::Array(elements.iter().map(to_json).collect()),
+        DynamicValue::Struct(members) => Value::Object(
+            members
+                .iter()
+                .map(|(name, value)| (name.clone(), to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Accepts hex with or without the 0x prefix
+pub(crate) fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    hex::decode(s).ok()
+}
+
+/// Encodes a JSON number, or a decimal or 0x-prefixed hex string, as a 256 bit
+/// two's complement integer, checking that it fits in `bits`.
+pub(crate) fn encode_integer(value: &Value, bits: usize, signed: bool) -> Option<Bytes32> {
+    let (negative, magnitude) = match value {
+        Value::Number(n) => {
+            if let Some(n) = n.as_u64() {
+                (false, U256::from(n).0)
+            } else {
+                let n = n.as_i64()?;
+                (n < 0, U256::from(n.unsigned_abs()).0)
+            }
+        }
+        Value::String(s) => {
+            let (negative, s) = match s.strip_prefix('-') {
+                Some(s) => (true, s),
+                None => (false, s.as_str()),
+            };
+            let magnitude = if s.starts_with("0x") {
+                U256::from_hex_str(s)?
+            } else {
+                U256::from_dec_str(s)?
+            };
+            (negative, magnitude.0)
+        }
+        _ => return None,
+    };
+
+    let is_zero = magnitude == [0u8; 32];
+    if negative && !is_zero {
+        if !signed {
+            return None;
+        }
+        // -2^(bits-1) is the smallest value
+        let limit = power_of_two(bits - 1);
+        if magnitude > limit {
+            return None;
+        }
+        Some(negate(magnitude))
+    } else {
+        let limit_bits = if signed { bits - 1 } else { bits };
+        if limit_bits < 256 && magnitude >= power_of_two(limit_bits) {
+            return None;
+        }
+        Some(magnitude)
+    }
+}
+
+fn power_of_two(exponent: usize) -> Bytes32 {
+    let mut result = [0u8; 32];
+    result[31 - exponent / 8] = 1 << (exponent % 8);
+    result
+}
+
+fn negate(mut value: Bytes32) -> Bytes32 {
+    for byte in value.iter_mut() {
+        *byte = !*byte;
+    }
+    for byte in value.iter_mut().rev() {
+        let (sum, overflow) = byte.overflowing_add(1);
+        *byte = sum;
+        if !overflow {
+            break;
+        }
+    }
+    value
+}
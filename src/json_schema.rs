@@ -0,0 +1,104 @@
+//! JSON Schemas for the `message` object of eth_signTypedData_v4 requests.
+//! Requires the `json-schema` feature.
+//!
+//! The schema follows the value formats accepted by wallets: addresses and
+//! fixed bytes as 0x-prefixed hex of the exact length, integers as JSON
+//! numbers or as decimal or 0x-prefixed hex strings. Integer strings are
+//! limited by their number of digits, which approximates their range.
+
+use crate::prelude::*;
+use crate::type_hash::collect_types;
+use serde_json::{json, Map, Value};
+
+const SCHEMA: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// The schema of the message `value`, and of any value of its type.
+pub fn json_schema<T: StructType>(value: &T) -> Value {
+    let mut defs = Map::new();
+    for t in collect_types(value) {
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        for member in &t.members {
+            properties.insert(member.name.to_owned(), member_schema(&member.r#type));
+            required.push(Value::from(member.name));
+        }
+        defs.insert(
+            t.name.to_owned(),
+            json!({
+                "type": "object",
+                "properties": properties,
+                "required": required,
+                "additionalProperties": false,
+            }),
+        );
+    }
+    json!({
+        "$schema": SCHEMA,
+        "$ref": format!("#/$defs/{}", T::TYPE_NAME),
+        "$defs": defs,
+    })
+}
+
+fn hex_string(pattern: String) -> Value {
+    json!({ "type": "string", "pattern": format!("^0x{}$", pattern) })
+}
+
+fn member_schema(r#type: &str) -> Value {
+    if let Some(element) = r#type.strip_suffix(']') {
+        let open = element.rfind('[').unwrap();
+        let items = member_schema(&element[..open]);
+        return match element[open + 1..].parse::<u64>() {
+            Ok(len) => json!({ "type": "array", "items": items, "minItems": len, "maxItems": len }),
+            Err(_) => json!({ "type": "array", "items": items }),
+        };
+    }
+    match r#type {
+        "address" => hex_string("[0-9a-fA-F]{40}".to_owned()),
+        "bytes" => hex_string("([0-9a-fA-F]{2})*".to_owned()),
+        "string" => json!({ "type": "string" }),
+        "bool" => json!({ "type": "boolean" }),
+        _ => {
+            if let Some(size) = r#type.strip_prefix("bytes") {
+                let size: usize = size.parse().unwrap();
+                return hex_string(format!("[0-9a-fA-F]{{{}}}", size * 2));
+            }
+            if let Some(bits) = r#type.strip_prefix("uint") {
+                return integer_schema(bits.parse().unwrap(), false);
+            }
+            if let Some(bits) = r#type.strip_prefix("int") {
+                return integer_schema(bits.parse().unwrap(), true);
+            }
+            json!({ "$ref": format!("#/$defs/{}", r#type) })
+        }
+    }
+}
+
+fn integer_schema(bits: u32, signed: bool) -> Value {
+    let magnitude_bits = if signed { bits - 1 } else { bits };
+    // Digits needed for 2^bits - 1, rounded up
+    let decimal_digits = (f64::from(magnitude_bits) * std::f64::consts::LOG10_2).ceil() as u32;
+    let hex_digits = bits.div_ceil(4);
+    let sign = if signed { "-?" } else { "" };
+    let pattern = format!(
+        "^({}[0-9]{{1,{}}}|0x[0-9a-fA-F]{{1,{}}})$",
+        sign, decimal_digits, hex_digits
+    );
+
+    let mut number = Map::new();
+    number.insert("type".to_owned(), "integer".into());
+    // Beyond 53 bits JSON numbers lose precision, so no exact bound applies
+    if magnitude_bits <= 53 {
+        let max = (1u64 << magnitude_bits) - 1;
+        let min = if signed { -(max as i64) - 1 } else { 0 };
+        number.insert("minimum".to_owned(), min.into());
+        number.insert("maximum".to_owned(), max.into());
+    } else if !signed {
+        number.insert("minimum".to_owned(), 0.into());
+    }
+    json!({
+        "anyOf": [
+            Value::Object(number),
+            { "type": "string", "pattern": pattern },
+        ]
+    })
+}
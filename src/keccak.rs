@@ -0,0 +1,84 @@
+// keccak256, implemented with tiny-keccak or, with the `sha3` feature, with
+// RustCrypto's sha3. Both are the same function, so the backend only matters
+// for the dependency tree. It is chosen with features only: Keccak256 is not
+// public, and other implementations cannot be plugged in.
+
+use crate::Bytes32;
+
+#[cfg(not(any(feature = "tiny-keccak", feature = "sha3")))]
+compile_error!("A keccak backend is required: enable tiny-keccak or sha3");
+
+/// What the crate needs from a keccak256 implementation
+pub(crate) trait Keccak256 {
+    fn new() -> Self;
+    fn update(&mut self, data: &[u8]);
+    fn finalize(self) -> Bytes32;
+
+    fn digest(data: &[u8]) -> Bytes32
+    where
+        Self: Sized,
+    {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+#[cfg(not(feature = "sha3"))]
+type Backend = tiny_keccak::Keccak;
+
+#[cfg(not(feature = "sha3"))]
+impl Keccak256 for tiny_keccak::Keccak {
+    fn new() -> Self {
+        Self::v256()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        tiny_keccak::Hasher::update(self, data);
+    }
+
+    fn finalize(self) -> Bytes32 {
+        let mut result = [0u8; 32];
+        tiny_keccak::Hasher::finalize(self, &mut result);
+        result
+    }
+}
+
+#[cfg(feature = "sha3")]
+type Backend = sha3::Keccak256;
+
+#[cfg(feature = "sha3")]
+impl Keccak256 for sha3::Keccak256 {
+    fn new() -> Self {
+        sha3::Digest::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        sha3::Digest::update(self, data);
+    }
+
+    fn finalize(self) -> Bytes32 {
+        sha3::Digest::finalize(self).into()
+    }
+}
+
+pub(crate) fn keccak<T: AsRef<[u8]>>(buffer: T) -> Bytes32 {
+    Backend::digest(buffer.as_ref())
+}
+
+/// Incremental keccak256, for input which is not available all at once.
+pub(crate) struct Keccak(Backend);
+
+impl Keccak {
+    pub fn new() -> Self {
+        Self(Keccak256::new())
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    pub fn finalize(self) -> Bytes32 {
+        self.0.finalize()
+    }
+}
@@ -0,0 +1,202 @@
+//! Import of secp256k1 private keys in the formats produced by OpenSSL and
+//! most PKI tooling: SEC1 `ECPrivateKey` (RFC 5915, "EC PRIVATE KEY") and
+//! PKCS#8 `PrivateKeyInfo` (RFC 5208, "PRIVATE KEY"), as DER or PEM.
+//! Encrypted keys are not supported.
+
+use crate::{Error, SecretKey};
+use zeroize::{Zeroize, Zeroizing};
+
+const INTEGER: u8 = 0x02;
+const BIT_STRING: u8 = 0x03;
+const OCTET_STRING: u8 = 0x04;
+const OBJECT_IDENTIFIER: u8 = 0x06;
+const SEQUENCE: u8 = 0x30;
+const PARAMETERS: u8 = 0xa0;
+const PUBLIC_KEY: u8 = 0xa1;
+
+/// 1.2.840.10045.2.1
+const ID_EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+/// 1.3.132.0.10
+const SECP256K1: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x0a];
+
+fn invalid(reason: &str) -> Error {
+    Error::InvalidKeyEncoding(reason.to_owned())
+}
+
+/// Reads consecutive DER elements from a buffer
+struct Der<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Der<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn peek_tag(&self) -> Option<u8> {
+        self.data.first().copied()
+    }
+
+    /// Returns the contents of the next element, which must have `tag`
+    fn read(&mut self, tag: u8) -> Result<&'a [u8], Error> {
+        if self.peek_tag() != Some(tag) {
+            return Err(invalid("unexpected DER tag"));
+        }
+        let (&first, rest) = self
+            .data
+            .get(1..)
+            .and_then(<[u8]>::split_first)
+            .ok_or_else(|| invalid("truncated DER"))?;
+        let (len, rest) = if first < 0x80 {
+            (first as usize, rest)
+        } else {
+            let n = (first & 0x7f) as usize;
+            if n == 0 || n > 2 || rest.len() < n {
+                return Err(invalid("unsupported DER length"));
+            }
+            let len = rest[..n].iter().fold(0, |len, &b| (len << 8) | b as usize);
+            (len, &rest[n..])
+        };
+        if rest.len() < len {
+            return Err(invalid("truncated DER"));
+        }
+        let (contents, rest) = rest.split_at(len);
+        self.data = rest;
+        Ok(contents)
+    }
+
+    fn read_version(&mut self, expected: &[u8]) -> Result<(), Error> {
+        let version = self.read(INTEGER)?;
+        if version.len() != 1 || !expected.contains(&version[0]) {
+            return Err(invalid("unsupported version"));
+        }
+        Ok(())
+    }
+
+    fn read_curve(&mut self) -> Result<(), Error> {
+        if self.read(OBJECT_IDENTIFIER)? != SECP256K1 {
+            return Err(invalid("curve is not secp256k1"));
+        }
+        Ok(())
+    }
+}
+
+impl SecretKey {
+    /// Parses a DER encoded SEC1 or PKCS#8 private key.
+    pub fn from_der(der: &[u8]) -> Result<Self, Error> {
+        let mut outer = Der::new(der);
+        let mut sequence = Der::new(outer.read(SEQUENCE)?);
+        if !outer.is_empty() {
+            return Err(invalid("trailing data"));
+        }
+        // SEC1 has the key as an octet string after the version, where
+        // PKCS#8 has an AlgorithmIdentifier sequence.
+        let mut lookahead = Der::new(sequence.data);
+        lookahead.read(INTEGER)?;
+        if lookahead.peek_tag() == Some(OCTET_STRING) {
+            return parse_sec1(sequence, true);
+        }
+        // Version 1 is OneAsymmetricKey (RFC 5958), which only adds fields
+        sequence.read_version(&[0, 1])?;
+        let mut algorithm = Der::new(sequence.read(SEQUENCE)?);
+        if algorithm.read(OBJECT_IDENTIFIER)? != ID_EC_PUBLIC_KEY {
+            return Err(invalid("not an elliptic curve key"));
+        }
+        algorithm.read_curve()?;
+        let sec1 = sequence.read(OCTET_STRING)?;
+        let mut outer = Der::new(sec1);
+        let sequence = Der::new(outer.read(SEQUENCE)?);
+        parse_sec1(sequence, false)
+    }
+
+    /// Parses a PEM encoded SEC1 ("EC PRIVATE KEY") or PKCS#8
+    /// ("PRIVATE KEY") private key. Other PEM blocks, such as the
+    /// "EC PARAMETERS" emitted by `openssl ecparam`, are skipped.
+    pub fn from_pem(pem: &str) -> Result<Self, Error> {
+        let mut lines = pem.lines().map(str::trim);
+        while let Some(line) = lines.next() {
+            let label = match line
+                .strip_prefix("-----BEGIN ")
+                .and_then(|l| l.strip_suffix("-----"))
+            {
+                Some(label) => label,
+                None => continue,
+            };
+            if label != "EC PRIVATE KEY" && label != "PRIVATE KEY" {
+                continue;
+            }
+            let end = format!("-----END {}-----", label);
+            // Sized up front so that pushing never reallocates, which would
+            // leave copies of the key behind
+            let mut base64 = Zeroizing::new(String::with_capacity(pem.len()));
+            for line in &mut lines {
+                if line == end {
+                    return Self::from_der(&decode_base64(&base64)?);
+                }
+                base64.push_str(line);
+            }
+            return Err(invalid("unterminated PEM block"));
+        }
+        Err(invalid("no private key found in PEM"))
+    }
+}
+
+/// Parses the contents of an ECPrivateKey sequence. Standalone SEC1 keys must
+/// name their curve, since nothing else does.
+fn parse_sec1(mut sequence: Der<'_>, require_curve: bool) -> Result<SecretKey, Error> {
+    sequence.read_version(&[1])?;
+    let private_key = sequence.read(OCTET_STRING)?;
+    if private_key.len() > 32 {
+        return Err(invalid("private key is too long"));
+    }
+    let mut key = [0u8; 32];
+    key[32 - private_key.len()..].copy_from_slice(private_key);
+    let result = SecretKey::from_bytes(&key);
+    key.zeroize();
+    let secret_key = result?;
+
+    if sequence.peek_tag() == Some(PARAMETERS) {
+        Der::new(sequence.read(PARAMETERS)?).read_curve()?;
+    } else if require_curve {
+        return Err(invalid("missing curve parameters"));
+    }
+    if sequence.peek_tag() == Some(PUBLIC_KEY) {
+        let bits = Der::new(sequence.read(PUBLIC_KEY)?).read(BIT_STRING)?;
+        let public_key = bits
+            .split_first()
+            .filter(|(&unused, _)| unused == 0)
+            .and_then(|(_, key)| crate::ecdsa::parse_public_key(key))
+            .ok_or_else(|| invalid("malformed public key"))?;
+        if crate::signature::address_of_public_key(&public_key) != secret_key.address() {
+            return Err(invalid("public key does not match private key"));
+        }
+    }
+    Ok(secret_key)
+}
+
+fn decode_base64(text: &str) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let mut result = Zeroizing::new(Vec::with_capacity(text.len() * 3 / 4));
+    let mut buffer = Zeroizing::new(0u32);
+    let mut bits = 0;
+    for c in text.trim_end_matches('=').bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return Err(invalid("invalid base64")),
+        };
+        *buffer = (*buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            result.push((*buffer >> bits) as u8);
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,56 @@
+//! Storage of secret keys in the operating system's credential store: the
+//! Keychain on macOS, the Credential Manager (DPAPI) on Windows, and the
+//! Secret Service (libsecret) on Linux. Requires the `keychain` feature.
+//!
+//! Entries are identified by a service name, eg: the name of the application,
+//! and an account name within that service.
+
+use crate::{Error, SecretKey};
+use keyring::Entry;
+use zeroize::Zeroize;
+
+fn entry(service: &str, account: &str) -> Result<Entry, Error> {
+    Entry::new(service, account).map_err(keychain_error)
+}
+
+fn keychain_error(error: keyring::Error) -> Error {
+    Error::Keychain(error.to_string())
+}
+
+/// Stores `key`, replacing any existing entry for the same service and
+/// account.
+pub fn store(service: &str, account: &str, key: &SecretKey) -> Result<(), Error> {
+    entry(service, account)?
+        .set_secret(key.as_bytes())
+        .map_err(keychain_error)
+}
+
+/// Loads a key previously saved with [`store`]. Returns `Ok(None)` if there is
+/// no such entry.
+pub fn load(service: &str, account: &str) -> Result<Option<SecretKey>, Error> {
+    let mut secret = match entry(service, account)?.get_secret() {
+        Ok(secret) => secret,
+        Err(keyring::Error::NoEntry) => return Ok(None),
+        Err(e) => return Err(keychain_error(e)),
+    };
+    let result = if secret.len() == 32 {
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&secret);
+        let result = SecretKey::from_bytes(&key);
+        key.zeroize();
+        result.map(Some)
+    } else {
+        Err(Error::InvalidPrivateKey)
+    };
+    secret.zeroize();
+    result
+}
+
+/// Removes the entry, returning whether there was one.
+pub fn delete(service: &str, account: &str) -> Result<bool, Error> {
+    match entry(service, account)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(keychain_error(e)),
+    }
+}
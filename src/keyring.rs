@@ -0,0 +1,64 @@
+use crate::*;
+use std::collections::BTreeMap;
+
+/// A set of secret keys held in memory, looked up by the address they sign
+/// as. Useful for services which sign on behalf of several identities.
+///
+/// Keys are zeroized when removed or when the keyring is dropped.
+#[derive(Default)]
+pub struct Keyring {
+    keys: BTreeMap<Address, SecretKey>,
+}
+
+impl Keyring {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a key, returning the address it signs as. Replaces any existing
+    /// copy of the same key.
+    pub fn insert(&mut self, key: SecretKey) -> Address {
+        let address = key.address();
+        self.keys.insert(address, key);
+        address
+    }
+
+    /// Removes the key for `address`, returning whether it was present.
+    pub fn remove(&mut self, address: &Address) -> bool {
+        self.keys.remove(address).is_some()
+    }
+
+    pub fn contains(&self, address: &Address) -> bool {
+        self.keys.contains_key(address)
+    }
+
+    /// The addresses of all keys, in ascending order
+    pub fn addresses(&self) -> impl Iterator<Item = &Address> {
+        self.keys.keys()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn sign_as(&self, address: &Address, request: &SigningRequest) -> Result<Signature, Error> {
+        let key = self
+            .keys
+            .get(address)
+            .ok_or(Error::UnknownSigner(*address))?;
+        sign_digest(request.digest(), key)
+    }
+
+    pub fn sign_typed_as<T: StructType>(
+        &self,
+        address: &Address,
+        domain_separator: &DomainSeparator,
+        value: &T,
+    ) -> Result<Signature, Error> {
+        self.sign_as(address, &SigningRequest::new(domain_separator, value))
+    }
+}
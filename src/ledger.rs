@@ -0,0 +1,150 @@
+//! Signing with a Ledger device running the Ethereum app. Requires the
+//! `ledger` feature.
+//!
+//! The key never leaves the device: [`LedgerSigner`] sends the domain
+//! separator and struct hash with the app's EIP-712 signing APDU, and the
+//! user confirms the hashes on the device. It talks to the device through any
+//! [`Exchange`], eg: ledger-transport-hid for USB or a TCP transport for the
+//! Speculos emulator.
+//!
+//! The transports are asynchronous, so this is an [`AsyncSigner`].
+
+use crate::ecdsa::PublicKey;
+use crate::signature::address_of_public_key;
+use crate::{Address, AsyncSigner, Error, RecoveryId, Signature, SigningRequest};
+use ledger_transport::{APDUCommand, Exchange};
+use std::fmt;
+
+/// Added to an index of a derivation path to harden it, eg:
+/// `[44 + HARDENED, 60 + HARDENED, HARDENED, 0, 0]` for m/44'/60'/0'/0/0
+pub const HARDENED: u32 = 0x8000_0000;
+
+const CLA: u8 = 0xe0;
+const INS_GET_ADDRESS: u8 = 0x02;
+const INS_SIGN_EIP712: u8 = 0x0c;
+/// Of SIGN_EIP712, for a domain separator and struct hash rather than the
+/// full message
+const P2_HASHED: u8 = 0x00;
+
+const SW_OK: u16 = 0x9000;
+const SW_DENIED: u16 = 0x6985;
+
+/// The Ethereum app allows at most this many indices in a path
+const MAX_PATH_LEN: usize = 10;
+
+/// A key held by a Ledger device. See the [module](self) documentation.
+pub struct LedgerSigner<E> {
+    transport: E,
+    path: Vec<u32>,
+    address: Address,
+}
+
+impl<E> LedgerSigner<E>
+where
+    E: Exchange + Sync,
+    E::Error: fmt::Display,
+{
+    /// Asks the device for the address of the key at `path`, without
+    /// displaying it.
+    pub async fn new(transport: E, path: &[u32]) -> Result<Self, Error> {
+        if path.is_empty() || path.len() > MAX_PATH_LEN {
+            return Err(Error::Signer(format!(
+                "Derivation paths must have 1 to {} indices",
+                MAX_PATH_LEN
+            )));
+        }
+        let mut signer = Self {
+            transport,
+            path: path.to_vec(),
+            address: Address::default(),
+        };
+        let response = signer.exchange(INS_GET_ADDRESS, 0x00, Vec::new()).await?;
+        // public key length ‖ public key ‖ address length ‖ address as hex
+        let public_key = match response.split_first() {
+            Some((&65, rest)) if rest.len() >= 65 && rest[0] == 0x04 => &rest[1..65],
+            _ => return Err(malformed()),
+        };
+        let mut uncompressed: PublicKey = [0u8; 64];
+        uncompressed.copy_from_slice(public_key);
+        signer.address = address_of_public_key(&uncompressed);
+        Ok(signer)
+    }
+
+    pub fn path(&self) -> &[u32] {
+        &self.path
+    }
+
+    pub fn into_transport(self) -> E {
+        self.transport
+    }
+
+    /// Sends an APDU prefixed with the path, returning the response data
+    async fn exchange(&self, ins: u8, p2: u8, data: Vec<u8>) -> Result<Vec<u8>, Error> {
+        let mut payload = Vec::with_capacity(1 + 4 * self.path.len() + data.len());
+        payload.push(self.path.len() as u8);
+        for index in &self.path {
+            payload.extend_from_slice(&index.to_be_bytes());
+        }
+        payload.extend_from_slice(&data);
+        let command = APDUCommand {
+            cla: CLA,
+            ins,
+            p1: 0x00,
+            p2,
+            data: payload,
+        };
+        let answer = self
+            .transport
+            .exchange(&command)
+            .await
+            .map_err(|e| Error::Signer(e.to_string()))?;
+        match answer.retcode() {
+            SW_OK => Ok(answer.data().to_vec()),
+            SW_DENIED => Err(Error::Rejected),
+            status => Err(Error::Signer(format!("Ledger status {:#06x}", status))),
+        }
+    }
+}
+
+fn malformed() -> Error {
+    Error::Signer("Malformed response from the Ledger".to_owned())
+}
+
+impl<E> AsyncSigner for LedgerSigner<E>
+where
+    E: Exchange + Sync,
+    E::Error: fmt::Display,
+{
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign(&self, request: &SigningRequest) -> Result<Signature, Error> {
+        let mut data = Vec::with_capacity(64);
+        data.extend_from_slice(request.domain_separator().as_bytes());
+        data.extend_from_slice(request.struct_hash());
+        let response = self.exchange(INS_SIGN_EIP712, P2_HASHED, data).await?;
+
+        // v ‖ r ‖ s, with v as 27 or 28
+        if response.len() != 65 {
+            return Err(malformed());
+        }
+        let recovery_id = RecoveryId::from_eth_v(response[0])
+            .or_else(|| RecoveryId::from_parity(response[0]))
+            .ok_or_else(malformed)?;
+        let mut rs = [0u8; 64];
+        rs.copy_from_slice(&response[1..]);
+        let signature = Signature::new(&rs, recovery_id);
+
+        // The device signs its own hash of what it was sent, so check that
+        // it is the expected digest and key
+        let recovered = signature.recover(request.digest())?;
+        if recovered != self.address {
+            return Err(Error::SignerMismatch {
+                expected: self.address,
+                recovered,
+            });
+        }
+        Ok(signature)
+    }
+}
@@ -1,23 +1,150 @@
-//! eip-712-derive: The `derive` is aspirational
+//! eip-712-derive: With the `derive` feature, `#[derive(StructType)]` implements
+//! `StructType` from a struct's definition.
 
+#[cfg(feature = "alloy")]
+mod alloy;
 mod atomic_types;
+pub mod audit;
+#[cfg(feature = "chains")]
+pub mod chains;
+#[cfg(feature = "codegen")]
+pub mod codegen;
+#[cfg(feature = "conformance")]
+pub mod conformance;
+mod descriptor;
+mod domain;
+pub mod dynamic;
 mod dynamic_types;
+#[cfg(feature = "verify")]
+mod ecdsa;
+#[cfg(feature = "erc7730")]
+pub mod erc7730;
+mod error;
+#[cfg(feature = "ethers")]
+pub mod ethers;
+#[cfg(feature = "json")]
+mod from_json;
+#[cfg(feature = "fuzzing")]
+pub mod fuzzing;
+mod hashed;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "json-schema")]
+pub mod json_schema;
+mod keccak;
+#[cfg(feature = "sign")]
+mod key_encoding;
+#[cfg(feature = "keychain")]
+pub mod keychain;
+#[cfg(feature = "sign")]
+mod keyring;
+#[cfg(feature = "ledger")]
+pub mod ledger;
+mod macros;
+pub mod metrics;
+#[cfg(feature = "async")]
+pub mod middleware;
+mod migration;
+#[cfg(feature = "uniffi")]
+pub mod mobile;
+#[cfg(feature = "verify")]
+mod multi_signed;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod policy;
 mod prelude;
+#[cfg(feature = "sign")]
+mod private_key;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+mod salt;
+#[cfg(feature = "sign")]
+mod secret_key;
+#[cfg(feature = "serde")]
+pub mod serde_helpers;
+#[cfg(feature = "sign")]
+mod session;
+mod signature;
+mod signed;
+mod signer;
+pub mod summary;
+#[cfg(feature = "testing")]
+pub mod testing;
 mod type_hash;
 mod types;
+#[cfg(feature = "walletconnect")]
+pub mod walletconnect;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 extern crate lazy_static;
 
-use clear_on_drop::clear_stack_on_return;
-use libsecp256k1::{Message, RecoveryId, SecretKey, Signature};
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
+
 use prelude::*;
-use std::io::{Cursor, Write};
+use std::io::{self, Cursor, Write};
 
 // API
 pub use atomic_types::*;
-pub use type_hash::{encode_type, type_hash};
-pub use types::{MemberType, MemberVisitor, StructType};
+pub use descriptor::{MemberDescriptor, StructDescriptor, TypeDescriptor};
+pub use domain::{DomainBuilder, DomainRegistry, PartialDomain};
+pub use dynamic_types::HashedBytes;
+#[cfg(feature = "nfc")]
+pub use dynamic_types::NfcString;
+#[cfg(feature = "derive")]
+pub use eip_712_derive_macros::{MemberType, StructType};
+pub use error::{Error, FieldDifference};
+#[cfg(feature = "json")]
+pub use from_json::FromJson;
+pub use hashed::Hashed;
+#[cfg(feature = "json")]
+pub use json::{domain_separator_json, hash_typed_json, typed_data_json, verify_typed_json};
+#[cfg(feature = "sign")]
+pub use keyring::Keyring;
+pub use migration::{DomainChange, DomainEra, DomainMigration, MigrationDigests};
+#[cfg(feature = "verify")]
+pub use multi_signed::MultiSigned;
+#[cfg(feature = "parallel")]
+pub use parallel::hash_struct_batch;
+#[cfg(all(feature = "parallel", feature = "sign"))]
+pub use parallel::sign_typed_batch;
+pub use policy::PolicySigner;
+#[cfg(feature = "sign")]
+pub use private_key::PrivateKey;
+pub use salt::Salt;
+#[cfg(feature = "sign")]
+pub use secret_key::SecretKey;
+#[cfg(feature = "sign")]
+pub use session::{SessionScope, SessionSigner};
+#[cfg(feature = "sign")]
+pub use signature::signer_address;
+pub use signature::{RecoveryId, Signature};
+pub use signed::Signed;
+#[cfg(feature = "async")]
+pub use signer::AsyncSigner;
+pub use signer::{Signer, SigningRequest};
+pub use type_hash::{
+    encode_type, encode_type_of, try_encode_type, try_encode_type_of, try_type_hash,
+    try_type_hash_of, type_hash, type_hash_of,
+};
+pub use types::{
+    AtomicType, DynamicType, MemberType, MemberVisitor, ReferenceType, StructType, TypeVisitor,
+};
+
+// Used by the code which #[derive(StructType)] and the macros generate
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "json")]
+    pub use crate::from_json::{struct_member, struct_members};
+    pub use crate::macros::{member_name, parse_hex, parse_u256};
+    pub use crate::type_hash::TypeHashBuilder;
+    #[cfg(feature = "json")]
+    pub use serde_json::Value;
+}
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Hash)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Default, Hash)]
 pub struct DomainSeparator(Bytes32);
 
 impl DomainSeparator {
@@ -39,8 +166,7 @@ impl DomainSeparator {
     }
 }
 
-pub type PrivateKey = Bytes32;
-
+#[derive(Clone, PartialEq, Eq, Debug, Default, Hash)]
 pub struct Eip712Domain {
     pub name: String,
     pub version: String,
@@ -59,72 +185,306 @@ impl StructType for Eip712Domain {
         v.visit("verifyingContract", &self.verifying_contract);
         v.visit("salt", &self.salt);
     }
+
+    fn visit_member_types<T: TypeVisitor>(v: &mut T) -> Result<(), Error> {
+        v.visit::<String>("name");
+        v.visit::<String>("version");
+        v.visit::<U256>("chainId");
+        v.visit::<Address>("verifyingContract");
+        v.visit::<Bytes32>("salt");
+        Ok(())
+    }
 }
 
 pub fn encode_data<T: StructType>(s: &T) -> Vec<u8> {
-    let mut buffer = Vec::new();
+    let mut buffer = Vec::with_capacity(32 * (1 + member_count(s)));
+    // Writing to a Vec cannot fail
+    encode_data_into(s, &mut buffer).unwrap();
+    buffer
+}
 
-    buffer.extend_from_slice(&type_hash(s));
+/// Like [`encode_data`], but writes to `out`, so that buffers can be reused.
+pub fn encode_data_into<T: StructType, W: Write>(s: &T, out: &mut W) -> io::Result<()> {
+    out.write_all(&type_hash(s))?;
 
-    struct EncodeVisitor<'a> {
-        buffer: &'a mut Vec<u8>,
+    struct EncodeVisitor<'a, W> {
+        out: &'a mut W,
+        result: io::Result<()>,
     }
     let mut visitor = EncodeVisitor {
-        buffer: &mut buffer,
+        out,
+        result: Ok(()),
     };
-    impl MemberVisitor for EncodeVisitor<'_> {
+    impl<W: Write> MemberVisitor for EncodeVisitor<'_, W> {
         fn visit<T: MemberType>(&mut self, _name: &'static str, value: &T) {
-            let member_value = value.encode_data();
-            self.buffer.extend_from_slice(&member_value);
+            // Stop writing after the first error
+            if self.result.is_ok() {
+                let member_value = value.encode_data();
+                self.result = self.out.write_all(&member_value);
+            }
         }
     }
     s.visit_members(&mut visitor);
-    buffer
+    visitor.result
+}
+
+/// The number of members visited, so that encode_data allocates only once.
+fn member_count<T: StructType>(s: &T) -> usize {
+    struct CountVisitor(usize);
+    impl MemberVisitor for CountVisitor {
+        fn visit<T: MemberType>(&mut self, _name: &'static str, _value: &T) {
+            self.0 += 1;
+        }
+    }
+    let mut visitor = CountVisitor(0);
+    s.visit_members(&mut visitor);
+    visitor.0
 }
 
 pub fn hash_struct<T: StructType>(s: &T) -> Bytes32 {
     // hashStruct(s : 𝕊) = keccak256(typeHash ‖ encodeData(s))
     // Looks like typeHash is missing here! But, it's in encodeData.
-    keccak(encode_data(s))
+    let start = metrics::start();
+    // encodeData is fed to the hasher as it is produced, never buffered
+    let mut hasher = HashWriter {
+        keccak: Keccak::new(),
+        len: 0,
+    };
+    // Hashing cannot fail
+    encode_data_into(s, &mut hasher).unwrap();
+    let len = hasher.len;
+    let result = hasher.keccak.finalize();
+    metrics::hash_struct(start, len);
+    result
+}
+
+struct HashWriter {
+    keccak: Keccak,
+    len: usize,
+}
+
+impl Write for HashWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.keccak.update(buf);
+        self.len += buf.len();
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Like [`hash_struct`], but returns an error for invalid types instead of
+/// panicking.
+pub fn try_hash_struct<T: StructType>(s: &T) -> Result<Bytes32, Error> {
+    // Every struct type referenced by T is checked along with it, so once the
+    // type hash is known, hashing cannot fail.
+    try_type_hash(s)?;
+    Ok(hash_struct(s))
+}
+
+/// Every intermediate value of the EIP-712 encoding of a message.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Eip712Hashes {
+    /// keccak256(encodeType(typeOf(message)))
+    pub type_hash: Bytes32,
+    /// hashStruct(message)
+    pub struct_hash: Bytes32,
+    pub domain_separator: DomainSeparator,
+    /// keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(message)), as
+    /// returned by sign_hash
+    pub digest: Bytes32,
+}
+
+/// Computes all of the hashes involved in signing `message`, hashing each
+/// part only once.
+pub fn encode_eip712<T: StructType>(
+    domain_separator: &DomainSeparator,
+    message: &T,
+) -> Eip712Hashes {
+    let struct_hash = hash_struct(message);
+    Eip712Hashes {
+        type_hash: type_hash(message),
+        struct_hash,
+        domain_separator: *domain_separator,
+        digest: sign_hash_of(domain_separator, &struct_hash),
+    }
+}
+
+/// (SPEC) The array values are encoded as the keccak256 hash of the
+/// concatenated encodeData of their contents.
+///
+/// The elements are hashed as they are produced, so arrays too large to hold
+/// in memory can be hashed from an iterator.
+pub fn hash_array_iter<T: MemberType, I: IntoIterator<Item = T>>(elements: I) -> Bytes32 {
+    let mut hasher = Keccak::new();
+    for element in elements {
+        hasher.update(&element.encode_data());
+    }
+    hasher.finalize()
 }
 
 pub fn encode<T: StructType>(domain_separator: &DomainSeparator, message: &T) -> [u8; 66] {
-    // encode(domainSeparator : 𝔹²⁵⁶, message : 𝕊) = "\x19\x01" ‖ domainSeparator ‖ hashStruct(message)
     let mut result = [0u8; 66];
-    let mut cursor = Cursor::new(&mut result[..]);
+    encode_into(domain_separator, message, &mut result);
+    result
+}
+
+/// Like [`encode`], but writes to `out`, so that buffers can be reused.
+pub fn encode_into<T: StructType>(
+    domain_separator: &DomainSeparator,
+    message: &T,
+    out: &mut [u8; 66],
+) {
+    // encode(domainSeparator : 𝔹²⁵⁶, message : 𝕊) = "\x19\x01" ‖ domainSeparator ‖ hashStruct(message)
+    let mut cursor = Cursor::new(&mut out[..]);
     cursor.write_all("\x19\x01".as_bytes()).unwrap();
     cursor.write_all(domain_separator.as_bytes()).unwrap();
     cursor.write_all(&hash_struct(message)).unwrap();
-    result
 }
 
 pub fn sign_hash<T: StructType>(domain_separator: &DomainSeparator, message: &T) -> Bytes32 {
-    let data = encode(domain_separator, message);
-    keccak(&data[..])
+    sign_hash_of(domain_separator, &hash_struct(message))
 }
 
-/// Returns the serialized libsecp256k1 signature and the recoveryId on success.
+/// sign_hash, given hashStruct(message)
+pub(crate) fn sign_hash_of(domain_separator: &DomainSeparator, struct_hash: &Bytes32) -> Bytes32 {
+    // keccak256(encode(domainSeparator, message)), without building encode
+    let mut hasher = Keccak::new();
+    hasher.update(b"\x19\x01");
+    hasher.update(domain_separator.as_bytes());
+    hasher.update(struct_hash);
+    hasher.finalize()
+}
+
+/// Signs `value` under `domain_separator` with `key`. Keys are validated by
+/// [`SecretKey::from_bytes`].
+#[cfg(feature = "sign")]
 pub fn sign_typed<T: StructType>(
     domain_separator: &DomainSeparator,
     value: &T,
-    key: &PrivateKey,
-) -> Result<([u8; 64], u8), impl std::error::Error> {
-    let message = Message::parse(&sign_hash(domain_separator, value));
-
-    // Security: clear_stack_on_return zeroizes the temporary copy of SecretKey
-    // created by SecretKey::parse
-    let result =
-        clear_stack_on_return::<_, Result<(Signature, RecoveryId), libsecp256k1::Error>>(1, || {
-            let secret_key = SecretKey::parse(key)?;
-            Ok(libsecp256k1::sign(&message, &secret_key))
+    key: &SecretKey,
+) -> Result<Signature, Error> {
+    sign_digest(&sign_hash(domain_separator, value), key)
+}
+
+/// Like [`sign_typed`], but also returns an error for invalid types instead of
+/// panicking.
+#[cfg(feature = "sign")]
+pub fn try_sign_typed<T: StructType>(
+    domain_separator: &DomainSeparator,
+    value: &T,
+    key: &SecretKey,
+) -> Result<Signature, Error> {
+    try_type_hash(value)?;
+    sign_typed(domain_separator, value, key)
+}
+
+/// Recovers the address which signed `message` under `domain_separator`.
+///
+/// Any signature recovers to some address, so compare the result with the
+/// expected signer.
+#[cfg(feature = "verify")]
+pub fn recover_address<T: StructType>(
+    domain_separator: &DomainSeparator,
+    message: &T,
+    signature: &Signature,
+) -> Result<Address, Error> {
+    signature.recover(&sign_hash(domain_separator, message))
+}
+
+/// Checks that `signature` over `message` under `domain_separator` was made by
+/// `expected_signer`.
+///
+/// A valid signature from someone else is reported as
+/// [`Error::SignerMismatch`], distinct from the errors for malformed
+/// signatures.
+#[cfg(feature = "verify")]
+pub fn verify_typed<T: StructType>(
+    domain_separator: &DomainSeparator,
+    message: &T,
+    signature: &Signature,
+    expected_signer: &Address,
+) -> Result<(), Error> {
+    let recovered = recover_address(domain_separator, message, signature)?;
+    if &recovered != expected_signer {
+        return Err(Error::SignerMismatch {
+            expected: *expected_signer,
+            recovered,
         });
+    }
+    Ok(())
+}
 
-    // Shenanigans to satisfy the compiler.
-    // `?` doesn't work because returning impl Error
-    let (sig, recovery) = match result {
-        Ok(s) => s,
-        Err(e) => return Err(e),
-    };
+#[cfg(feature = "sign")]
+pub(crate) fn sign_digest(digest: &Bytes32, key: &SecretKey) -> Result<Signature, Error> {
+    let start = metrics::start();
+    // The key was validated by SecretKey, so this does not fail
+    let (rs, parity) = ecdsa::sign(digest, key.as_bytes())?;
+    metrics::sign(start);
+    // This unwrap is ok, because the backends only produce 0 or 1.
+    let recovery = RecoveryId::from_parity(parity).unwrap();
+    Ok(Signature::new(&rs, recovery))
+}
 
-    Ok((sig.serialize(), recovery.serialize() + 27))
+/// The bytes which precede the domain separator and struct hash in the
+/// encoding that is hashed for signing.
+///
+/// EIP-712 fixes these to "\x19\x01", which is what the functions without a
+/// profile use. Some chains (eg: Tron's TIP-712) adopt the same structured
+/// data scheme under a different prefix, and can be targeted explicitly with a
+/// profile created by [`EncodingProfile::with_prefix`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub struct EncodingProfile {
+    prefix: &'static [u8],
+}
+
+impl Default for EncodingProfile {
+    fn default() -> Self {
+        Self::EIP_712
+    }
+}
+
+impl EncodingProfile {
+    pub const EIP_712: Self = Self::with_prefix(b"\x19\x01");
+
+    pub const fn with_prefix(prefix: &'static [u8]) -> Self {
+        Self { prefix }
+    }
+
+    pub fn prefix(&self) -> &'static [u8] {
+        self.prefix
+    }
+
+    /// prefix ‖ domainSeparator ‖ hashStruct(message)
+    pub fn encode<T: StructType>(
+        &self,
+        domain_separator: &DomainSeparator,
+        message: &T,
+    ) -> Vec<u8> {
+        let mut result = Vec::with_capacity(self.prefix.len() + 64);
+        result.extend_from_slice(self.prefix);
+        result.extend_from_slice(domain_separator.as_bytes());
+        result.extend_from_slice(&hash_struct(message));
+        result
+    }
+
+    pub fn sign_hash<T: StructType>(
+        &self,
+        domain_separator: &DomainSeparator,
+        message: &T,
+    ) -> Bytes32 {
+        keccak(self.encode(domain_separator, message))
+    }
+
+    /// Like [`sign_typed`], but using this profile's prefix.
+    #[cfg(feature = "sign")]
+    pub fn sign_typed<T: StructType>(
+        &self,
+        domain_separator: &DomainSeparator,
+        value: &T,
+        key: &SecretKey,
+    ) -> Result<Signature, Error> {
+        sign_digest(&self.sign_hash(domain_separator, value), key)
+    }
 }
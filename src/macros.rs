@@ -0,0 +1,178 @@
+/// Declares structs with their StructType impls, without a proc-macro
+/// dependency. Member names are the field names, and the type name is the
+/// struct name.
+///
+/// ```
+/// use eip_712_derive::{eip712_struct, Address};
+///
+/// eip712_struct! {
+///     #[derive(Clone, Debug)]
+///     pub Person {
+///         pub name: String,
+///         pub wallet: Address,
+///     }
+///
+///     pub Mail {
+///         pub from: Person,
+///         pub to: Person,
+///         pub contents: String,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! eip712_struct {
+    ($(
+        $(#[$meta:meta])*
+        $vis:vis $name:ident {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident : $type:ty),* $(,)?
+        }
+    )*) => {
+        $(
+            $(#[$meta])*
+            $vis struct $name {
+                $($(#[$field_meta])* $field_vis $field: $type,)*
+            }
+
+            impl $crate::StructType for $name {
+                const TYPE_NAME: &'static str = stringify!($name);
+                fn visit_members<T: $crate::MemberVisitor>(&self, visitor: &mut T) {
+                    $(visitor.visit(
+                        $crate::__private::member_name(stringify!($field)),
+                        &self.$field,
+                    );)*
+                }
+                fn visit_member_types<T: $crate::TypeVisitor>(
+                    visitor: &mut T,
+                ) -> ::std::result::Result<(), $crate::Error> {
+                    $(visitor.visit::<$type>(
+                        $crate::__private::member_name(stringify!($field)),
+                    );)*
+                    ::std::result::Result::Ok(())
+                }
+            }
+        )*
+    };
+}
+
+/// Strips the r# of raw identifiers
+#[doc(hidden)]
+pub fn member_name(field: &'static str) -> &'static str {
+    field.strip_prefix("r#").unwrap_or(field)
+}
+
+/// An [`Address`](crate::Address) from a hex literal, checked at compile
+/// time. The 0x prefix is optional. The EIP-55 checksum of mixed case
+/// literals is not checked.
+///
+/// ```
+/// use eip_712_derive::{address, Address};
+///
+/// const CONTRACT: Address = address!("0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC");
+/// ```
+///
+/// ```compile_fail
+/// // One digit short
+/// let contract = eip_712_derive::address!("0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCccccccc");
+/// ```
+#[macro_export]
+macro_rules! address {
+    ($hex:literal) => {{
+        const VALUE: $crate::Address = $crate::Address($crate::__private::parse_hex($hex));
+        VALUE
+    }};
+}
+
+/// A [`U256`](crate::U256) from a decimal or 0x-prefixed hex literal, checked
+/// at compile time.
+///
+/// ```
+/// use eip_712_derive::{u256, U256};
+///
+/// const ONE_ETHER: U256 = u256!("1000000000000000000");
+/// ```
+///
+/// ```compile_fail
+/// let too_big = eip_712_derive::u256!("0x10000000000000000000000000000000000000000000000000000000000000000");
+/// ```
+#[macro_export]
+macro_rules! u256 {
+    ($number:literal) => {{
+        const VALUE: $crate::U256 = $crate::U256($crate::__private::parse_u256($number));
+        VALUE
+    }};
+}
+
+/// A [`Bytes32`](crate::Bytes32) from a hex literal, checked at compile time.
+/// The 0x prefix is optional.
+#[macro_export]
+macro_rules! bytes32 {
+    ($hex:literal) => {{
+        const VALUE: $crate::Bytes32 = $crate::__private::parse_hex($hex);
+        VALUE
+    }};
+}
+
+const fn strip_0x(s: &str) -> &[u8] {
+    match s.as_bytes() {
+        [b'0', b'x', rest @ ..] => rest,
+        bytes => bytes,
+    }
+}
+
+const fn hex_digit(c: u8) -> u8 {
+    match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - b'a' + 10,
+        b'A'..=b'F' => c - b'A' + 10,
+        _ => panic!("invalid hex digit"),
+    }
+}
+
+#[doc(hidden)]
+pub const fn parse_hex<const N: usize>(s: &str) -> [u8; N] {
+    let digits = strip_0x(s);
+    if digits.len() != N * 2 {
+        panic!("wrong number of hex digits");
+    }
+    let mut result = [0u8; N];
+    let mut i = 0;
+    while i < N {
+        result[i] = hex_digit(digits[2 * i]) << 4 | hex_digit(digits[2 * i + 1]);
+        i += 1;
+    }
+    result
+}
+
+#[doc(hidden)]
+pub const fn parse_u256(s: &str) -> [u8; 32] {
+    let (digits, radix) = match s.as_bytes() {
+        [b'0', b'x', rest @ ..] => (rest, 16),
+        bytes => (bytes, 10),
+    };
+    if digits.is_empty() {
+        panic!("empty number");
+    }
+    let mut result = [0u8; 32];
+    let mut i = 0;
+    while i < digits.len() {
+        let digit = match (radix, digits[i]) {
+            (10, c @ b'0'..=b'9') => c - b'0',
+            (10, _) => panic!("invalid decimal digit"),
+            (_, c) => hex_digit(c),
+        };
+        // result = result * radix + digit, big-endian
+        let mut carry = digit as u32;
+        let mut j = 32;
+        while j > 0 {
+            j -= 1;
+            let value = result[j] as u32 * radix + carry;
+            result[j] = value as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            panic!("number does not fit in 256 bits");
+        }
+        i += 1;
+    }
+    result
+}
@@ -0,0 +1,79 @@
+//! Optional instrumentation hooks.
+//!
+//! Install a [`Metrics`] implementation with [`set_metrics`] to be called back
+//! from the hashing and signing paths, eg: to feed Prometheus counters and
+//! histograms. When nothing is installed the only cost is an uncontended read
+//! lock per call.
+
+use lazy_static::lazy_static;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// All methods have empty default implementations, so implement only the ones
+/// of interest.
+pub trait Metrics: Send + Sync {
+    /// Called after each successful signature.
+    fn sign(&self, _duration: Duration) {}
+    /// Called after each signature verification, whether or not the signature
+    /// was valid.
+    fn verify(&self, _duration: Duration) {}
+    /// Called for each struct hashed, including nested structs. `encoded_len`
+    /// is the length of the encodeData which was hashed.
+    fn hash_struct(&self, _encoded_len: usize, _duration: Duration) {}
+    /// Called on each type hash lookup.
+    fn type_hash_cache(&self, _hit: bool) {}
+}
+
+lazy_static! {
+    static ref METRICS: RwLock<Option<Arc<dyn Metrics>>> = RwLock::new(None);
+}
+
+/// Installs the process-wide metrics hooks, replacing any previous ones.
+pub fn set_metrics<T: Metrics + 'static>(metrics: T) {
+    *METRICS.write().unwrap() = Some(Arc::new(metrics));
+}
+
+/// Removes the process-wide metrics hooks.
+pub fn clear_metrics() {
+    *METRICS.write().unwrap() = None;
+}
+
+fn installed() -> Option<Arc<dyn Metrics>> {
+    METRICS.read().unwrap().clone()
+}
+
+pub(crate) fn type_hash_cache(hit: bool) {
+    if let Some(metrics) = installed() {
+        metrics.type_hash_cache(hit);
+    }
+}
+
+/// Starts a timer if there is anyone to report to. The timer is passed back to
+/// one of the reporting functions below.
+pub(crate) fn start() -> Option<Instant> {
+    if METRICS.read().unwrap().is_some() {
+        Some(Instant::now())
+    } else {
+        None
+    }
+}
+
+pub(crate) fn hash_struct(start: Option<Instant>, encoded_len: usize) {
+    if let (Some(start), Some(metrics)) = (start, installed()) {
+        metrics.hash_struct(encoded_len, start.elapsed());
+    }
+}
+
+#[cfg(feature = "sign")]
+pub(crate) fn sign(start: Option<Instant>) {
+    if let (Some(start), Some(metrics)) = (start, installed()) {
+        metrics.sign(start.elapsed());
+    }
+}
+
+#[cfg(feature = "verify")]
+pub(crate) fn verify(start: Option<Instant>) {
+    if let (Some(start), Some(metrics)) = (start, installed()) {
+        metrics.verify(start.elapsed());
+    }
+}
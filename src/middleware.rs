@@ -0,0 +1,215 @@
+//! Composable wrappers for [`AsyncSigner`]s. Requires the `async` feature.
+//!
+//! Remote signers fail in transient ways: requests time out, services restart,
+//! rate limits kick in. These wrappers keep such failures from surfacing
+//! directly, and can be stacked:
+//!
+//! ```ignore
+//! let signer = CircuitBreaker::new(
+//!     Retry::new(Timeout::new(kms, Duration::from_secs(2), sleep), 3, Duration::from_millis(100), sleep),
+//!     5,
+//!     Duration::from_secs(30),
+//! );
+//! ```
+//!
+//! Wrappers which need to wait take a `sleep` function from the async runtime
+//! in use (eg: `tokio::time::sleep`) rather than depending on one.
+//!
+//! All wrappers pass the same [`SigningRequest`] to every attempt, so the
+//! digest is identical across retries.
+
+use crate::{Address, AsyncSigner, Error, Signature, SigningRequest};
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::sync::Mutex;
+use std::task::Poll;
+use std::time::{Duration, Instant};
+
+/// Fails attempts which take longer than a duration with [`Error::Timeout`].
+pub struct Timeout<S, F> {
+    inner: S,
+    duration: Duration,
+    sleep: F,
+}
+
+impl<S, F> Timeout<S, F> {
+    pub fn new(inner: S, duration: Duration, sleep: F) -> Self {
+        Self {
+            inner,
+            duration,
+            sleep,
+        }
+    }
+}
+
+impl<S, F, Fut> AsyncSigner for Timeout<S, F>
+where
+    S: AsyncSigner,
+    F: Fn(Duration) -> Fut + Sync,
+    Fut: Future<Output = ()> + Send,
+{
+    fn address(&self) -> Address {
+        self.inner.address()
+    }
+
+    async fn sign(&self, request: &SigningRequest) -> Result<Signature, Error> {
+        let mut sign = pin!(self.inner.sign(request));
+        let mut sleep = pin!((self.sleep)(self.duration));
+        poll_fn(|cx| {
+            if let Poll::Ready(result) = sign.as_mut().poll(cx) {
+                return Poll::Ready(result);
+            }
+            if sleep.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(Err(Error::Timeout));
+            }
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+/// Retries transient failures (see [`Error::is_transient`]) up to a maximum
+/// number of times, doubling the delay between attempts each time up to a
+/// maximum.
+pub struct Retry<S, F> {
+    inner: S,
+    max_retries: u32,
+    backoff: Duration,
+    max_backoff: Duration,
+    sleep: F,
+}
+
+impl<S, F> Retry<S, F> {
+    /// `backoff` is the delay before the first retry.
+    pub fn new(inner: S, max_retries: u32, backoff: Duration, sleep: F) -> Self {
+        Self {
+            inner,
+            max_retries,
+            backoff,
+            max_backoff: Duration::MAX,
+            sleep,
+        }
+    }
+
+    /// Caps the delay between attempts, which is otherwise only bounded by
+    /// `Duration::MAX`
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+}
+
+impl<S, F, Fut> AsyncSigner for Retry<S, F>
+where
+    S: AsyncSigner,
+    F: Fn(Duration) -> Fut + Sync,
+    Fut: Future<Output = ()> + Send,
+{
+    fn address(&self) -> Address {
+        self.inner.address()
+    }
+
+    async fn sign(&self, request: &SigningRequest) -> Result<Signature, Error> {
+        let mut delay = self.backoff.min(self.max_backoff);
+        let mut retries = 0;
+        loop {
+            match self.inner.sign(request).await {
+                Err(e) if e.is_transient() && retries < self.max_retries => {
+                    retries += 1;
+                    (self.sleep)(delay).await;
+                    delay = delay.saturating_mul(2).min(self.max_backoff);
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+struct CircuitState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    /// Whether the trial attempt after a cooldown is in flight
+    probing: bool,
+}
+
+/// Clears [`CircuitState::probing`] if the trial attempt is dropped before it
+/// completes, so that a cancelled attempt does not keep the circuit open.
+struct Probe<'a>(&'a Mutex<CircuitState>);
+
+impl Drop for Probe<'_> {
+    fn drop(&mut self) {
+        self.0.lock().unwrap().probing = false;
+    }
+}
+
+/// Stops calling the inner signer for a cooldown period after a number of
+/// consecutive transient failures, failing fast with [`Error::CircuitOpen`]
+/// instead. After the cooldown, a single trial attempt is let through while
+/// other attempts keep failing fast. Its failure reopens the circuit, and its
+/// success closes it.
+pub struct CircuitBreaker<S> {
+    inner: S,
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<CircuitState>,
+}
+
+impl<S> CircuitBreaker<S> {
+    pub fn new(inner: S, threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            inner,
+            threshold,
+            cooldown,
+            state: Mutex::new(CircuitState {
+                consecutive_failures: 0,
+                open_until: None,
+                probing: false,
+            }),
+        }
+    }
+}
+
+impl<S: AsyncSigner> AsyncSigner for CircuitBreaker<S> {
+    fn address(&self) -> Address {
+        self.inner.address()
+    }
+
+    async fn sign(&self, request: &SigningRequest) -> Result<Signature, Error> {
+        let probe = {
+            let mut state = self.state.lock().unwrap();
+            match state.open_until {
+                Some(open_until) if state.probing || Instant::now() < open_until => {
+                    return Err(Error::CircuitOpen);
+                }
+                Some(_) => {
+                    state.probing = true;
+                    Some(Probe(&self.state))
+                }
+                None => None,
+            }
+        };
+
+        let result = self.inner.sign(request).await;
+
+        let mut state = self.state.lock().unwrap();
+        if let Some(probe) = probe {
+            // Cleared together with recording the outcome, so that no other
+            // attempt is let through in between
+            std::mem::forget(probe);
+            state.probing = false;
+        }
+        match &result {
+            Err(e) if e.is_transient() => {
+                state.consecutive_failures += 1;
+                if state.consecutive_failures >= self.threshold {
+                    state.open_until = Some(Instant::now() + self.cooldown);
+                }
+            }
+            _ => {
+                state.consecutive_failures = 0;
+                state.open_until = None;
+            }
+        }
+        result
+    }
+}
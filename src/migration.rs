@@ -0,0 +1,139 @@
+use crate::prelude::*;
+use crate::summary::{summarize, Hints, SummaryValue};
+use crate::*;
+
+/// A move from one domain to another, eg: when an upgraded contract bumps
+/// its `version`.
+///
+/// Signatures are bound to the domain separator, so any change to the domain
+/// invalidates every signature made under the old one. During a migration
+/// window, digests can be computed under both domains and signatures accepted
+/// under either.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DomainMigration {
+    old: DomainSeparator,
+    new: DomainSeparator,
+    changes: Vec<DomainChange>,
+}
+
+/// A member of the domain which was added, removed or changed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DomainChange {
+    pub member: String,
+    /// None if the member was added
+    pub old: Option<SummaryValue>,
+    /// None if the member was removed
+    pub new: Option<SummaryValue>,
+}
+
+/// Which side of a migration something belongs to
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum DomainEra {
+    Old,
+    New,
+}
+
+/// The sign_hash of one message under both domains of a migration
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct MigrationDigests {
+    pub old: Bytes32,
+    pub new: Bytes32,
+}
+
+impl DomainMigration {
+    pub fn new<O: StructType, N: StructType>(old: &O, new: &N) -> Self {
+        let old_summary = summarize(old, &Hints::new());
+        let new_summary = summarize(new, &Hints::new());
+        let find = |members: &[crate::summary::SummaryMember], label: &str| {
+            members
+                .iter()
+                .find(|m| m.label == label)
+                .map(|m| m.value.clone())
+        };
+
+        let mut changes = Vec::new();
+        for member in &old_summary.members {
+            let new = find(&new_summary.members, &member.label);
+            if new.as_ref() != Some(&member.value) {
+                changes.push(DomainChange {
+                    member: member.label.clone(),
+                    old: Some(member.value.clone()),
+                    new,
+                });
+            }
+        }
+        for member in &new_summary.members {
+            if find(&old_summary.members, &member.label).is_none() {
+                changes.push(DomainChange {
+                    member: member.label.clone(),
+                    old: None,
+                    new: Some(member.value.clone()),
+                });
+            }
+        }
+
+        Self {
+            old: DomainSeparator::new(old),
+            new: DomainSeparator::new(new),
+            changes,
+        }
+    }
+
+    /// For when only the separators are known. No changes are reported.
+    pub fn from_separators(old: DomainSeparator, new: DomainSeparator) -> Self {
+        Self {
+            old,
+            new,
+            changes: Vec::new(),
+        }
+    }
+
+    pub fn domain_separator(&self, era: DomainEra) -> &DomainSeparator {
+        match era {
+            DomainEra::Old => &self.old,
+            DomainEra::New => &self.new,
+        }
+    }
+
+    /// Whether signatures made under the old domain verify under the new one,
+    /// which is the case only if the separator is unchanged. Renaming the type
+    /// of the domain or reordering its members changes the separator even if
+    /// no value changes.
+    pub fn signatures_remain_valid(&self) -> bool {
+        self.old == self.new
+    }
+
+    /// The members whose values differ, in the order of the old domain
+    /// followed by added members.
+    pub fn changes(&self) -> &[DomainChange] {
+        &self.changes
+    }
+
+    /// Hashes the message once, for both domains.
+    pub fn sign_hashes<T: StructType>(&self, message: &T) -> MigrationDigests {
+        let struct_hash = hash_struct(message);
+        MigrationDigests {
+            old: sign_hash_of(&self.old, &struct_hash),
+            new: sign_hash_of(&self.new, &struct_hash),
+        }
+    }
+
+    /// Which domain, if either, `signature` was made under by `signer`. The
+    /// new domain is preferred when they are the same.
+    #[cfg(feature = "verify")]
+    pub fn verify<T: StructType>(
+        &self,
+        message: &T,
+        signature: &Signature,
+        signer: &Address,
+    ) -> Option<DomainEra> {
+        let digests = self.sign_hashes(message);
+        if signature.recover(&digests.new).ok().as_ref() == Some(signer) {
+            return Some(DomainEra::New);
+        }
+        if signature.recover(&digests.old).ok().as_ref() == Some(signer) {
+            return Some(DomainEra::Old);
+        }
+        None
+    }
+}
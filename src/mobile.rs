@@ -0,0 +1,49 @@
+//! Kotlin and Swift bindings through UniFFI. Requires the `uniffi` feature.
+//!
+//! Typed data crosses the boundary as eth_signTypedData_v4 JSON strings and
+//! hashes as byte arrays. Errors are thrown as exceptions named after the
+//! [`Error`] variants, with this crate's messages.
+//!
+//! Build the library as a cdylib, then generate the bindings from it with
+//! uniffi-bindgen, eg:
+//! `cargo rustc --lib --release --features uniffi --crate-type cdylib` and
+//! `uniffi-bindgen generate --library target/release/libeip_712_derive.so --language kotlin`.
+
+use crate::dynamic::TypedData;
+use crate::Error;
+
+/// The digest of typed data JSON, which is what gets signed
+#[uniffi::export]
+pub fn hash_typed_data(json: &str) -> Result<Vec<u8>, Error> {
+    Ok(TypedData::from_json(json)?.digest()?.to_vec())
+}
+
+/// hashStruct of the message of typed data JSON
+#[uniffi::export]
+pub fn hash_struct(json: &str) -> Result<Vec<u8>, Error> {
+    Ok(TypedData::from_json(json)?.struct_hash()?.to_vec())
+}
+
+/// See [`domain_separator_json`](crate::domain_separator_json)
+#[uniffi::export]
+pub fn domain_separator(domain: &str) -> Result<Vec<u8>, Error> {
+    Ok(crate::domain_separator_json(domain)?.as_bytes().to_vec())
+}
+
+/// The checksummed address which produced a 65 byte `signature` over typed
+/// data JSON
+#[uniffi::export]
+pub fn recover_typed_data(json: &str, signature: &[u8]) -> Result<String, Error> {
+    let signature = crate::Signature::from_bytes(signature)?;
+    Ok(TypedData::from_json(json)?.recover(&signature)?.to_string())
+}
+
+/// See [`verify_typed_json`](crate::verify_typed_json)
+#[uniffi::export]
+pub fn verify_typed_data(
+    json: &str,
+    signature: &str,
+    expected_signer: &str,
+) -> Result<bool, Error> {
+    crate::verify_typed_json(json, signature, expected_signer)
+}
@@ -0,0 +1,87 @@
+use crate::prelude::*;
+use crate::*;
+use std::collections::BTreeMap;
+
+/// Signatures from several signers over one message, for M-of-N approvals.
+///
+/// Each signature is recovered as it is added, and kept only once per
+/// signer. Which signers count is decided when checking the threshold, so the
+/// same collection can be checked against different allowlists.
+#[derive(Clone, Debug)]
+pub struct MultiSigned<T> {
+    message: T,
+    domain_separator: DomainSeparator,
+    digest: Bytes32,
+    signatures: BTreeMap<Address, Signature>,
+}
+
+impl<T: StructType> MultiSigned<T> {
+    pub fn new(domain_separator: &DomainSeparator, message: T) -> Self {
+        let digest = sign_hash(domain_separator, &message);
+        Self {
+            message,
+            domain_separator: *domain_separator,
+            digest,
+            signatures: BTreeMap::new(),
+        }
+    }
+
+    pub fn message(&self) -> &T {
+        &self.message
+    }
+
+    pub fn domain_separator(&self) -> &DomainSeparator {
+        &self.domain_separator
+    }
+
+    /// The digest which every signature must sign
+    pub fn digest(&self) -> &Bytes32 {
+        &self.digest
+    }
+
+    /// Adds a signature, returning its signer. A second signature from the
+    /// same signer replaces the first.
+    pub fn add(&mut self, signature: Signature) -> Result<Address, Error> {
+        let signer = signature.recover(&self.digest)?;
+        self.signatures.insert(signer, signature);
+        Ok(signer)
+    }
+
+    /// Every signer so far, in ascending order
+    pub fn signers(&self) -> impl Iterator<Item = &Address> {
+        self.signatures.keys()
+    }
+
+    /// The signatures from signers in `allowlist`, in ascending order of
+    /// signer.
+    pub fn approvals(&self, allowlist: &[Address]) -> Vec<(Address, Signature)> {
+        self.signatures
+            .iter()
+            .filter(|(signer, _)| allowlist.contains(signer))
+            .map(|(signer, signature)| (*signer, *signature))
+            .collect()
+    }
+
+    /// Checks that at least `threshold` signers in `allowlist` have signed,
+    /// and if so returns their signatures for submission: 65 bytes each,
+    /// concatenated in ascending order of signer, as expected by multisig
+    /// contracts such as Safe.
+    pub fn check_threshold(
+        &self,
+        allowlist: &[Address],
+        threshold: usize,
+    ) -> Result<Vec<u8>, Error> {
+        let approvals = self.approvals(allowlist);
+        if approvals.len() < threshold {
+            return Err(Error::ThresholdNotMet {
+                approvals: approvals.len(),
+                threshold,
+            });
+        }
+        let mut packed = Vec::with_capacity(approvals.len() * 65);
+        for (_, signature) in approvals {
+            packed.extend_from_slice(&signature.to_bytes());
+        }
+        Ok(packed)
+    }
+}
@@ -0,0 +1,27 @@
+// Hashing and signing many messages at once, spread across the rayon thread
+// pool. Results are in the order of the messages.
+
+use crate::prelude::*;
+use crate::{hash_struct, StructType};
+#[cfg(feature = "sign")]
+use crate::{sign_typed, DomainSeparator, Error, SecretKey, Signature};
+use rayon::prelude::*;
+
+/// [`hash_struct`](crate::hash_struct) of each message
+pub fn hash_struct_batch<T: StructType + Sync>(messages: &[T]) -> Vec<Bytes32> {
+    messages.par_iter().map(hash_struct).collect()
+}
+
+/// [`sign_typed`](crate::sign_typed) of each message, with the same key and
+/// domain.
+#[cfg(feature = "sign")]
+pub fn sign_typed_batch<T: StructType + Sync>(
+    domain_separator: &DomainSeparator,
+    messages: &[T],
+    key: &SecretKey,
+) -> Result<Vec<Signature>, Error> {
+    messages
+        .par_iter()
+        .map(|message| sign_typed(domain_separator, message, key))
+        .collect()
+}
@@ -0,0 +1,111 @@
+use crate::*;
+use std::any::Any;
+use std::collections::{BTreeMap, BTreeSet};
+
+type Constraint = Box<dyn Fn(&dyn Any) -> Result<(), String> + Send + Sync>;
+
+/// A [`Signer`] which only signs messages of allowed types under allowed
+/// domains, and refuses everything else with [`Error::PolicyViolation`].
+///
+/// This limits what a compromised or buggy caller can get signed. Types may
+/// also be given a constraint on their values, eg: a maximum amount, which is
+/// checked by [`Signer::sign_typed`]. Since a bare [`SigningRequest`] carries
+/// only hashes, requests for constrained types passed to [`Signer::sign`] are
+/// refused.
+///
+/// Types are allowed by their type hash, so a struct of the same name but
+/// with other members is refused. The type hash must be known without a
+/// value: the struct must implement [`StructType::visit_member_types`], as
+/// `#[derive(StructType)]` does, and may not contain a
+/// [`PartialDomain`](crate::PartialDomain).
+pub struct PolicySigner<S> {
+    inner: S,
+    domains: BTreeSet<DomainSeparator>,
+    types: BTreeMap<Bytes32, Option<Constraint>>,
+}
+
+impl<S: Signer> PolicySigner<S> {
+    /// Allows nothing until configured
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            domains: BTreeSet::new(),
+            types: BTreeMap::new(),
+        }
+    }
+
+    pub fn allow_domain(mut self, domain_separator: DomainSeparator) -> Self {
+        self.domains.insert(domain_separator);
+        self
+    }
+
+    /// Allows any message of type `T`
+    ///
+    /// Panics if the type hash of `T` is not known without a value
+    pub fn allow_type<T: StructType>(mut self) -> Self {
+        self.types.insert(type_hash_of::<T>(), None);
+        self
+    }
+
+    /// Allows messages of type `T` for which `constraint` returns Ok. The
+    /// error is reported in the policy violation.
+    ///
+    /// Panics if the type hash of `T` is not known without a value
+    pub fn allow_type_with<T, F>(mut self, constraint: F) -> Self
+    where
+        T: StructType,
+        F: Fn(&T) -> Result<(), String> + Send + Sync + 'static,
+    {
+        let constraint: Constraint = Box::new(move |value| match value.downcast_ref::<T>() {
+            Some(value) => constraint(value),
+            None => Err(format!("Not the allowed {} type", T::TYPE_NAME)),
+        });
+        self.types.insert(type_hash_of::<T>(), Some(constraint));
+        self
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn check_request(&self, request: &SigningRequest) -> Result<Option<&Constraint>, Error> {
+        if !self.domains.contains(request.domain_separator()) {
+            return Err(Error::PolicyViolation("Domain not allowed".to_owned()));
+        }
+        match self.types.get(request.type_hash()) {
+            Some(constraint) => Ok(constraint.as_ref()),
+            None => Err(Error::PolicyViolation(format!(
+                "Type {} not allowed",
+                request.type_name()
+            ))),
+        }
+    }
+}
+
+impl<S: Signer> Signer for PolicySigner<S> {
+    fn address(&self) -> Address {
+        self.inner.address()
+    }
+
+    fn sign(&self, request: &SigningRequest) -> Result<Signature, Error> {
+        if self.check_request(request)?.is_some() {
+            return Err(Error::PolicyViolation(format!(
+                "Values of {} must be checked; use sign_typed",
+                request.type_name()
+            )));
+        }
+        self.inner.sign(request)
+    }
+
+    fn sign_typed<T: StructType>(
+        &self,
+        domain_separator: &DomainSeparator,
+        value: &T,
+    ) -> Result<Signature, Error> {
+        let request = SigningRequest::new(domain_separator, value);
+        if let Some(constraint) = self.check_request(&request)? {
+            constraint(value).map_err(Error::PolicyViolation)?;
+        }
+        self.inner.sign(&request)
+    }
+}
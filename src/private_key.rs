@@ -0,0 +1,62 @@
+use crate::prelude::*;
+use crate::Error;
+use std::fmt;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The 32 bytes of a secp256k1 private key, zeroized when dropped. Debug
+/// output is redacted, and there is no Display.
+///
+/// The key is not validated; [`SecretKey`](crate::SecretKey) does that, and
+/// is what signing functions accept.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PrivateKey(Bytes32);
+
+impl PrivateKey {
+    pub fn from_bytes(bytes: &Bytes32) -> Self {
+        Self(*bytes)
+    }
+
+    /// A key written in place by `fill`, eg: from a random number generator,
+    /// so that no copy of it is left behind.
+    pub fn from_fn(fill: impl FnOnce(&mut Bytes32)) -> Self {
+        let mut key = Self([0u8; 32]);
+        fill(&mut key.0);
+        key
+    }
+
+    /// 64 hex digits, with or without a 0x prefix. The error does not include
+    /// the input.
+    pub fn from_hex(s: &str) -> Result<Self, Error> {
+        let mut key = Self([0u8; 32]);
+        decode_hex_into(s, &mut key.0).map_err(|_| Error::InvalidPrivateKey)?;
+        Ok(key)
+    }
+
+    /// The raw key. Avoid copying it anywhere that is not zeroized.
+    pub fn as_bytes(&self) -> &Bytes32 {
+        &self.0
+    }
+}
+
+/// Bytes32 is Copy, so this cannot clear the caller's copy of the key. Prefer
+/// [`PrivateKey::from_fn`] for keys which are generated, or zeroize the
+/// source afterwards.
+impl From<Bytes32> for PrivateKey {
+    fn from(bytes: Bytes32) -> Self {
+        Self(bytes)
+    }
+}
+
+impl Drop for PrivateKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for PrivateKey {}
+
+impl fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PrivateKey(<redacted>)")
+    }
+}
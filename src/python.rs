@@ -0,0 +1,79 @@
+//! Python bindings through PyO3. Requires the `python` feature.
+//!
+//! The `eip_712_derive` module takes typed data as eth_signTypedData_v4 JSON
+//! strings (`json.dumps` of the dict given to eth-sig-util) and returns
+//! hashes and signatures as `bytes`. Errors are raised as `ValueError`s with
+//! this crate's messages.
+//!
+//! Build the extension with maturin from the crate root, eg: `maturin build`.
+//! pyproject.toml enables this feature and `pyo3/extension-module`, and
+//! maturin builds the crate as a cdylib for the extension.
+
+use crate::dynamic::TypedData;
+use crate::Error;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::borrow::Cow;
+
+impl From<Error> for PyErr {
+    fn from(error: Error) -> Self {
+        PyValueError::new_err(error.to_string())
+    }
+}
+
+/// The digest of typed data JSON, which is what gets signed
+#[pyfunction]
+pub fn hash_typed_data(json: &str) -> Result<Cow<'static, [u8]>, Error> {
+    Ok(TypedData::from_json(json)?.digest()?.to_vec().into())
+}
+
+/// hashStruct of the message of typed data JSON
+#[pyfunction]
+pub fn hash_struct(json: &str) -> Result<Cow<'static, [u8]>, Error> {
+    Ok(TypedData::from_json(json)?.struct_hash()?.to_vec().into())
+}
+
+/// See [`domain_separator_json`](crate::domain_separator_json)
+#[pyfunction]
+pub fn domain_separator(domain: &str) -> Result<Cow<'static, [u8]>, Error> {
+    Ok(crate::domain_separator_json(domain)?
+        .as_bytes()
+        .to_vec()
+        .into())
+}
+
+/// Signs typed data JSON with a 32 byte private key, returning r ‖ s ‖ v with
+/// v as 27 or 28, like eth_signTypedData_v4.
+///
+/// The key is borrowed from the Python `bytes`, which this cannot zeroize.
+#[cfg(feature = "sign")]
+#[pyfunction]
+pub fn sign_typed_data(json: &str, private_key: &[u8]) -> Result<Cow<'static, [u8]>, Error> {
+    use std::convert::TryInto;
+
+    let key = private_key
+        .try_into()
+        .map_err(|_| Error::InvalidPrivateKey)
+        .and_then(crate::SecretKey::from_bytes)?;
+    let signature = TypedData::from_json(json)?.sign(&key)?;
+    Ok(signature.to_bytes().to_vec().into())
+}
+
+/// The checksummed address which produced a 65 byte `signature` over typed
+/// data JSON
+#[pyfunction]
+pub fn recover_typed_data(json: &str, signature: &[u8]) -> Result<String, Error> {
+    let signature = crate::Signature::from_bytes(signature)?;
+    Ok(TypedData::from_json(json)?.recover(&signature)?.to_string())
+}
+
+#[pymodule]
+fn eip_712_derive(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(hash_typed_data, m)?)?;
+    m.add_function(wrap_pyfunction!(hash_struct, m)?)?;
+    m.add_function(wrap_pyfunction!(domain_separator, m)?)?;
+    #[cfg(feature = "sign")]
+    m.add_function(wrap_pyfunction!(sign_typed_data, m)?)?;
+    m.add_function(wrap_pyfunction!(recover_typed_data, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,291 @@
+//! Checks against a deployed verifying contract. Requires the `rpc` feature.
+//!
+//! This module does not ship a JSON-RPC client. Instead it asks for the one
+//! operation it needs through the [`Provider`] trait, so that it can sit on
+//! top of whichever client the application already uses.
+
+use crate::prelude::*;
+use crate::{Address, DomainSeparator, Error, FieldDifference, PartialDomain, U256};
+use std::fmt;
+use std::future::Future;
+
+/// The slice of an Ethereum JSON-RPC client needed by this module.
+pub trait Provider {
+    type Error: fmt::Display;
+    /// Performs an `eth_call` of `data` against `to` at the latest block,
+    /// returning the raw return data.
+    fn call(
+        &self,
+        to: &Address,
+        data: &[u8],
+    ) -> impl Future<Output = Result<Vec<u8>, Self::Error>> + Send;
+}
+
+/// Provider errors are reported as [`Error::Provider`], with their message
+fn provider_error(error: impl fmt::Display) -> Error {
+    Error::Provider(error.to_string())
+}
+
+/// Calls `DOMAIN_SEPARATOR()` on the verifying contract and checks that it
+/// matches the domain separator of `domain`.
+///
+/// Any difference between the two means that every signature produced locally
+/// will be rejected by the contract, so this is worth running once at startup.
+/// On a mismatch the contract's ERC-5267 `eip712Domain()` is read, if it has
+/// one, to report which fields differ.
+pub async fn verify_domain_separator<P: Provider>(
+    provider: &P,
+    verifying_contract: &Address,
+    domain: &PartialDomain,
+) -> Result<(), Error> {
+    let local = &DomainSeparator::new(domain);
+    let selector = keccak("DOMAIN_SEPARATOR()");
+    let data = provider
+        .call(verifying_contract, &selector[..4])
+        .await
+        .map_err(provider_error)?;
+
+    // The return value is a single bytes32, which the ABI encodes as is.
+    if data.len() != 32 {
+        return Err(Error::MalformedResponse(data));
+    }
+    let mut on_chain = [0u8; 32];
+    on_chain.copy_from_slice(&data);
+    let on_chain = DomainSeparator::from_bytes(&on_chain);
+
+    if &on_chain != local {
+        let differences = match fetch_domain(provider, verifying_contract).await {
+            Ok(on_chain) => domain_differences(domain, &on_chain),
+            Err(_) => Vec::new(),
+        };
+        return Err(Error::DomainSeparatorMismatch {
+            contract: *verifying_contract,
+            local: *local,
+            on_chain,
+            differences,
+        });
+    }
+    Ok(())
+}
+
+fn domain_differences(local: &PartialDomain, on_chain: &Erc5267Domain) -> Vec<FieldDifference> {
+    let mut differences = Vec::new();
+    let mut compare = |field: &'static str, local: Option<String>, on_chain: Option<String>| {
+        if local != on_chain {
+            differences.push(FieldDifference {
+                field,
+                local,
+                on_chain,
+            });
+        }
+    };
+    let text = |s: &String| format!("{:?}", s);
+    compare(
+        "name",
+        local.name.as_ref().map(text),
+        on_chain.name.as_ref().map(text),
+    );
+    compare(
+        "version",
+        local.version.as_ref().map(text),
+        on_chain.version.as_ref().map(text),
+    );
+    compare(
+        "chainId",
+        local.chain_id.map(|c| c.to_decimal_string()),
+        on_chain.chain_id.map(|c| c.to_decimal_string()),
+    );
+    compare(
+        "verifyingContract",
+        local.verifying_contract.map(|a| a.to_checksum_string()),
+        on_chain.verifying_contract.map(|a| a.to_checksum_string()),
+    );
+    compare(
+        "salt",
+        local.salt.map(|s| s.to_hex()),
+        on_chain.salt.map(|s| s.to_hex()),
+    );
+    differences
+}
+
+/// A domain as reported by ERC-5267 `eip712Domain()`. Fields which the
+/// contract does not use are `None`, and are left out of the EIP712Domain type.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Erc5267Domain {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub chain_id: Option<U256>,
+    pub verifying_contract: Option<Address>,
+    pub salt: Option<Bytes32>,
+    /// EIP numbers of extensions to the domain. Typically empty.
+    pub extensions: Vec<U256>,
+}
+
+impl Erc5267Domain {
+    /// Fails if there are extensions, since their fields are unknown.
+    pub fn domain_separator(&self) -> Result<DomainSeparator, Vec<U256>> {
+        Ok(DomainSeparator::new(&self.to_partial_domain()?))
+    }
+
+    /// The domain with the fields the contract uses. Fails if there are
+    /// extensions, since their fields are unknown.
+    pub fn to_partial_domain(&self) -> Result<PartialDomain, Vec<U256>> {
+        if !self.extensions.is_empty() {
+            return Err(self.extensions.clone());
+        }
+        Ok(PartialDomain {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            chain_id: self.chain_id,
+            verifying_contract: self.verifying_contract,
+            salt: self.salt,
+        })
+    }
+
+    /// Decodes the ABI encoded return value of `eip712Domain()`:
+    /// `(bytes1 fields, string name, string version, uint256 chainId,
+    /// address verifyingContract, bytes32 salt, uint256[] extensions)`
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let word = |index: usize| -> Option<Bytes32> {
+            let mut word = [0u8; 32];
+            word.copy_from_slice(data.get(index * 32..(index + 1) * 32)?);
+            Some(word)
+        };
+        // Dynamic values are found at an offset, and begin with their length
+        let tail = |index: usize| -> Option<(usize, &[u8])> {
+            let offset = as_usize(&word(index)?)?;
+            let rest = data.get(offset..)?;
+            let len = as_usize(rest.get(..32)?)?;
+            Some((len, &rest[32..]))
+        };
+        let string = |index: usize| -> Option<String> {
+            let (len, rest) = tail(index)?;
+            String::from_utf8(rest.get(..len)?.to_vec()).ok()
+        };
+
+        let fields = word(0)?[0];
+        let has = |bit: u8| fields & (1 << bit) != 0;
+        let mut domain = Self::default();
+        if has(0) {
+            domain.name = Some(string(1)?);
+        }
+        if has(1) {
+            domain.version = Some(string(2)?);
+        }
+        if has(2) {
+            domain.chain_id = Some(U256(word(3)?));
+        }
+        if has(3) {
+            let mut address = [0u8; 20];
+            address.copy_from_slice(&word(4)?[12..]);
+            domain.verifying_contract = Some(Address(address));
+        }
+        if has(4) {
+            domain.salt = Some(word(5)?);
+        }
+        let (len, rest) = tail(6)?;
+        for i in 0..len {
+            let mut extension = [0u8; 32];
+            extension.copy_from_slice(rest.get(i * 32..(i + 1) * 32)?);
+            domain.extensions.push(U256(extension));
+        }
+        Some(domain)
+    }
+}
+
+/// Reads a 32 byte big-endian word, if it is small enough to be an offset
+fn as_usize(word: &[u8]) -> Option<usize> {
+    let (high, low) = word.split_at(24);
+    if high.iter().any(|&b| b != 0) {
+        return None;
+    }
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(low);
+    let value = u64::from_be_bytes(bytes);
+    if value > usize::MAX as u64 {
+        return None;
+    }
+    Some(value as usize)
+}
+
+/// Calls ERC-5267 `eip712Domain()` on `contract`.
+pub async fn fetch_domain<P: Provider>(
+    provider: &P,
+    contract: &Address,
+) -> Result<Erc5267Domain, Error> {
+    let selector = keccak("eip712Domain()");
+    let data = provider
+        .call(contract, &selector[..4])
+        .await
+        .map_err(provider_error)?;
+    Erc5267Domain::decode(&data).ok_or(Error::MalformedResponse(data))
+}
+
+/// The domain separator of a contract which implements ERC-5267, so that it
+/// need not be configured locally.
+pub async fn fetch_domain_separator<P: Provider>(
+    provider: &P,
+    contract: &Address,
+) -> Result<DomainSeparator, Error> {
+    fetch_domain(provider, contract)
+        .await?
+        .domain_separator()
+        .map_err(Error::UnsupportedExtensions)
+}
+
+/// Returned by ERC-1271 `isValidSignature` for valid signatures. It is the
+/// selector of `isValidSignature(bytes32,bytes)`.
+const ERC1271_MAGIC_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
+
+/// Asks the smart contract account `signer` whether `signature` over `digest`
+/// is valid, with ERC-1271 `isValidSignature(bytes32,bytes)`.
+///
+/// Contract accounts such as Safes cannot produce ECDSA signatures, and define
+/// their own signature formats, so `signature` is passed through as is. An
+/// account without code answers with empty data, which is taken as invalid.
+pub async fn is_valid_signature<P: Provider>(
+    provider: &P,
+    signer: &Address,
+    digest: &Bytes32,
+    signature: &[u8],
+) -> Result<bool, Error> {
+    // selector ‖ hash ‖ offset of the bytes ‖ length ‖ zero padded bytes
+    let padded_len = signature.len().div_ceil(32) * 32;
+    let mut data = Vec::with_capacity(4 + 32 * 3 + padded_len);
+    data.extend_from_slice(&ERC1271_MAGIC_VALUE);
+    data.extend_from_slice(digest);
+    data.extend_from_slice(&U256::from(64u64).0);
+    data.extend_from_slice(&U256::from(signature.len() as u64).0);
+    data.extend_from_slice(signature);
+    data.resize(4 + 32 * 3 + padded_len, 0);
+
+    let response = provider.call(signer, &data).await.map_err(provider_error)?;
+    match response.len() {
+        0 => Ok(false),
+        // The return value is a bytes4, which the ABI pads at the end
+        32 => Ok(response[..4] == ERC1271_MAGIC_VALUE),
+        _ => Err(Error::MalformedResponse(response)),
+    }
+}
+
+/// Checks that `signer` signed `message`, whether it is an externally owned
+/// account or a smart contract account.
+///
+/// An ECDSA signature which recovers to `signer` is accepted without a call.
+/// Otherwise the signature is checked with ERC-1271 against `signer`.
+#[cfg(feature = "verify")]
+pub async fn verify_signature<P: Provider, T: StructType>(
+    provider: &P,
+    domain_separator: &DomainSeparator,
+    message: &T,
+    signature: &[u8],
+    signer: &Address,
+) -> Result<bool, Error> {
+    let digest = crate::sign_hash(domain_separator, message);
+    if let Ok(parsed) = crate::Signature::from_bytes(signature) {
+        if parsed.recover(&digest).as_ref() == Ok(signer) {
+            return Ok(true);
+        }
+    }
+    is_valid_signature(provider, signer, &digest, signature).await
+}
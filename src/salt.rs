@@ -0,0 +1,80 @@
+use crate::prelude::*;
+use crate::summary::{Hints, SummaryValue};
+use crate::{Address, Error, U256};
+use std::borrow::Cow;
+
+/// A value for the `salt` field of a domain.
+///
+/// The salt is the last resort for separating domains which would otherwise
+/// be identical, eg: two deployments of the same contract which share a name,
+/// version and address on different forks. It only does so if every
+/// deployment uses a different, non-zero salt, so the constructors here
+/// refuse zero and derive salts from something unique.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Salt(Bytes32);
+
+impl Salt {
+    /// Fails if `bytes` is zero
+    pub fn new(bytes: Bytes32) -> Result<Self, Error> {
+        if bytes == [0u8; 32] {
+            return Err(Error::InvalidSalt);
+        }
+        Ok(Self(bytes))
+    }
+
+    /// keccak256(label). Use a label which names the deployment, eg:
+    /// "myprotocol-mainnet-2024", rather than just the protocol.
+    pub fn from_label(label: &str) -> Self {
+        Self(keccak(label))
+    }
+
+    /// keccak256(label ‖ chainId ‖ verifyingContract), which is unique to a
+    /// deployment even if the label is reused.
+    pub fn for_deployment(label: &str, chain_id: &U256, verifying_contract: &Address) -> Self {
+        let mut data = Vec::with_capacity(label.len() + 52);
+        data.extend_from_slice(label.as_bytes());
+        data.extend_from_slice(&chain_id.0);
+        data.extend_from_slice(&verifying_contract.0);
+        Self(keccak(data))
+    }
+
+    /// A salt from the operating system's random number generator. Requires
+    /// the `random` feature. Remember to persist it; a salt which changes
+    /// invalidates every signature made under the old one.
+    #[cfg(feature = "random")]
+    pub fn random() -> Self {
+        loop {
+            let mut bytes = [0u8; 32];
+            getrandom::getrandom(&mut bytes).expect("Failed to obtain randomness");
+            if let Ok(salt) = Self::new(bytes) {
+                return salt;
+            }
+        }
+    }
+
+    pub fn as_bytes(&self) -> &Bytes32 {
+        &self.0
+    }
+}
+
+impl From<Salt> for Bytes32 {
+    fn from(salt: Salt) -> Self {
+        salt.0
+    }
+}
+
+impl MemberType for Salt {
+    fn type_name() -> Cow<'static, str> {
+        Cow::Borrowed("bytes32")
+    }
+    fn encode_data(&self) -> Bytes32 {
+        self.0
+    }
+    #[inline(always)]
+    fn add_members(&self, _builder: &mut TypeHashBuilder) {}
+    fn summarize(&self, hints: &Hints) -> SummaryValue {
+        self.0.summarize(hints)
+    }
+}
+
+impl AtomicType for Salt {}
@@ -0,0 +1,73 @@
+use crate::prelude::*;
+use crate::*;
+use std::fmt;
+use zeroize::ZeroizeOnDrop;
+
+/// A validated private key which is zeroized when dropped. Signing functions
+/// only accept keys in this form, so that key material is not left in memory
+/// by copies of raw bytes.
+///
+/// Debug prints the address instead of the key.
+#[derive(Clone)]
+pub struct SecretKey {
+    key: PrivateKey,
+    address: Address,
+}
+
+impl SecretKey {
+    /// Fails with [`Error::InvalidPrivateKey`] if `key` is zero or not less
+    /// than the secp256k1 curve order.
+    pub fn new(key: PrivateKey) -> Result<Self, Error> {
+        Ok(Self {
+            address: signer_address(&key)?,
+            key,
+        })
+    }
+
+    pub fn from_bytes(key: &Bytes32) -> Result<Self, Error> {
+        Self::new(PrivateKey::from_bytes(key))
+    }
+
+    /// 64 hex digits, with or without a 0x prefix
+    pub fn from_hex(s: &str) -> Result<Self, Error> {
+        Self::new(PrivateKey::from_hex(s)?)
+    }
+
+    /// The raw key. Avoid copying it anywhere that is not zeroized.
+    pub fn as_bytes(&self) -> &Bytes32 {
+        self.key.as_bytes()
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+}
+
+// The key zeroizes itself
+impl ZeroizeOnDrop for SecretKey {}
+
+impl PartialEq for SecretKey {
+    fn eq(&self, other: &Self) -> bool {
+        // Keys are equal iff their addresses are, which avoids comparing the
+        // key material itself.
+        self.address == other.address
+    }
+}
+
+impl Eq for SecretKey {}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretKey({})", self.address.to_checksum_string())
+    }
+}
+
+impl Signer for SecretKey {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn sign(&self, request: &SigningRequest) -> Result<Signature, Error> {
+        sign_digest(request.digest(), self)
+    }
+}
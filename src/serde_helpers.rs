@@ -0,0 +1,223 @@
+//! serde support. Requires the `serde` feature.
+//!
+//! [`Signature`] serializes as the 0x-prefixed hex of its 65 byte r ‖ s ‖ v
+//! form, and deserializes from either that or an `{r, s, v}` object. To
+//! serialize as the object instead, annotate the field with
+//! `#[serde(with = "eip_712_derive::serde_helpers::rsv")]`.
+//!
+//! [`Address`] serializes as its EIP-55 checksummed hex, and
+//! [`DomainSeparator`] as 0x-prefixed hex.
+//!
+//! [`U256`] and the narrower uints like [`Uint64`](crate::Uint64) serialize
+//! as decimal strings, since JSON numbers lose precision beyond 2^53, and
+//! deserialize from decimal or 0x-prefixed hex strings or from numbers.
+//! BytesN are plain arrays, which serde treats as tuples, so annotate them
+//! with `#[serde(with = "eip_712_derive::serde_helpers::hex_bytes")]` for
+//! 0x-prefixed hex. The same works for `bytes` members held as `Vec<u8>`.
+
+use crate::{Address, DomainSeparator, Signature, U256};
+use serde::de::{Error as _, Unexpected, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::TryFrom;
+use std::fmt;
+
+fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn from_hex<E: serde::de::Error>(s: &str) -> Result<Vec<u8>, E> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    hex::decode(s).map_err(E::custom)
+}
+
+fn from_hex_array<'de, D: Deserializer<'de>, const N: usize>(
+    deserializer: D,
+) -> Result<[u8; N], D::Error> {
+    let s = String::deserialize(deserializer)?;
+    let bytes = from_hex::<D::Error>(&s)?;
+    let mut result = [0u8; N];
+    if bytes.len() != N {
+        return Err(D::Error::invalid_length(
+            bytes.len(),
+            &&*format!("{} bytes", N),
+        ));
+    }
+    result.copy_from_slice(&bytes);
+    Ok(result)
+}
+
+impl Serialize for Address {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_checksum_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        from_hex_array(deserializer).map(Address)
+    }
+}
+
+impl Serialize for DomainSeparator {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&to_hex(self.as_bytes()))
+    }
+}
+
+impl<'de> Deserialize<'de> for DomainSeparator {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        from_hex_array(deserializer).map(|bytes| DomainSeparator::from_bytes(&bytes))
+    }
+}
+
+impl Serialize for U256 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+struct U256Visitor;
+
+impl Visitor<'_> for U256Visitor {
+    type Value = U256;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a uint256 as a number, or a decimal or 0x-prefixed hex string")
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<U256, E> {
+        Ok(U256::from(value))
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<U256, E> {
+        let parsed = if value.starts_with("0x") {
+            U256::from_hex_str(value)
+        } else {
+            U256::from_dec_str(value)
+        };
+        parsed.ok_or_else(|| E::invalid_value(Unexpected::Str(value), &self))
+    }
+}
+
+impl<'de> Deserialize<'de> for U256 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(U256Visitor)
+    }
+}
+
+macro_rules! impl_uint_serde {
+    ($($T:ident)+) => {
+        $(
+            impl Serialize for crate::$T {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    self.value().serialize(serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for crate::$T {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let value = U256::deserialize(deserializer)?;
+                    crate::$T::new(value).ok_or_else(|| {
+                        D::Error::custom(format!(
+                            "{} does not fit in {} bits",
+                            value,
+                            crate::$T::BITS
+                        ))
+                    })
+                }
+            }
+        )+
+    }
+}
+
+impl_uint_serde! {
+    Uint8 Uint16 Uint24 Uint32 Uint40 Uint48 Uint56 Uint64
+    Uint72 Uint80 Uint88 Uint96 Uint104 Uint112 Uint120 Uint128
+    Uint136 Uint144 Uint152 Uint160 Uint168 Uint176 Uint184 Uint192
+    Uint200 Uint208 Uint216 Uint224 Uint232 Uint240 Uint248
+}
+
+/// Serializes BytesN or `Vec<u8>` as 0x-prefixed hex. For use with
+/// `#[serde(with = "...")]`. Deserializing checks the length of BytesN.
+pub mod hex_bytes {
+    use super::*;
+
+    pub fn serialize<T: AsRef<[u8]>, S: Serializer>(
+        bytes: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&to_hex(bytes.as_ref()))
+    }
+
+    /// Accepts hex with or without the 0x prefix
+    pub fn deserialize<'de, T: TryFrom<Vec<u8>>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let bytes = from_hex::<D::Error>(&s)?;
+        let len = bytes.len();
+        T::try_from(bytes).map_err(|_| D::Error::invalid_length(len, &"the declared length"))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Rsv {
+    r: String,
+    s: String,
+    v: u8,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SignatureRepr {
+    Hex(String),
+    Rsv(Rsv),
+}
+
+impl Serialize for Signature {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&to_hex(&self.to_bytes()))
+    }
+}
+
+impl<'de> Deserialize<'de> for Signature {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = match SignatureRepr::deserialize(deserializer)? {
+            SignatureRepr::Hex(s) => from_hex(&s)?,
+            SignatureRepr::Rsv(rsv) => {
+                let mut bytes = from_hex::<D::Error>(&rsv.r)?;
+                let s = from_hex::<D::Error>(&rsv.s)?;
+                if bytes.len() != 32 || s.len() != 32 {
+                    return Err(D::Error::custom("r and s must be 32 bytes"));
+                }
+                bytes.extend_from_slice(&s);
+                bytes.push(rsv.v);
+                bytes
+            }
+        };
+        Signature::from_bytes(&bytes).map_err(D::Error::custom)
+    }
+}
+
+/// Serializes a [`Signature`] as `{r, s, v}`, with r and s as 0x-prefixed hex
+/// and v as 27 or 28. For use with `#[serde(with = "...")]`.
+pub mod rsv {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        signature: &Signature,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let rsv = Rsv {
+            r: to_hex(&signature.r),
+            s: to_hex(&signature.s),
+            v: signature.recovery_id.to_eth_v(),
+        };
+        rsv.serialize(serializer)
+    }
+
+    /// Accepts either representation
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Signature, D::Error> {
+        Signature::deserialize(deserializer)
+    }
+}
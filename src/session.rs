@@ -0,0 +1,148 @@
+use crate::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What a session key may sign, and when.
+///
+/// Domains and types are enforced locally by [`SessionSigner`]. Targets, the
+/// contracts the session may call, can only be enforced on-chain and are
+/// included for the validator in [`SessionScope::abi_encode`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SessionScope {
+    pub valid_after: SystemTime,
+    pub valid_until: SystemTime,
+    pub domains: Vec<DomainSeparator>,
+    pub types: Vec<&'static str>,
+    pub targets: Vec<Address>,
+}
+
+impl SessionScope {
+    /// Allows nothing until configured
+    pub fn new(valid_after: SystemTime, valid_until: SystemTime) -> Self {
+        Self {
+            valid_after,
+            valid_until,
+            domains: Vec::new(),
+            types: Vec::new(),
+            targets: Vec::new(),
+        }
+    }
+
+    pub fn allow_domain(mut self, domain_separator: DomainSeparator) -> Self {
+        self.domains.push(domain_separator);
+        self
+    }
+
+    pub fn allow_type<T: StructType>(mut self) -> Self {
+        self.types.push(T::TYPE_NAME);
+        self
+    }
+
+    pub fn allow_target(mut self, target: Address) -> Self {
+        self.targets.push(target);
+        self
+    }
+
+    /// Checks a request made at `now`. Since a [`SigningRequest`] can only be
+    /// made from a typed value, the type and domain checked are those of the
+    /// digest which is signed.
+    pub fn check(&self, request: &SigningRequest, now: SystemTime) -> Result<(), Error> {
+        if now < self.valid_after || now >= self.valid_until {
+            return Err(Error::SessionExpired);
+        }
+        if !self.domains.contains(request.domain_separator()) {
+            return Err(Error::PolicyViolation(
+                "Domain not in session scope".to_owned(),
+            ));
+        }
+        if !self.types.contains(&request.type_name()) {
+            return Err(Error::PolicyViolation(format!(
+                "Type {} not in session scope",
+                request.type_name()
+            )));
+        }
+        Ok(())
+    }
+
+    /// The ABI encoding of `(address sessionKey, uint48 validAfter,
+    /// uint48 validUntil, bytes32[] domainSeparators, address[] targets)`,
+    /// with times in seconds since the Unix epoch, for registering the
+    /// session with an on-chain validator.
+    pub fn abi_encode(&self, session_key: &Address) -> Vec<u8> {
+        let seconds = |time: SystemTime| {
+            time.duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs().min((1 << 48) - 1))
+        };
+        let word = |value: u64| {
+            let mut word = [0u8; 32];
+            word[24..].copy_from_slice(&value.to_be_bytes());
+            word
+        };
+        let mut encoded = Vec::with_capacity(5 * 32);
+        encoded.extend_from_slice(&session_key.encode_data());
+        encoded.extend_from_slice(&word(seconds(self.valid_after)));
+        encoded.extend_from_slice(&word(seconds(self.valid_until)));
+
+        let domains_offset = 5 * 32;
+        let targets_offset = domains_offset + (1 + self.domains.len()) * 32;
+        encoded.extend_from_slice(&word(domains_offset as u64));
+        encoded.extend_from_slice(&word(targets_offset as u64));
+
+        encoded.extend_from_slice(&word(self.domains.len() as u64));
+        for domain_separator in &self.domains {
+            encoded.extend_from_slice(domain_separator.as_bytes());
+        }
+        encoded.extend_from_slice(&word(self.targets.len() as u64));
+        for target in &self.targets {
+            encoded.extend_from_slice(&target.encode_data());
+        }
+        encoded
+    }
+}
+
+/// A short-lived key which signs only within a [`SessionScope`], as used by
+/// account abstraction wallets to delegate limited authority to an app.
+pub struct SessionSigner {
+    key: SecretKey,
+    scope: SessionScope,
+}
+
+impl SessionSigner {
+    pub fn new(key: SecretKey, scope: SessionScope) -> Self {
+        Self { key, scope }
+    }
+
+    /// A session with a fresh random key. Requires the `random` feature.
+    #[cfg(feature = "random")]
+    pub fn generate(scope: SessionScope) -> Self {
+        let key = loop {
+            let key = PrivateKey::from_fn(|bytes| {
+                getrandom::getrandom(bytes).expect("Failed to obtain randomness")
+            });
+            if let Ok(key) = SecretKey::new(key) {
+                break key;
+            }
+        };
+        Self::new(key, scope)
+    }
+
+    pub fn scope(&self) -> &SessionScope {
+        &self.scope
+    }
+
+    /// The scope, encoded for the on-chain validator. See
+    /// [`SessionScope::abi_encode`].
+    pub fn export_scope(&self) -> Vec<u8> {
+        self.scope.abi_encode(&self.key.address())
+    }
+}
+
+impl Signer for SessionSigner {
+    fn address(&self) -> Address {
+        self.key.address()
+    }
+
+    fn sign(&self, request: &SigningRequest) -> Result<Signature, Error> {
+        self.scope.check(request, SystemTime::now())?;
+        self.key.sign(request)
+    }
+}
@@ -0,0 +1,157 @@
+#[cfg(feature = "verify")]
+use crate::ecdsa::{self, PublicKey};
+use crate::prelude::*;
+use crate::Error;
+#[cfg(feature = "verify")]
+use crate::{metrics, Address};
+
+/// Which of the two candidate public keys produced a signature, encoded as
+/// the parity of the y coordinate of the signature's R point.
+///
+/// Ethereum represents this in different ways in different places: ecrecover
+/// and legacy transactions expect `v` to be 27 or 28 while typed transactions
+/// and some libraries use the bare parity 0 or 1. Use the explicit conversions
+/// to get the right one.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum RecoveryId {
+    Parity0,
+    Parity1,
+}
+
+impl RecoveryId {
+    /// 0 or 1
+    pub fn to_parity(self) -> u8 {
+        match self {
+            RecoveryId::Parity0 => 0,
+            RecoveryId::Parity1 => 1,
+        }
+    }
+
+    /// 27 or 28, as expected by ecrecover
+    pub fn to_eth_v(self) -> u8 {
+        self.to_parity() + 27
+    }
+
+    pub fn from_parity(parity: u8) -> Option<Self> {
+        match parity {
+            0 => Some(RecoveryId::Parity0),
+            1 => Some(RecoveryId::Parity1),
+            _ => None,
+        }
+    }
+
+    /// Only accepts 27 or 28. For values which may be either form, try
+    /// from_parity as well.
+    pub fn from_eth_v(v: u8) -> Option<Self> {
+        Self::from_parity(v.wrapping_sub(27))
+    }
+}
+
+/// A secp256k1 signature over an EIP-712 digest.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Signature {
+    pub r: Bytes32,
+    pub s: Bytes32,
+    pub recovery_id: RecoveryId,
+}
+
+impl Signature {
+    /// From the 64 byte r ‖ s serialization and the recovery id
+    pub fn new(rs: &[u8; 64], recovery_id: RecoveryId) -> Self {
+        let mut r = [0u8; 32];
+        let mut s = [0u8; 32];
+        r.copy_from_slice(&rs[..32]);
+        s.copy_from_slice(&rs[32..]);
+        Self { r, s, recovery_id }
+    }
+
+    /// r ‖ s
+    pub fn rs(&self) -> [u8; 64] {
+        let mut result = [0u8; 64];
+        result[..32].copy_from_slice(&self.r);
+        result[32..].copy_from_slice(&self.s);
+        result
+    }
+
+    /// r ‖ s ‖ v, with v being 27 or 28. This is the form returned by wallets
+    /// from eth_signTypedData and accepted by most contracts.
+    pub fn to_bytes(&self) -> [u8; 65] {
+        let mut result = [0u8; 65];
+        result[..64].copy_from_slice(&self.rs());
+        result[64] = self.recovery_id.to_eth_v();
+        result
+    }
+
+    /// Parses r ‖ s ‖ v, accepting v as either 27/28 or 0/1.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != 65 {
+            return Err(Error::MalformedSignature);
+        }
+        let v = bytes[64];
+        let recovery_id = RecoveryId::from_eth_v(v)
+            .or_else(|| RecoveryId::from_parity(v))
+            .ok_or(Error::InvalidRecoveryId(v))?;
+        let mut rs = [0u8; 64];
+        rs.copy_from_slice(&bytes[..64]);
+        Ok(Self::new(&rs, recovery_id))
+    }
+
+    /// (EIP-2098) The 64 byte compact form r ‖ yParityAndS, with the
+    /// recovery id in the top bit of s.
+    ///
+    /// None if s is in the upper half of the curve order, where the top bit is
+    /// taken. Signatures made by this crate are always in the lower half.
+    pub fn to_compact(&self) -> Option<[u8; 64]> {
+        if self.s[0] & 0x80 != 0 {
+            return None;
+        }
+        let mut result = self.rs();
+        result[32] |= self.recovery_id.to_parity() << 7;
+        Some(result)
+    }
+
+    /// Parses the 64 byte compact form of EIP-2098.
+    pub fn from_compact(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != 64 {
+            return Err(Error::MalformedSignature);
+        }
+        let mut rs = [0u8; 64];
+        rs.copy_from_slice(bytes);
+        let recovery_id = if rs[32] & 0x80 != 0 {
+            RecoveryId::Parity1
+        } else {
+            RecoveryId::Parity0
+        };
+        rs[32] &= 0x7f;
+        Ok(Self::new(&rs, recovery_id))
+    }
+
+    /// Recovers the address of the signer of `digest`.
+    ///
+    /// Any signature recovers to some address, so this alone does not verify
+    /// anything. Compare the result with the expected signer.
+    #[cfg(feature = "verify")]
+    pub fn recover(&self, digest: &Bytes32) -> Result<Address, Error> {
+        let start = metrics::start();
+        let public_key = ecdsa::recover(digest, &self.rs(), self.recovery_id.to_parity());
+        metrics::verify(start);
+        Ok(address_of_public_key(&public_key?))
+    }
+}
+
+/// The address is the last 20 bytes of the keccak of the uncompressed public
+/// key, without the leading 0x04 tag.
+#[cfg(feature = "verify")]
+pub(crate) fn address_of_public_key(public_key: &PublicKey) -> Address {
+    let hash = keccak(&public_key[..]);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    Address(address)
+}
+
+/// The address which signatures made with `key` recover to.
+#[cfg(feature = "sign")]
+pub fn signer_address(key: &crate::PrivateKey) -> Result<Address, Error> {
+    let public_key = ecdsa::public_key(key.as_bytes())?;
+    Ok(address_of_public_key(&public_key))
+}
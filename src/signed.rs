@@ -0,0 +1,98 @@
+#[cfg(feature = "verify")]
+use crate::sign_hash;
+use crate::{Address, DomainSeparator, Error, Signature, Signer, StructType};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A message together with its signature, the domain it was signed under
+/// and the address which claims to have signed it.
+///
+/// Keeping these together avoids verifying a signature against the wrong
+/// payload. The parts are only accessible as a whole, and a value which was
+/// deserialized, or otherwise came from an untrusted source, must be checked
+/// with [`Signed::verify`] before the message is acted on.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct Signed<T> {
+    primary_type: String,
+    message: T,
+    domain_separator: DomainSeparator,
+    signature: Signature,
+    signer: Address,
+}
+
+impl<T: StructType> Signed<T> {
+    pub fn sign<S: Signer>(
+        signer: &S,
+        domain_separator: &DomainSeparator,
+        message: T,
+    ) -> Result<Self, Error> {
+        let signature = signer.sign_typed(domain_separator, &message)?;
+        Ok(Self {
+            primary_type: T::TYPE_NAME.to_owned(),
+            message,
+            domain_separator: *domain_separator,
+            signature,
+            signer: signer.address(),
+        })
+    }
+
+    /// Checks that the message was signed by the claimed signer under
+    /// `domain_separator`, and returns it.
+    ///
+    /// Each cause of failure has its own error: [`Error::TypeMismatch`] if the
+    /// envelope was made for another type, [`Error::DomainMismatch`],
+    /// [`Error::SignerMismatch`] if the signature is valid but from someone
+    /// else, and the signature parsing errors.
+    #[cfg(feature = "verify")]
+    pub fn verify(&self, domain_separator: &DomainSeparator) -> Result<&T, Error> {
+        if self.primary_type != T::TYPE_NAME {
+            return Err(Error::TypeMismatch {
+                expected: T::TYPE_NAME.to_owned(),
+                found: self.primary_type.clone(),
+            });
+        }
+        if &self.domain_separator != domain_separator {
+            return Err(Error::DomainMismatch);
+        }
+        let recovered = self
+            .signature
+            .recover(&sign_hash(&self.domain_separator, &self.message))?;
+        if recovered != self.signer {
+            return Err(Error::SignerMismatch {
+                expected: self.signer,
+                recovered,
+            });
+        }
+        Ok(&self.message)
+    }
+
+    /// The claimed signer, which is only trustworthy after verification.
+    pub fn signer(&self) -> &Address {
+        &self.signer
+    }
+
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    pub fn domain_separator(&self) -> &DomainSeparator {
+        &self.domain_separator
+    }
+
+    /// The message, without verifying it
+    pub fn message_unverified(&self) -> &T {
+        &self.message
+    }
+
+    /// message, domain separator, signature, signer
+    pub fn into_parts(self) -> (T, DomainSeparator, Signature, Address) {
+        (
+            self.message,
+            self.domain_separator,
+            self.signature,
+            self.signer,
+        )
+    }
+}
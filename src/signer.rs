@@ -0,0 +1,157 @@
+use crate::prelude::*;
+use crate::{hash_struct, sign_hash_of, type_hash, Address, DomainSeparator, Error, Signature};
+#[cfg(feature = "async")]
+use std::future::Future;
+
+/// Everything a signer needs to know about a message, computed up front.
+///
+/// The digest is derived once when the request is created, so that wrappers
+/// which retry or forward a request are guaranteed to sign the same bytes.
+///
+/// Requests can only be made from a typed value, so the type and domain which
+/// wrappers like [`PolicySigner`](crate::PolicySigner) check are always those
+/// of the digest which is signed:
+///
+/// ```compile_fail
+/// # use eip_712_derive::*;
+/// let request = SigningRequest {
+///     type_name: "Allowed",
+///     type_hash: [0; 32],
+///     domain_separator: DomainSeparator::from_bytes(&[1; 32]),
+///     struct_hash: [2; 32],
+///     digest: [3; 32],
+/// };
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SigningRequest {
+    type_name: &'static str,
+    type_hash: Bytes32,
+    domain_separator: DomainSeparator,
+    struct_hash: Bytes32,
+    digest: Bytes32,
+}
+
+impl SigningRequest {
+    pub fn new<T: StructType>(domain_separator: &DomainSeparator, value: &T) -> Self {
+        Self::from_struct_hash(domain_separator, value, hash_struct(value))
+    }
+
+    /// For a `value` whose struct hash has already been computed
+    pub(crate) fn from_struct_hash<T: StructType>(
+        domain_separator: &DomainSeparator,
+        value: &T,
+        struct_hash: Bytes32,
+    ) -> Self {
+        Self {
+            type_name: T::TYPE_NAME,
+            type_hash: type_hash(value),
+            domain_separator: *domain_separator,
+            struct_hash,
+            digest: sign_hash_of(domain_separator, &struct_hash),
+        }
+    }
+
+    /// The TYPE_NAME of the message's primary type
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// The typeHash of the message, which unlike its type name tells apart
+    /// struct types with different members
+    pub fn type_hash(&self) -> &Bytes32 {
+        &self.type_hash
+    }
+
+    pub fn domain_separator(&self) -> &DomainSeparator {
+        &self.domain_separator
+    }
+
+    /// hashStruct(message)
+    pub fn struct_hash(&self) -> &Bytes32 {
+        &self.struct_hash
+    }
+
+    /// keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(message))
+    pub fn digest(&self) -> &Bytes32 {
+        &self.digest
+    }
+}
+
+/// Something which holds, or has access to, a key and signs with it.
+pub trait Signer {
+    /// The address of the key used for signing
+    fn address(&self) -> Address;
+    fn sign(&self, request: &SigningRequest) -> Result<Signature, Error>;
+    fn sign_typed<T: StructType>(
+        &self,
+        domain_separator: &DomainSeparator,
+        value: &T,
+    ) -> Result<Signature, Error> {
+        self.sign(&SigningRequest::new(domain_separator, value))
+    }
+}
+
+impl<S: Signer + ?Sized> Signer for &S {
+    fn address(&self) -> Address {
+        (**self).address()
+    }
+    fn sign(&self, request: &SigningRequest) -> Result<Signature, Error> {
+        (**self).sign(request)
+    }
+    fn sign_typed<T: StructType>(
+        &self,
+        domain_separator: &DomainSeparator,
+        value: &T,
+    ) -> Result<Signature, Error> {
+        (**self).sign_typed(domain_separator, value)
+    }
+}
+
+/// A signer whose operations complete asynchronously, such as a remote KMS or
+/// a signing daemon. Requires the `async` feature.
+///
+/// The returned futures are `Send`, so that generic code can spawn them onto
+/// a multi-threaded runtime such as tokio's. Signers are shared by the
+/// futures they return, so they are `Sync`.
+#[cfg(feature = "async")]
+pub trait AsyncSigner: Sync {
+    /// The address of the key used for signing
+    fn address(&self) -> Address;
+    fn sign(
+        &self,
+        request: &SigningRequest,
+    ) -> impl Future<Output = Result<Signature, Error>> + Send;
+    /// Signs `value` under `domain_separator`. The message is hashed when
+    /// this is called, not when the returned future is polled.
+    ///
+    /// Named apart from [`Signer::sign_typed`] so that calls are not
+    /// ambiguous for types which implement both traits.
+    fn sign_typed_async<T: StructType>(
+        &self,
+        domain_separator: &DomainSeparator,
+        value: &T,
+    ) -> impl Future<Output = Result<Signature, Error>> + Send {
+        let request = SigningRequest::new(domain_separator, value);
+        async move { self.sign(&request).await }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S: AsyncSigner + ?Sized> AsyncSigner for &S {
+    fn address(&self) -> Address {
+        (**self).address()
+    }
+    fn sign(
+        &self,
+        request: &SigningRequest,
+    ) -> impl Future<Output = Result<Signature, Error>> + Send {
+        (**self).sign(request)
+    }
+    fn sign_typed_async<T: StructType>(
+        &self,
+        domain_separator: &DomainSeparator,
+        value: &T,
+    ) -> impl Future<Output = Result<Signature, Error>> + Send {
+        (**self).sign_typed_async(domain_separator, value)
+    }
+}
@@ -0,0 +1,191 @@
+//! Human readable summaries of messages, suitable for "you are about to sign"
+//! confirmation screens.
+//!
+//! A summary is a tree of labeled members. Addresses are shown in their
+//! checksummed form, unsigned integers as decimals, and byte strings as hex.
+//! Since the raw message does not say which integers are token amounts,
+//! [`Hints`] may be used to supply decimals and symbols for those members, as
+//! well as friendlier labels.
+
+use crate::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Summary {
+    pub type_name: &'static str,
+    pub members: Vec<SummaryMember>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SummaryMember {
+    /// The label from the hints, or else the member name.
+    pub label: String,
+    pub value: SummaryValue,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SummaryValue {
+    /// A checksummed address
+    Address(String),
+    /// A decimal number, with the decimal point and symbol applied if a token
+    /// hint was given for this member.
+    Uint(String),
+    /// A signed decimal number
+    Int(String),
+    /// 0x-prefixed hex
+    Bytes(String),
+    /// A string member. Displayed quoted, with newlines and other control
+    /// characters escaped, so that it cannot pass for other members.
+    Text(String),
+    Bool(bool),
+    Struct(Summary),
+    Array(Vec<SummaryValue>),
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    decimals: u8,
+    symbol: String,
+}
+
+/// Display hints, keyed by struct type name and member name.
+#[derive(Clone, Debug, Default)]
+pub struct Hints {
+    labels: HashMap<(&'static str, &'static str), String>,
+    tokens: HashMap<(&'static str, &'static str), Token>,
+}
+
+impl Hints {
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Shows `label` instead of the member name
+    pub fn label(
+        mut self,
+        type_name: &'static str,
+        member: &'static str,
+        label: impl Into<String>,
+    ) -> Self {
+        self.labels.insert((type_name, member), label.into());
+        self
+    }
+    /// Marks a uint member as an amount of a token with the given decimals.
+    pub fn token(
+        mut self,
+        type_name: &'static str,
+        member: &'static str,
+        decimals: u8,
+        symbol: impl Into<String>,
+    ) -> Self {
+        let token = Token {
+            decimals,
+            symbol: symbol.into(),
+        };
+        self.tokens.insert((type_name, member), token);
+        self
+    }
+}
+
+pub fn summarize<T: StructType>(value: &T, hints: &Hints) -> Summary {
+    struct SummaryVisitor<'a> {
+        type_name: &'static str,
+        hints: &'a Hints,
+        members: Vec<SummaryMember>,
+    }
+    impl MemberVisitor for SummaryVisitor<'_> {
+        fn visit<T: MemberType>(&mut self, name: &'static str, value: &T) {
+            let key = (self.type_name, name);
+            let label = match self.hints.labels.get(&key) {
+                Some(label) => label.clone(),
+                None => name.to_owned(),
+            };
+            let mut value = value.summarize(self.hints);
+            if let (SummaryValue::Uint(digits), Some(token)) =
+                (&mut value, self.hints.tokens.get(&key))
+            {
+                *digits = format_amount(digits, token);
+            }
+            self.members.push(SummaryMember { label, value });
+        }
+    }
+    let mut visitor = SummaryVisitor {
+        type_name: T::TYPE_NAME,
+        hints,
+        members: Vec::new(),
+    };
+    value.visit_members(&mut visitor);
+    Summary {
+        type_name: T::TYPE_NAME,
+        members: visitor.members,
+    }
+}
+
+/// Places the decimal point in a string of decimal digits and appends the
+/// symbol. Eg: "1500000" with 6 decimals is "1.5 USDC"
+fn format_amount(digits: &str, token: &Token) -> String {
+    let decimals = token.decimals as usize;
+    let padded;
+    let digits = if digits.len() <= decimals {
+        padded = format!("{}{}", "0".repeat(decimals + 1 - digits.len()), digits);
+        &padded
+    } else {
+        digits
+    };
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    let mut result = whole.to_owned();
+    if !fraction.is_empty() {
+        result.push('.');
+        result.push_str(fraction);
+    }
+    if !token.symbol.is_empty() {
+        result.push(' ');
+        result.push_str(&token.symbol);
+    }
+    result
+}
+
+impl Summary {
+    fn write(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        for member in &self.members {
+            write_value(f, &member.label, &member.value, depth)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_value(
+    f: &mut fmt::Formatter<'_>,
+    label: &str,
+    value: &SummaryValue,
+    depth: usize,
+) -> fmt::Result {
+    write!(f, "{:indent$}{}: ", "", label, indent = depth * 2)?;
+    match value {
+        SummaryValue::Address(s)
+        | SummaryValue::Uint(s)
+        | SummaryValue::Int(s)
+        | SummaryValue::Bytes(s) => writeln!(f, "{}", s),
+        SummaryValue::Text(s) => writeln!(f, "{:?}", s),
+        SummaryValue::Bool(b) => writeln!(f, "{}", b),
+        SummaryValue::Struct(inner) => {
+            writeln!(f, "{}", inner.type_name)?;
+            inner.write(f, depth + 1)
+        }
+        SummaryValue::Array(elements) => {
+            writeln!(f, "{} items", elements.len())?;
+            for (i, element) in elements.iter().enumerate() {
+                write_value(f, &format!("[{}]", i), element, depth + 1)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.type_name)?;
+        self.write(f, 1)
+    }
+}
@@ -0,0 +1,115 @@
+//! Test doubles and helpers for code which signs messages. Requires the
+//! `testing` feature, which is intended to be enabled only in
+//! dev-dependencies.
+
+use crate::prelude::*;
+use crate::*;
+use std::sync::Mutex;
+
+/// The key used by [`MockSigner::new`]
+pub fn mock_key() -> PrivateKey {
+    key_from_seed("eip-712-derive mock signer")
+}
+
+/// A deterministic key derived from `seed`, so that tests can use distinct,
+/// stable identities by name.
+pub fn key_from_seed(seed: &str) -> PrivateKey {
+    // keccak output is a valid secp256k1 key with overwhelming probability.
+    PrivateKey::from(keccak(seed))
+}
+
+/// A fresh random key, for tests which must not share identities. Requires
+/// the `random` feature.
+#[cfg(feature = "random")]
+pub fn random_key() -> PrivateKey {
+    loop {
+        let key = PrivateKey::from_fn(|bytes| {
+            getrandom::getrandom(bytes).expect("Failed to obtain randomness")
+        });
+        if signer_address(&key).is_ok() {
+            return key;
+        }
+    }
+}
+
+/// A domain for tests, with every field of EIP712Domain set.
+pub fn test_domain() -> Eip712Domain {
+    Eip712Domain {
+        name: "Test".to_owned(),
+        version: "1".to_owned(),
+        // As used by local development chains
+        chain_id: U256::from(1337u64),
+        verifying_contract: Address([0x11; 20]),
+        salt: Salt::from_label("eip-712-derive test domain").into(),
+    }
+}
+
+/// A [`Signer`] with a fixed key which records every request it receives.
+///
+/// Signatures are real, so they verify against [`Signer::address`]. Use
+/// [`MockSigner::fail_with`] to exercise error handling.
+pub struct MockSigner {
+    key: SecretKey,
+    address: Address,
+    requests: Mutex<Vec<SigningRequest>>,
+    failure: Mutex<Option<Error>>,
+}
+
+impl Default for MockSigner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockSigner {
+    pub fn new() -> Self {
+        Self::with_key(mock_key())
+    }
+
+    /// Panics if the key is invalid
+    pub fn with_key(key: PrivateKey) -> Self {
+        let key = SecretKey::new(key).expect("Invalid key for MockSigner");
+        Self {
+            address: key.address(),
+            key,
+            requests: Mutex::new(Vec::new()),
+            failure: Mutex::new(None),
+        }
+    }
+
+    /// Every request received so far, in order, including failed ones.
+    pub fn requests(&self) -> Vec<SigningRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Makes every subsequent request fail with `error`, until cleared with
+    /// `None`.
+    pub fn fail_with(&self, error: Option<Error>) {
+        *self.failure.lock().unwrap() = error;
+    }
+}
+
+impl Signer for MockSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn sign(&self, request: &SigningRequest) -> Result<Signature, Error> {
+        self.requests.lock().unwrap().push(*request);
+        if let Some(error) = self.failure.lock().unwrap().clone() {
+            return Err(error);
+        }
+        sign_digest(request.digest(), &self.key)
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncSigner for MockSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign(&self, request: &SigningRequest) -> Result<Signature, Error> {
+        Signer::sign(self, request)
+    }
+}
@@ -1,6 +1,8 @@
 use crate::prelude::*;
+use crate::Error;
 use lazy_static::lazy_static;
 use std::any::TypeId;
+use std::borrow::Cow;
 use std::collections::{BTreeMap, HashMap};
 use std::sync::RwLock;
 
@@ -8,17 +10,33 @@ use std::sync::RwLock;
 // member₂ ‖ "," ‖ … ‖ memberₙ ")" where each member is written as type ‖ " " ‖
 // name. For example, the above Mail struct is encoded as Mail(address
 // from,address to,string contents)
+//
+// Panics if the type is invalid. See try_encode_type.
 pub fn encode_type<T: StructType>(value: &T) -> String {
-    let mut builder = TypeHashBuilder {
-        outer: None,
-        inner: BTreeMap::new(),
-    };
+    try_encode_type(value).unwrap_or_else(|e| panic!("{}", e))
+}
 
-    value.add_members(&mut builder);
+/// Like [`encode_type`], but returns an error for invalid types, eg: distinct
+/// struct types with the same name.
+pub fn try_encode_type<T: StructType>(value: &T) -> Result<String, Error> {
+    Ok(write_types(&try_collect_types(value)?))
+}
 
+/// Like [`encode_type`], given only the type. Every struct type referenced by
+/// `T` must implement [`StructType::visit_member_types`].
+///
+/// Panics if the type is invalid. See try_encode_type_of.
+pub fn encode_type_of<T: StructType>() -> String {
+    try_encode_type_of::<T>().unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Like [`encode_type_of`], but returns an error for invalid types.
+pub fn try_encode_type_of<T: StructType>() -> Result<String, Error> {
+    Ok(write_types(&try_collect_types_of::<T>()?))
+}
+
+fn write_types(types: &[EncodedType]) -> String {
     let mut buffer = String::new();
-    let outer = builder.outer.unwrap();
-    assert!(outer.name == T::TYPE_NAME);
 
     fn add_type(s: &mut String, t: &EncodedType) {
         s.push_str(t.name);
@@ -34,32 +52,134 @@ pub fn encode_type<T: StructType>(value: &T) -> String {
         s.push(')');
     }
 
-    add_type(&mut buffer, &outer);
-    for inner in builder.inner.values() {
-        add_type(&mut buffer, inner);
+    for t in types {
+        add_type(&mut buffer, t);
     }
     buffer
 }
 
+/// The struct types making up the type of `value`: the outer type first,
+/// followed by the referenced struct types sorted by name.
+///
+/// Panics if the type is invalid.
+#[cfg(any(feature = "erc7730", feature = "json-schema"))]
+pub(crate) fn collect_types<T: StructType>(value: &T) -> Vec<EncodedType> {
+    try_collect_types(value).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub(crate) fn try_collect_types<T: StructType>(value: &T) -> Result<Vec<EncodedType>, Error> {
+    let mut builder = TypeHashBuilder::new();
+    value.add_members(&mut builder);
+    builder.finish::<T>()
+}
+
+pub(crate) fn try_collect_types_of<T: StructType>() -> Result<Vec<EncodedType>, Error> {
+    let mut builder = TypeHashBuilder::new();
+    builder.struct_type_of::<T>();
+    builder.finish::<T>()
+}
+
+impl TypeHashBuilder {
+    fn new() -> Self {
+        Self {
+            outer: None,
+            inner: BTreeMap::new(),
+            error: None,
+            fixed: true,
+        }
+    }
+
+    /// The outer type first, followed by the referenced struct types sorted
+    /// by name
+    fn finish<T: StructType>(self) -> Result<Vec<EncodedType>, Error> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let outer = match self.outer {
+            Some(outer) if outer.name == T::TYPE_NAME => outer,
+            other => {
+                return Err(Error::TypeMismatch {
+                    expected: T::TYPE_NAME.to_owned(),
+                    found: other.map_or("", |outer| outer.name).to_owned(),
+                })
+            }
+        };
+
+        let mut result = Vec::with_capacity(1 + self.inner.len());
+        result.push(outer);
+        result.extend(self.inner.into_values());
+        Ok(result)
+    }
+}
+
 lazy_static! {
     static ref CACHE: RwLock<HashMap<TypeId, Bytes32>> = RwLock::new(HashMap::new());
 }
 
 /// Memoized type hash
+///
+/// Panics if the type is invalid. See try_type_hash.
 pub fn type_hash<T: StructType>(value: &T) -> Bytes32 {
+    try_type_hash(value).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Like [`type_hash`], but returns an error for invalid types. Only valid
+/// types are memoized.
+pub fn try_type_hash<T: StructType>(value: &T) -> Result<Bytes32, Error> {
+    memoized_type_hash::<T>(|| {
+        let mut builder = TypeHashBuilder::new();
+        value.add_members(&mut builder);
+        let fixed = builder.fixed;
+        Ok((write_types(&builder.finish::<T>()?), fixed))
+    })
+}
+
+/// Like [`type_hash`], given only the type. See [`encode_type_of`].
+///
+/// Panics if the type is invalid. See try_type_hash_of.
+pub fn type_hash_of<T: StructType>() -> Bytes32 {
+    try_type_hash_of::<T>().unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Like [`type_hash_of`], but returns an error for invalid types.
+pub fn try_type_hash_of<T: StructType>() -> Result<Bytes32, Error> {
+    memoized_type_hash::<T>(|| {
+        let mut builder = TypeHashBuilder::new();
+        builder.struct_type_of::<T>();
+        let fixed = builder.fixed;
+        Ok((write_types(&builder.finish::<T>()?), fixed))
+    })
+}
+
+/// `encode_type` returns the encoded type, and whether every struct type in
+/// it has fixed members. Only then is the type hash memoized.
+fn memoized_type_hash<T: StructType>(
+    encode_type: impl FnOnce() -> Result<(String, bool), Error>,
+) -> Result<Bytes32, Error> {
+    if !T::fixed_members() {
+        return Ok(keccak(encode_type()?.0.as_bytes()));
+    }
+    // Keyed by the struct identity, so that pointers share the entry of the
+    // struct they point to.
     let read = CACHE.read().unwrap();
-    if let Some(cached) = read.get(&TypeId::of::<T>()) {
-        return *cached;
+    if let Some(cached) = read.get(&T::struct_identity()) {
+        crate::metrics::type_hash_cache(true);
+        return Ok(*cached);
     }
     drop(read);
+    crate::metrics::type_hash_cache(false);
 
     // (SPEC) keccak256(encodeType(typeOf(s)))
-    let encoded = encode_type(value);
+    let (encoded, fixed) = encode_type()?;
     let result = keccak(encoded.as_bytes());
 
-    let mut write = CACHE.write().unwrap();
-    write.insert(TypeId::of::<T>(), result);
-    result
+    // Members such as a PartialDomain may differ for the next value
+    if fixed {
+        let mut write = CACHE.write().unwrap();
+        write.insert(T::struct_identity(), result);
+    }
+    Ok(result)
 }
 
 pub struct TypeHashBuilder {
@@ -73,10 +193,14 @@ pub struct TypeHashBuilder {
     // of the sorted set.
     outer: Option<EncodedType>,
     inner: BTreeMap<&'static str, EncodedType>,
+    /// The first problem found with the type, if any
+    error: Option<Error>,
+    /// Whether every struct type added has fixed members
+    fixed: bool,
 }
 
 impl TypeHashBuilder {
-    fn get_encoded_type_mut(&mut self, name: &'static str) -> Option<&mut EncodedType> {
+    fn get_encoded_type_mut(&mut self, name: &str) -> Option<&mut EncodedType> {
         if let Some(outer) = &self.outer {
             if outer.name == name {
                 return self.outer.as_mut();
@@ -84,10 +208,28 @@ impl TypeHashBuilder {
         }
         self.inner.get_mut(name)
     }
-    pub fn struct_type<T: StructType>(&mut self) -> StructTypeBuilder {
-        assert!(self.get_encoded_type_mut(T::TYPE_NAME).is_none());
+    fn fail(&mut self, error: Error) {
+        if self.error.is_none() {
+            self.error = Some(error);
+        }
+    }
+
+    pub fn struct_type<T: StructType>(&mut self) -> StructTypeBuilder<'_> {
+        self.fixed &= T::fixed_members();
+        if !is_identifier(T::TYPE_NAME) {
+            self.fail(Error::InvalidIdentifier(T::TYPE_NAME.to_owned()));
+        }
+        // Member types check is_added first, so only a MemberType which does
+        // not report the name of the struct type it adds gets here
+        if self.get_encoded_type_mut(T::TYPE_NAME).is_some() {
+            self.fail(Error::DuplicateTypeName(T::TYPE_NAME.to_owned()));
+            return StructTypeBuilder {
+                parent: self,
+                own_type: T::TYPE_NAME,
+            };
+        }
         let value = EncodedType {
-            type_id: TypeId::of::<T>(),
+            type_id: T::struct_identity(),
             name: T::TYPE_NAME,
             members: Vec::new(),
         };
@@ -102,26 +244,65 @@ impl TypeHashBuilder {
             own_type: T::TYPE_NAME,
         }
     }
+
+    /// Adds the struct type `T` and those it references, without a value.
+    pub fn struct_type_of<T: StructType>(&mut self) {
+        let mut builder = self.struct_type::<T>();
+        if let Err(error) = T::visit_member_types(&mut builder) {
+            builder.parent.fail(error);
+        }
+    }
+
+    /// Adds the struct types referenced by `value`, unless its type has
+    /// already been added.
+    pub fn member_type<T: MemberType>(&mut self, value: &T) {
+        if !self.is_added::<T>() {
+            value.add_members(self);
+        }
+    }
+
+    /// Like member_type, without a value
+    pub fn member_type_of<T: MemberType>(&mut self) {
+        if !self.is_added::<T>() {
+            T::add_member_types(self);
+        }
+    }
+
+    fn is_added<T: MemberType>(&mut self) -> bool {
+        // It's possible that types show up more than once, so we need
+        // to check if this is a type we've already added. Recursion
+        // is also possible, so verify that as well.
+        if let Some(encoded_type) = self.get_encoded_type_mut(&T::type_name()) {
+            // Ensure the uniqueness of type names. The spec doesn't seem to
+            // address this, but it makes sense because with duplicated type
+            // names the result of the sort by name step would be undefined.
+            if encoded_type.type_id != T::type_identity() {
+                self.fail(Error::DuplicateTypeName(T::type_name().into_owned()));
+            }
+            return true;
+        }
+        false
+    }
 }
 
 #[derive(PartialEq, Eq, Hash, Debug, Clone)]
-struct Member {
-    pub r#type: &'static str,
+pub(crate) struct Member {
+    pub r#type: Cow<'static, str>,
     pub name: &'static str,
 }
 
 impl Member {
     pub fn write(&self, buffer: &mut String) {
-        buffer.push_str(self.r#type);
+        buffer.push_str(&self.r#type);
         buffer.push(' ');
         buffer.push_str(self.name);
     }
 }
 
-struct EncodedType {
+pub(crate) struct EncodedType {
     type_id: TypeId,
-    name: &'static str,
-    members: Vec<Member>,
+    pub name: &'static str,
+    pub members: Vec<Member>,
 }
 
 pub struct StructTypeBuilder<'a> {
@@ -129,33 +310,56 @@ pub struct StructTypeBuilder<'a> {
     own_type: &'static str,
 }
 
-impl MemberVisitor for StructTypeBuilder<'_> {
-    fn visit<T: MemberType>(&mut self, name: &'static str, value: &T) {
+impl StructTypeBuilder<'_> {
+    fn add_member<T: MemberType>(&mut self, name: &'static str) {
+        if !is_identifier(name) {
+            self.parent.fail(Error::InvalidIdentifier(format!(
+                "{}.{}",
+                self.own_type, name
+            )));
+        }
         // This unwrap is ok, because we know that this must exist because it was
         // added with this builder.
         let set = self.parent.get_encoded_type_mut(self.own_type).unwrap();
+        // The encodeType would be valid, but no contract can declare it
+        let duplicated = set.members.iter().any(|m| m.name == name);
         let member = Member {
             name,
-            r#type: T::TYPE_NAME,
+            r#type: T::type_name(),
         };
-        // TODO: Assertion fail on duplicated member name?
         set.members.push(member);
+        if duplicated {
+            self.parent.fail(Error::DuplicateMember {
+                type_name: self.own_type.to_owned(),
+                member: name.to_owned(),
+            });
+        }
+    }
+}
 
+impl MemberVisitor for StructTypeBuilder<'_> {
+    fn visit<T: MemberType>(&mut self, name: &'static str, value: &T) {
+        self.add_member::<T>(name);
         // Recurse into the members to add their types.
-        // It's possible that types show up more than once, so we need
-        // to check if this is a type we've already added. Recursion
-        // is also possible, so verify that as well.
-        if let Some(encoded_type) = self.parent.get_encoded_type_mut(T::TYPE_NAME) {
-            // Ensure the uniqueness of type names. The spec doesn't seem to
-            // address this, but it makes sense because with duplicated type
-            // names the result of the sort by name step would be undefined.
-            assert!(
-                encoded_type.type_id == TypeId::of::<T>(),
-                "Types with duplicated name: {}",
-                T::TYPE_NAME
-            );
-            return;
-        }
-        value.add_members(self.parent);
+        self.parent.member_type(value);
+    }
+}
+
+impl TypeVisitor for StructTypeBuilder<'_> {
+    fn visit<T: MemberType>(&mut self, name: &'static str) {
+        self.add_member::<T>(name);
+        self.parent.member_type_of::<T>();
+    }
+}
+
+/// (SPEC) A struct type has valid identifier as name. Solidity identifiers are
+/// ASCII letters, digits, `_` and `$`, not starting with a digit. Anything else
+/// would make encodeType ambiguous or impossible to declare in a contract.
+pub(crate) fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$' => {}
+        _ => return false,
     }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
 }
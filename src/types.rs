@@ -1,4 +1,10 @@
 use crate::prelude::*;
+use crate::summary::{Hints, SummaryValue};
+use crate::Error;
+use std::any::TypeId;
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
 
 /// (SPEC) Definition: The atomic types are bytes1 to bytes32, uint8 to uint256, int8
 /// to int256, bool and address. These correspond to their definition in
@@ -31,8 +37,29 @@ pub trait StructType: 'static {
     ///
     /// This API exists to make it very easy to implement, without requiring too much
     /// very similar boilerplate for the requirements of add_members and encode_data.
-    /// It will likely go away if a derive is added.
+    /// With the `derive` feature, `#[derive(StructType)]` writes it instead.
     fn visit_members<T: MemberVisitor>(&self, visitor: &mut T);
+    /// Call visitor.visit with the type of each of the fields, in the same
+    /// order as visit_members. This makes the type known without a value, see
+    /// encode_type_of. `#[derive(StructType)]` writes it too.
+    ///
+    /// The default fails with Error::TypeNeedsValue.
+    fn visit_member_types<T: TypeVisitor>(_visitor: &mut T) -> Result<(), Error> {
+        Err(Error::TypeNeedsValue(Self::TYPE_NAME.to_owned()))
+    }
+    /// Distinguishes struct types with the same name. Pointers forward to the
+    /// struct they point to.
+    #[doc(hidden)]
+    fn struct_identity() -> TypeId {
+        TypeId::of::<Self>()
+    }
+    /// False for types whose members depend on the value, so that their type
+    /// hash is not memoized, nor that of the structs which contain them. See
+    /// PartialDomain.
+    #[doc(hidden)]
+    fn fixed_members() -> bool {
+        true
+    }
 }
 
 pub trait MemberVisitor {
@@ -40,29 +67,134 @@ pub trait MemberVisitor {
     fn visit<T: MemberType>(&mut self, name: &'static str, value: &T);
 }
 
+/// Like MemberVisitor, but for types rather than values.
+pub trait TypeVisitor {
+    /// The name should be the Ethereum name (usually camel case)
+    fn visit<T: MemberType>(&mut self, name: &'static str);
+}
+
 /// (SPEC) Definition: A member type can be either an atomic type, a dynamic
 /// type or a reference type.
 ///
 /// There is no need for a consumer of a crate to implement this manually.
 /// It is easier to implement StructType instead.
 pub trait MemberType: 'static {
-    const TYPE_NAME: &'static str;
+    /// The name of the type as written in encodeType, eg: `uint256`, `Mail`
+    /// or `Person[2]`.
+    fn type_name() -> Cow<'static, str>;
     fn encode_data(&self) -> Bytes32;
     fn add_members(&self, builder: &mut TypeHashBuilder);
+    /// Like add_members, without a value. Types which reference struct types
+    /// must override it.
+    #[doc(hidden)]
+    fn add_member_types(_builder: &mut TypeHashBuilder) {}
+    /// A display-friendly rendering of the value. See the summary module.
+    /// Defaults to the hex of the encoded value.
+    fn summarize(&self, _hints: &Hints) -> SummaryValue {
+        SummaryValue::Bytes(self.encode_data().to_hex())
+    }
+    /// The value, for serializing it. Atomic values are decoded from
+    /// encode_data, and other types override it. None for values known only
+    /// by their hash, like HashedBytes.
+    #[doc(hidden)]
+    fn dynamic_value(&self) -> Option<DynamicValue> {
+        DynamicValue::from_atomic(&Self::type_name(), &self.encode_data())
+    }
+    /// Distinguishes struct types with the same name. Pointers forward to the
+    /// type they point to.
+    #[doc(hidden)]
+    fn type_identity() -> TypeId {
+        TypeId::of::<Self>()
+    }
 }
 
 impl<T: StructType> MemberType for T {
-    const TYPE_NAME: &'static str = T::TYPE_NAME;
+    fn type_name() -> Cow<'static, str> {
+        Cow::Borrowed(T::TYPE_NAME)
+    }
     fn add_members(&self, builder: &mut TypeHashBuilder) {
         let mut builder = builder.struct_type::<T>();
         self.visit_members(&mut builder);
     }
+    fn add_member_types(builder: &mut TypeHashBuilder) {
+        builder.struct_type_of::<T>();
+    }
     fn encode_data(&self) -> Bytes32 {
         crate::hash_struct(self)
     }
+    fn summarize(&self, hints: &Hints) -> SummaryValue {
+        SummaryValue::Struct(crate::summary::summarize(self, hints))
+    }
+    fn dynamic_value(&self) -> Option<DynamicValue> {
+        DynamicValue::of_struct(self)
+    }
+    fn type_identity() -> TypeId {
+        T::struct_identity()
+    }
 }
 
 impl<T: StructType> ReferenceType for T {}
+
+// Pointers are members of the type they point to, so that members can be
+// shared between messages without cloning. Downstream crates could implement
+// StructType for Box and references, which are #[fundamental], so those
+// conflict with the blanket impl above as MemberTypes and are StructTypes
+// instead.
+macro_rules! impl_struct_pointer {
+    ($($P:ty),+) => {
+        $(
+            impl<T: StructType> StructType for $P {
+                const TYPE_NAME: &'static str = T::TYPE_NAME;
+                fn visit_members<V: MemberVisitor>(&self, visitor: &mut V) {
+                    (**self).visit_members(visitor)
+                }
+                fn visit_member_types<V: TypeVisitor>(visitor: &mut V) -> Result<(), Error> {
+                    T::visit_member_types(visitor)
+                }
+                fn struct_identity() -> TypeId {
+                    T::struct_identity()
+                }
+                fn fixed_members() -> bool {
+                    T::fixed_members()
+                }
+            }
+        )+
+    };
+}
+
+impl_struct_pointer!(&'static T, Box<T>);
+
+macro_rules! impl_pointer {
+    ($($P:ty),+) => {
+        $(
+            impl<T: MemberType> MemberType for $P {
+                fn type_name() -> Cow<'static, str> {
+                    T::type_name()
+                }
+                fn encode_data(&self) -> Bytes32 {
+                    (**self).encode_data()
+                }
+                fn add_members(&self, builder: &mut TypeHashBuilder) {
+                    (**self).add_members(builder)
+                }
+                fn add_member_types(builder: &mut TypeHashBuilder) {
+                    T::add_member_types(builder)
+                }
+                fn summarize(&self, hints: &Hints) -> SummaryValue {
+                    (**self).summarize(hints)
+                }
+                fn dynamic_value(&self) -> Option<DynamicValue> {
+                    (**self).dynamic_value()
+                }
+                fn type_identity() -> TypeId {
+                    T::type_identity()
+                }
+            }
+        )+
+    };
+}
+
+impl_pointer!(Rc<T>, Arc<T>);
 // We would like to simply do the following, but this has to wait on
 // some variation of https://github.com/rust-lang/rfcs/issues/1053
 // For the moment we auto-impl for StructType only, and
@@ -0,0 +1,30 @@
+//! WalletConnect v2 signing requests. Requires the `walletconnect` feature.
+//!
+//! WalletConnect relays JSON-RPC requests to the user's wallet wrapped in a
+//! `wc_sessionRequest`, scoped to a CAIP-2 chain. For typed data the wrapped
+//! request is `eth_signTypedData_v4`, whose second parameter is the typed data
+//! serialized as a string (not as a nested object).
+
+use crate::dynamic::TypedData;
+use crate::{Address, BytesHex};
+use serde_json::{json, Value};
+
+/// Builds a `wc_sessionRequest` asking the wallet for `account` to sign
+/// `typed_data` on the chain with id `chain_id`.
+///
+/// The returned value is the complete JSON-RPC request, ready to be published
+/// to the session topic.
+pub fn session_request(id: u64, chain_id: u64, account: &Address, typed_data: &TypedData) -> Value {
+    json!({
+        "id": id,
+        "jsonrpc": "2.0",
+        "method": "wc_sessionRequest",
+        "params": {
+            "request": {
+                "method": "eth_signTypedData_v4",
+                "params": [account.0.to_hex(), typed_data.to_json().to_string()],
+            },
+            "chainId": format!("eip155:{}", chain_id),
+        },
+    })
+}
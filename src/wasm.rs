@@ -0,0 +1,67 @@
+//! JavaScript bindings through wasm-bindgen. Requires the `wasm` feature.
+//!
+//! Typed data crosses the boundary as eth_signTypedData_v4 JSON strings, and
+//! hashes, keys and signatures as `Uint8Array`s, so browser extensions and
+//! Node tooling hash with the same code as Rust services. Errors are thrown
+//! as JavaScript `Error`s with this crate's messages.
+//!
+//! The functions are exported from whichever cdylib crate is built with
+//! wasm-pack and depends on this one with the feature enabled.
+
+use crate::dynamic::TypedData;
+use crate::Error;
+use wasm_bindgen::prelude::*;
+
+fn to_js(error: Error) -> JsError {
+    JsError::new(&error.to_string())
+}
+
+/// The digest of typed data JSON, which is what gets signed
+#[wasm_bindgen(js_name = hashTypedData)]
+pub fn hash_typed_data(json: &str) -> Result<Vec<u8>, JsError> {
+    let digest = TypedData::from_json(json).and_then(|t| t.digest());
+    Ok(digest.map_err(to_js)?.to_vec())
+}
+
+/// hashStruct of the message of typed data JSON
+#[wasm_bindgen(js_name = hashStruct)]
+pub fn hash_struct(json: &str) -> Result<Vec<u8>, JsError> {
+    let struct_hash = TypedData::from_json(json).and_then(|t| t.struct_hash());
+    Ok(struct_hash.map_err(to_js)?.to_vec())
+}
+
+/// See [`domain_separator_json`](crate::domain_separator_json)
+#[wasm_bindgen(js_name = domainSeparator)]
+pub fn domain_separator(domain: &str) -> Result<Vec<u8>, JsError> {
+    crate::domain_separator_json(domain)
+        .map(|separator| separator.as_bytes().to_vec())
+        .map_err(to_js)
+}
+
+/// Signs typed data JSON with a 32 byte private key, returning r ‖ s ‖ v with
+/// v as 27 or 28, like eth_signTypedData_v4.
+///
+/// The copy of the key passed in is zeroized before returning.
+#[cfg(feature = "sign")]
+#[wasm_bindgen(js_name = signTypedData)]
+pub fn sign_typed_data(json: &str, mut private_key: Vec<u8>) -> Result<Vec<u8>, JsError> {
+    use std::convert::TryInto;
+    use zeroize::Zeroize;
+
+    let key = match private_key.as_slice().try_into() {
+        Ok(bytes) => crate::SecretKey::from_bytes(bytes),
+        Err(_) => Err(Error::InvalidPrivateKey),
+    };
+    private_key.zeroize();
+    let signature = TypedData::from_json(json).and_then(|t| t.sign(&key?));
+    Ok(signature.map_err(to_js)?.to_bytes().to_vec())
+}
+
+/// The checksummed address which produced a 65 byte `signature` over typed
+/// data JSON
+#[wasm_bindgen(js_name = recoverTypedData)]
+pub fn recover_typed_data(json: &str, signature: &[u8]) -> Result<String, JsError> {
+    let address = crate::Signature::from_bytes(signature)
+        .and_then(|signature| TypedData::from_json(json)?.recover(&signature));
+    Ok(address.map_err(to_js)?.to_string())
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "alloy")]
+
+use alloy_primitives as alloy;
+use eip_712_derive::*;
+
+struct Transfer {
+    to: alloy::Address,
+    amount: alloy::U256,
+    memo: alloy::FixedBytes<4>,
+    id: alloy::B256,
+}
+
+impl StructType for Transfer {
+    const TYPE_NAME: &'static str = "Transfer";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("to", &self.to);
+        visitor.visit("amount", &self.amount);
+        visitor.visit("memo", &self.memo);
+        visitor.visit("id", &self.id);
+    }
+}
+
+struct LocalTransfer {
+    to: Address,
+    amount: U256,
+    memo: Bytes4,
+    id: Bytes32,
+}
+
+impl StructType for LocalTransfer {
+    const TYPE_NAME: &'static str = "Transfer";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("to", &self.to);
+        visitor.visit("amount", &self.amount);
+        visitor.visit("memo", &self.memo);
+        visitor.visit("id", &self.id);
+    }
+}
+
+#[test]
+fn same_encoding_as_local_types() {
+    let transfer = Transfer {
+        to: alloy::Address::repeat_byte(0xaa),
+        amount: alloy::U256::from(1_000_000u64),
+        memo: alloy::FixedBytes::new(*b"rent"),
+        id: alloy::B256::repeat_byte(7),
+    };
+    let local = LocalTransfer {
+        to: transfer.to.into(),
+        amount: transfer.amount.into(),
+        memo: transfer.memo.0,
+        id: transfer.id.0,
+    };
+    assert_eq!(
+        encode_type(&transfer),
+        "Transfer(address to,uint256 amount,bytes4 memo,bytes32 id)"
+    );
+    assert_eq!(encode_data(&transfer), encode_data(&local));
+
+    assert_eq!(alloy::Address::from(local.to), transfer.to);
+    assert_eq!(alloy::U256::from_be_bytes(local.amount.0), transfer.amount);
+    let domain_separator = DomainSeparator::from(transfer.id);
+    assert_eq!(alloy::B256::from(domain_separator), transfer.id);
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "testing")]
+
+use eip_712_derive::audit::*;
+use eip_712_derive::testing::*;
+use eip_712_derive::*;
+use std::sync::Mutex;
+
+#[test]
+fn records_every_request() {
+    let log = Mutex::new(Vec::new());
+    let signer = AuditSigner::new(MockSigner::new(), &log);
+    let domain_separator = DomainSeparator::new(&test_domain());
+
+    signer
+        .sign_typed(&domain_separator, &test_domain())
+        .unwrap();
+    signer
+        .tagged("billing")
+        .sign_typed(&domain_separator, &test_domain())
+        .unwrap();
+
+    let mut records = log.into_inner().unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1].tag, "billing");
+    assert_eq!(records[1].type_name, "EIP712Domain");
+    assert_eq!(
+        records[1].digest,
+        sign_hash(&domain_separator, &test_domain())
+    );
+    assert_eq!(records[1].struct_hash, hash_struct(&test_domain()));
+    assert!(verify_chain(&records));
+
+    // Tampering breaks the chain
+    records[0].tag = "other".to_owned();
+    assert!(!verify_chain(&records));
+    records.remove(0);
+    records[0].previous = [0u8; 32];
+    assert!(!verify_chain(&records));
+}
+
+struct FailingSink;
+
+impl AuditSink for FailingSink {
+    fn write(&self, _record: &AuditRecord) -> Result<(), String> {
+        Err("disk full".to_owned())
+    }
+}
+
+#[test]
+fn refuses_without_log() {
+    let mock = MockSigner::new();
+    let signer = AuditSigner::new(&mock, FailingSink);
+    let domain_separator = DomainSeparator::new(&test_domain());
+    assert_eq!(
+        signer.sign_typed(&domain_separator, &test_domain()),
+        Err(Error::AuditLog("disk full".to_owned()))
+    );
+    assert!(mock.requests().is_empty());
+}
+
+#[test]
+fn mismatched_digest_fails_verification() {
+    let log = Mutex::new(Vec::new());
+    let signer = AuditSigner::new(MockSigner::new(), &log);
+    let domain_separator = DomainSeparator::new(&test_domain());
+    signer
+        .sign_typed(&domain_separator, &test_domain())
+        .unwrap();
+
+    // A record claiming a digest other than the one of its domain and struct
+    // hash is rejected, even with a recomputed hash
+    let mut records = log.into_inner().unwrap();
+    records[0].digest = [1u8; 32];
+    records[0].hash = records[0].compute_hash();
+    assert!(!verify_chain(&records));
+}
@@ -0,0 +1,15 @@
+#![cfg(feature = "chains")]
+
+use eip_712_derive::chains::{self, Chain};
+
+#[test]
+fn generated_constants() {
+    assert_eq!(chains::ETHEREUM_MAINNET.chain_id, 1);
+    assert_eq!(chains::ARBITRUM_ONE.short_name, "arb1");
+    assert_eq!(chains::AVALANCHE_C_CHAIN.chain_id, 43114);
+
+    assert_eq!(Chain::by_chain_id(11155111), Some(&chains::SEPOLIA));
+    assert_eq!(Chain::by_short_name("base"), Some(&chains::BASE));
+    assert_eq!(Chain::by_name("Arbitrum One"), Some(&chains::ARBITRUM_ONE));
+    assert_eq!(Chain::by_chain_id(0), None);
+}
@@ -0,0 +1,176 @@
+#![cfg(feature = "cli")]
+
+use eip_712_derive::{DomainSeparator, Eip712Domain};
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+const MAIL: &str = r#"{
+    "types": {
+        "EIP712Domain": [
+            { "name": "name", "type": "string" },
+            { "name": "version", "type": "string" },
+            { "name": "chainId", "type": "uint256" },
+            { "name": "verifyingContract", "type": "address" }
+        ],
+        "Person": [
+            { "name": "name", "type": "string" },
+            { "name": "wallet", "type": "address" }
+        ],
+        "Mail": [
+            { "name": "from", "type": "Person" },
+            { "name": "to", "type": "Person" },
+            { "name": "contents", "type": "string" }
+        ]
+    },
+    "primaryType": "Mail",
+    "domain": {
+        "name": "Ether Mail",
+        "version": "1",
+        "chainId": 1,
+        "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+    },
+    "message": {
+        "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+        "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+        "contents": "Hello, Bob!"
+    }
+}"#;
+
+const MAIL_SIGNATURE: &str = "0x4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b915621c";
+
+fn eip712(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_eip712"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap().trim_end()
+}
+
+#[test]
+fn hash() {
+    let output = eip712(&["hash"], MAIL);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "0xbe609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+    );
+
+    let output = eip712(&["hash", "--all"], MAIL);
+    assert!(stdout(&output).contains(
+        "domainSeparator 0xf2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+    ));
+
+    let output = eip712(&["hash"], "{}");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("error: "));
+}
+
+#[test]
+fn sign_and_verify() {
+    let dir = std::env::temp_dir().join(format!("eip712-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let key_file = dir.join("key");
+    let key = hex::encode(keccak_hash::keccak("cow").0);
+    std::fs::write(&key_file, format!("0x{}\n", key)).unwrap();
+
+    let output = eip712(&["sign", "--key-file", key_file.to_str().unwrap()], MAIL);
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), MAIL_SIGNATURE);
+
+    let verify = |signer| {
+        eip712(
+            &["verify", "--signature", MAIL_SIGNATURE, "--signer", signer],
+            MAIL,
+        )
+    };
+    let output = verify("0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "OK");
+    assert!(!verify("0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB")
+        .status
+        .success());
+}
+
+#[test]
+fn domain() {
+    let output = eip712(
+        &[
+            "domain",
+            "--name",
+            "Ether Mail",
+            "--version",
+            "1",
+            "--chain-id",
+            "1",
+            "--verifying-contract",
+            "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC",
+        ],
+        "",
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "0xf2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+    );
+
+    let output = eip712(&["domain", "--name", "Ether Mail", "--chain-id", "0x1"], "");
+    let domain = Eip712Domain::builder()
+        .name("Ether Mail")
+        .chain_id(1u64)
+        .build();
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "0x{}",
+            hex::encode(DomainSeparator::new(&domain).as_bytes())
+        )
+    );
+}
+
+#[test]
+fn recover() {
+    let output = eip712(&["recover", "--signature", MAIL_SIGNATURE], MAIL);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"
+    );
+
+    let recover = |expected| {
+        eip712(
+            &[
+                "recover",
+                "--signature",
+                MAIL_SIGNATURE,
+                "--expect",
+                expected,
+            ],
+            MAIL,
+        )
+    };
+    let output = recover("0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826");
+    assert!(output.status.success());
+    assert!(stdout(&output).ends_with("matches 0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"));
+
+    let output = recover("0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB");
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("does not match"));
+}
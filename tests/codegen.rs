@@ -0,0 +1,418 @@
+#![cfg(feature = "codegen")]
+
+use eip_712_derive::codegen::*;
+use eip_712_derive::Error;
+
+const MAIL: &str = r#"
+pragma solidity ^0.8.0;
+
+contract Example {
+    // The example from EIP-712
+    struct Person {
+        string name;
+        address payable wallet;
+    }
+
+    /* Multi-line
+       comment */
+    struct Mail {
+        Person from;
+        Example.Person to;
+        string contents;
+        bytes32 messageID;
+    }
+}
+"#;
+
+#[test]
+fn parse() {
+    let structs = parse_solidity(MAIL).unwrap();
+    assert_eq!(structs.len(), 2);
+    let types: Vec<_> = structs[1].members.iter().map(|m| &m.r#type[..]).collect();
+    assert_eq!(types, ["Person", "Person", "string", "bytes32"]);
+    assert_eq!(structs[0].members[1].r#type, "address");
+
+    let structs = parse_solidity(
+        "enum Side { Buy, Sell } struct Order { Side side; uint[] amounts; uint[2] pair; }",
+    )
+    .unwrap();
+    let types: Vec<_> = structs[0].members.iter().map(|m| &m.r#type[..]).collect();
+    assert_eq!(types, ["uint8", "uint256[]", "uint256[2]"]);
+
+    let structs = parse_solidity("struct Batch { uint[] amounts; address[2][] pairs; }").unwrap();
+    let code = rust(&structs).unwrap();
+    assert!(code.contains("    pub amounts: Vec<::eip_712_derive::U256>,\n    pub pairs: Vec<[::eip_712_derive::Address; 2]>,\n"));
+}
+
+#[test]
+fn generate_rust() {
+    let expected = r#"// Generated by eip-712-derive. Do not edit.
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Person {
+    pub name: String,
+    pub wallet: ::eip_712_derive::Address,
+}
+
+impl ::eip_712_derive::StructType for Person {
+    const TYPE_NAME: &'static str = "Person";
+    fn visit_members<T: ::eip_712_derive::MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("name", &self.name);
+        visitor.visit("wallet", &self.wallet);
+    }
+    fn visit_member_types<T: ::eip_712_derive::TypeVisitor>(
+        visitor: &mut T,
+    ) -> ::std::result::Result<(), ::eip_712_derive::Error> {
+        visitor.visit::<String>("name");
+        visitor.visit::<::eip_712_derive::Address>("wallet");
+        ::std::result::Result::Ok(())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Mail {
+    pub from: Person,
+    pub to: Person,
+    pub contents: String,
+    pub message_id: ::eip_712_derive::Bytes32,
+}
+
+impl ::eip_712_derive::StructType for Mail {
+    const TYPE_NAME: &'static str = "Mail";
+    fn visit_members<T: ::eip_712_derive::MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("from", &self.from);
+        visitor.visit("to", &self.to);
+        visitor.visit("contents", &self.contents);
+        visitor.visit("messageID", &self.message_id);
+    }
+    fn visit_member_types<T: ::eip_712_derive::TypeVisitor>(
+        visitor: &mut T,
+    ) -> ::std::result::Result<(), ::eip_712_derive::Error> {
+        visitor.visit::<Person>("from");
+        visitor.visit::<Person>("to");
+        visitor.visit::<String>("contents");
+        visitor.visit::<::eip_712_derive::Bytes32>("messageID");
+        ::std::result::Result::Ok(())
+    }
+}
+"#;
+    assert_eq!(rust_from_solidity(MAIL).unwrap(), expected);
+}
+
+#[test]
+fn keyword_members() {
+    let code =
+        rust_from_solidity("struct S { uint crate; address self; uint super; string type; }")
+            .unwrap();
+    assert!(code.contains(
+        "    pub crate_: ::eip_712_derive::U256,\n    pub self_: ::eip_712_derive::Address,\n    \
+         pub super_: ::eip_712_derive::U256,\n    pub r#type: String,\n"
+    ));
+    assert!(code.contains("        visitor.visit(\"crate\", &self.crate_);\n"));
+
+    let code = rust_from_solidity("struct S { bool Self; }").unwrap();
+    assert!(code.contains("        visitor.visit(\"Self\", &self.self_);\n"));
+}
+
+#[test]
+fn errors() {
+    assert!(matches!(
+        rust_from_solidity("struct S { mapping(address => uint) balances; }"),
+        Err(Error::Codegen(_))
+    ));
+    assert!(matches!(
+        rust_from_solidity("struct S { Unknown u; }"),
+        Err(Error::Codegen(_))
+    ));
+    assert!(matches!(
+        rust_from_solidity("struct S { uint x;"),
+        Err(Error::Codegen(_))
+    ));
+}
+
+const ABI: &str = r#"[
+  {
+    "type": "function",
+    "name": "fulfillOrders",
+    "inputs": [
+      {
+        "name": "orders",
+        "type": "tuple[]",
+        "internalType": "struct Market.Order[]",
+        "components": [
+          { "name": "maker", "type": "address", "internalType": "address" },
+          {
+            "name": "asset",
+            "type": "tuple",
+            "internalType": "struct Market.Asset",
+            "components": [
+              { "name": "token", "type": "address", "internalType": "contract IERC20" },
+              { "name": "amount", "type": "uint256", "internalType": "uint256" }
+            ]
+          }
+        ]
+      },
+      { "name": "fee", "type": "tuple", "components": [
+        { "name": "token", "type": "address" },
+        { "name": "amount", "type": "uint256" }
+      ] }
+    ],
+    "outputs": []
+  },
+  { "type": "event", "name": "Filled", "inputs": [], "anonymous": false }
+]"#;
+
+#[test]
+fn parse_abi_tuples() {
+    let structs = parse_abi(ABI).unwrap();
+    let names: Vec<_> = structs.iter().map(|s| &s.name[..]).collect();
+    assert_eq!(names, ["Order", "Asset", "Fee"]);
+    assert_eq!(structs[0].members[1].r#type, "Asset");
+    assert_eq!(structs[2].members, structs[1].members);
+
+    let rust = rust(&structs[1..2]).unwrap();
+    assert!(rust.contains("pub struct Asset {\n    pub token: ::eip_712_derive::Address,\n    pub amount: ::eip_712_derive::U256,\n}"));
+
+    let conflicting = r#"[{"inputs":[
+        {"name":"a","type":"tuple","internalType":"struct A","components":[{"name":"x","type":"address"}]},
+        {"name":"b","type":"tuple","internalType":"struct A","components":[{"name":"y","type":"address"}]}
+    ]}]"#;
+    assert!(matches!(parse_abi(conflicting), Err(Error::Codegen(_))));
+}
+
+#[test]
+fn generate_typescript() {
+    use eip_712_derive::{Address, MemberVisitor, StructType, Uint8};
+
+    struct Person {
+        name: String,
+        wallets: Vec<Address>,
+    }
+    impl StructType for Person {
+        const TYPE_NAME: &'static str = "Person";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("name", &self.name);
+            visitor.visit("wallets", &self.wallets);
+        }
+    }
+    struct Mail {
+        from: Person,
+        to: [Person; 2],
+        priority: Uint8,
+        urgent: bool,
+    }
+    impl StructType for Mail {
+        const TYPE_NAME: &'static str = "Mail";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("from", &self.from);
+            visitor.visit("to", &self.to);
+            visitor.visit("priority", &self.priority);
+            visitor.visit("urgent", &self.urgent);
+        }
+    }
+
+    let person = || Person {
+        name: String::new(),
+        wallets: Vec::new(),
+    };
+    let mail = Mail {
+        from: person(),
+        to: [person(), person()],
+        priority: Uint8::default(),
+        urgent: false,
+    };
+    let expected = r#"// Generated by eip-712-derive. Do not edit.
+
+export interface Mail {
+  from: Person;
+  to: Person[];
+  priority: bigint;
+  urgent: boolean;
+}
+
+export interface Person {
+  name: string;
+  wallets: string[];
+}
+
+export const types = {
+  Mail: [
+    { name: "from", type: "Person" },
+    { name: "to", type: "Person[2]" },
+    { name: "priority", type: "uint8" },
+    { name: "urgent", type: "bool" },
+  ],
+  Person: [
+    { name: "name", type: "string" },
+    { name: "wallets", type: "address[]" },
+  ],
+};
+"#;
+    assert_eq!(typescript(&struct_defs(&mail).unwrap()).unwrap(), expected);
+
+    let structs = parse_solidity("struct S { Unknown u; }").unwrap();
+    assert!(matches!(typescript(&structs), Err(Error::Codegen(_))));
+}
+
+#[test]
+fn generate_solidity() {
+    let structs = parse_solidity(
+        "struct Person { string name; address[] wallets; }
+         struct Mail { Person from; Person[] to; string[] tags; bytes32 id; }",
+    )
+    .unwrap();
+    // The primary type first, as struct_defs returns them
+    let structs = [structs[1].clone(), structs[0].clone()];
+    let expected = r#"// Generated by eip-712-derive. Do not edit.
+pragma solidity ^0.8.4;
+
+struct Mail {
+    Person from;
+    Person[] to;
+    string[] tags;
+    bytes32 id;
+}
+
+bytes32 constant MAIL_TYPEHASH = keccak256("Mail(Person from,Person[] to,string[] tags,bytes32 id)Person(string name,address[] wallets)");
+
+struct Person {
+    string name;
+    address[] wallets;
+}
+
+bytes32 constant PERSON_TYPEHASH = keccak256("Person(string name,address[] wallets)");
+
+function hashStruct(Mail memory value) pure returns (bytes32) {
+    return keccak256(abi.encode(MAIL_TYPEHASH, hashStruct(value.from), hashArray(value.to), hashArray(value.tags), value.id));
+}
+
+function hashStruct(Person memory value) pure returns (bytes32) {
+    return keccak256(abi.encode(PERSON_TYPEHASH, keccak256(bytes(value.name)), keccak256(abi.encodePacked(value.wallets))));
+}
+
+function hashArray(Person[] memory values) pure returns (bytes32) {
+    bytes32[] memory hashes = new bytes32[](values.length);
+    for (uint256 i = 0; i < values.length; i++) {
+        hashes[i] = hashStruct(values[i]);
+    }
+    return keccak256(abi.encodePacked(hashes));
+}
+
+function hashArray(string[] memory values) pure returns (bytes32) {
+    bytes32[] memory hashes = new bytes32[](values.length);
+    for (uint256 i = 0; i < values.length; i++) {
+        hashes[i] = keccak256(bytes(values[i]));
+    }
+    return keccak256(abi.encodePacked(hashes));
+}
+
+/// Whether the signature (v, r, s) of `value` under `domainSeparator` is by `signer`
+function verify(bytes32 domainSeparator, Mail memory value, address signer, uint8 v, bytes32 r, bytes32 s) pure returns (bool) {
+    bytes32 digest = keccak256(abi.encodePacked("\x19\x01", domainSeparator, hashStruct(value)));
+    address recovered = ecrecover(digest, v, r, s);
+    return recovered != address(0) && recovered == signer;
+}
+"#;
+    assert_eq!(solidity(&structs).unwrap(), expected);
+
+    let nested = parse_solidity("struct S { uint[][] grid; }").unwrap();
+    assert!(matches!(solidity(&nested), Err(Error::Codegen(_))));
+}
+
+#[test]
+fn parse_typed_data_types() {
+    let json = r#"{
+        "types": {
+            "EIP712Domain": [
+                { "name": "name", "type": "string" },
+                { "name": "chainId", "type": "uint256" }
+            ],
+            "Person": [
+                { "name": "name", "type": "string" },
+                { "name": "wallet", "type": "address" }
+            ],
+            "Mail": [
+                { "name": "from", "type": "Person" },
+                { "name": "to", "type": "Person[]" },
+                { "name": "contents", "type": "string" }
+            ]
+        },
+        "primaryType": "Mail",
+        "domain": {},
+        "message": {}
+    }"#;
+    let structs = parse_typed_data(json).unwrap();
+    let names: Vec<_> = structs.iter().map(|s| &s.name[..]).collect();
+    assert_eq!(names, ["Mail", "EIP712Domain", "Person"]);
+    assert_eq!(structs[0].members[1].r#type, "Person[]");
+
+    let code = rust_from_typed_data(json).unwrap();
+    assert!(code.contains("pub struct Mail {\n    pub from: Person,\n    pub to: Vec<Person>,\n    pub contents: String,\n}"));
+    assert!(code.contains("pub struct EIP712Domain {\n    pub name: String,\n    pub chain_id: ::eip_712_derive::U256,\n}"));
+
+    // The types object alone
+    let types: serde_json::Value = serde_json::from_str(json).unwrap();
+    let structs = parse_typed_data(&types["types"].to_string()).unwrap();
+    let names: Vec<_> = structs.iter().map(|s| &s.name[..]).collect();
+    assert_eq!(names, ["EIP712Domain", "Mail", "Person"]);
+
+    for invalid in [
+        r#"{ "types": [] }"#,
+        r#"{ "S": [{ "name": "x" }] }"#,
+        r#"{ "S$": [] }"#,
+        r#"{ "S": [{ "name": "x", "type": "Unknown" }] }"#,
+    ] {
+        assert!(matches!(
+            rust_from_typed_data(invalid),
+            Err(Error::Codegen(_))
+        ));
+    }
+}
+
+#[test]
+fn builder() {
+    let dir = std::env::temp_dir().join(format!("eip712-codegen-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("Mail.sol"), MAIL).unwrap();
+    std::fs::write(dir.join("Market.json"), format!(r#"{{ "abi": {} }}"#, ABI)).unwrap();
+    std::fs::write(
+        dir.join("typed_data.json"),
+        r#"{ "Person": [
+            { "name": "name", "type": "string" },
+            { "name": "wallet", "type": "address" }
+        ] }"#,
+    )
+    .unwrap();
+
+    let builder = Builder::new()
+        .solidity(dir.join("Mail.sol"))
+        .abi(dir.join("Market.json"))
+        .typed_data(dir.join("typed_data.json"));
+    let names: Vec<_> = builder
+        .structs()
+        .unwrap()
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    // Person is defined identically twice
+    assert_eq!(names, ["Person", "Mail", "Order", "Asset", "Fee"]);
+
+    let out = dir.join("eip712.rs");
+    builder.write(&out).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&out).unwrap(),
+        builder.generate().unwrap()
+    );
+
+    std::fs::write(
+        dir.join("conflict.json"),
+        r#"{ "Person": [{ "name": "name", "type": "string" }] }"#,
+    )
+    .unwrap();
+    let conflicting = builder.clone().typed_data(dir.join("conflict.json"));
+    assert!(matches!(conflicting.generate(), Err(Error::Codegen(_))));
+    let missing = Builder::new().solidity(dir.join("Missing.sol"));
+    assert!(matches!(missing.generate(), Err(Error::Codegen(_))));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
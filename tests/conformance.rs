@@ -0,0 +1,37 @@
+#![cfg(feature = "conformance")]
+
+use eip_712_derive::conformance::{fixtures, run_bundled, run_conformance, Fixture};
+
+#[test]
+fn bundled_fixtures() {
+    run_bundled();
+}
+
+// A downstream fixture, with a deliberately wrong expectation
+struct WrongTypeHash;
+
+impl Fixture for WrongTypeHash {
+    type Domain = fixtures::MailDomain;
+    type Message = fixtures::MailMessage;
+
+    const ENCODE_TYPE: &'static str = fixtures::Mail::ENCODE_TYPE;
+    const TYPE_HASH: &'static str =
+        "0000000000000000000000000000000000000000000000000000000000000000";
+    const ENCODE_DATA: &'static str = fixtures::Mail::ENCODE_DATA;
+    const HASH_STRUCT: &'static str = fixtures::Mail::HASH_STRUCT;
+    const DOMAIN_SEPARATOR: &'static str = fixtures::Mail::DOMAIN_SEPARATOR;
+    const SIGN_HASH: &'static str = fixtures::Mail::SIGN_HASH;
+
+    fn domain() -> Self::Domain {
+        fixtures::Mail::domain()
+    }
+    fn message() -> Self::Message {
+        fixtures::Mail::message()
+    }
+}
+
+#[test]
+#[should_panic(expected = "typeHash differs")]
+fn reports_mismatch() {
+    run_conformance::<WrongTypeHash>();
+}
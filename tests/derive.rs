@@ -0,0 +1,314 @@
+#![cfg(feature = "derive")]
+
+use eip_712_derive::*;
+
+#[derive(StructType)]
+struct Person {
+    name: String,
+    wallet: Address,
+}
+
+#[derive(StructType)]
+struct Mail {
+    from: Person,
+    to: Person,
+    contents: String,
+}
+
+#[test]
+fn derived_members_keep_field_order() {
+    let person = |name: &str, byte| Person {
+        name: name.to_owned(),
+        wallet: Address([byte; 20]),
+    };
+    let mail = Mail {
+        from: person("Cow", 0xcd),
+        to: person("Bob", 0xbb),
+        contents: "Hello, Bob!".to_owned(),
+    };
+    assert_eq!(
+        encode_type(&mail),
+        "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+    );
+
+    let mut expected = Vec::new();
+    expected.extend_from_slice(&type_hash(&mail));
+    expected.extend_from_slice(&hash_struct(&mail.from));
+    expected.extend_from_slice(&hash_struct(&mail.to));
+    expected.extend_from_slice(&keccak_hash::keccak(b"Hello, Bob!").0);
+    assert_eq!(encode_data(&mail), expected);
+}
+
+#[derive(StructType)]
+struct EIP712Domain {
+    name: String,
+    #[eip712(rename = "chainId")]
+    chain: U256,
+    #[eip712(rename = "verifyingContract")]
+    contract: Address,
+}
+
+#[test]
+fn renamed_members() {
+    let domain = EIP712Domain {
+        name: "Ether Mail".to_owned(),
+        chain: U256::default(),
+        contract: Address::default(),
+    };
+    assert_eq!(
+        encode_type(&domain),
+        "EIP712Domain(string name,uint256 chainId,address verifyingContract)"
+    );
+}
+
+#[derive(StructType)]
+#[eip712(rename_all = "camelCase")]
+struct Permit {
+    owner: Address,
+    spender: Address,
+    value: U256,
+    nonce: U256,
+    #[eip712(rename = "deadline")]
+    valid_until: U256,
+    r#type: U256,
+    token_id_hi: U256,
+}
+
+#[test]
+fn camel_case_members() {
+    let permit = Permit {
+        owner: Address::default(),
+        spender: Address::default(),
+        value: U256::default(),
+        nonce: U256::default(),
+        valid_until: U256::default(),
+        r#type: U256::default(),
+        token_id_hi: U256::default(),
+    };
+    assert_eq!(
+        encode_type(&permit),
+        "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline,uint256 type,uint256 tokenIdHi)"
+    );
+}
+
+#[derive(StructType)]
+struct Order {
+    #[eip712(skip)]
+    id: u64,
+    maker: Address,
+    amount: U256,
+    #[eip712(skip)]
+    created_at: std::time::SystemTime,
+}
+
+#[derive(StructType)]
+struct OrderMessage {
+    maker: Address,
+    amount: U256,
+}
+
+#[test]
+fn skipped_members() {
+    let order = Order {
+        id: 7,
+        maker: Address([1; 20]),
+        amount: U256([2; 32]),
+        created_at: std::time::SystemTime::now(),
+    };
+    assert_eq!(encode_type(&order), "Order(address maker,uint256 amount)");
+
+    let message = OrderMessage {
+        maker: order.maker,
+        amount: order.amount,
+    };
+    assert_eq!(encode_data(&order)[32..], encode_data(&message)[32..]);
+    assert_eq!(order.id, 7);
+    assert!(order.created_at.elapsed().is_ok());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn from_typed_data_message() {
+    use serde_json::json;
+
+    let mail = Mail::from_typed_data_message(&json!({
+        "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+        "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+        "contents": "Hello, Bob!",
+    }))
+    .unwrap();
+    assert_eq!(mail.to.name, "Bob");
+    assert_eq!(
+        hex::encode(hash_struct(&mail)),
+        "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+    );
+
+    let permit = Permit::from_typed_data_message(&json!({
+        "owner": "0x1111111111111111111111111111111111111111",
+        "spender": "0x2222222222222222222222222222222222222222",
+        "value": "1000000000000000000",
+        "nonce": 0,
+        "deadline": "0xffffffff",
+        "type": 1,
+        "tokenIdHi": "2",
+    }))
+    .unwrap();
+    assert_eq!(permit.valid_until, U256::from(0xffff_ffffu64));
+
+    let errors = [
+        // Unknown member
+        json!({ "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826", "age": 3 }),
+        // Missing member
+        json!({ "name": "Cow" }),
+        // Wrong type
+        json!({ "name": "Cow", "wallet": "0xCD2a3d9F" }),
+        json!({ "name": 3, "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" }),
+    ];
+    for error in &errors {
+        assert!(matches!(
+            Person::from_typed_data_message(error),
+            Err(Error::InvalidTypedData(_))
+        ));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, MemberType)]
+#[eip712(repr = "uint8")]
+enum Side {
+    Buy,
+    Sell = 3,
+}
+
+#[derive(StructType)]
+struct Trade {
+    side: Side,
+    amount: U256,
+}
+
+#[test]
+fn enum_members() {
+    let trade = Trade {
+        side: Side::Sell,
+        amount: U256::from(5u64),
+    };
+    assert_eq!(encode_type(&trade), "Trade(uint8 side,uint256 amount)");
+    assert_eq!(Side::Buy.encode_data(), U256::from(0u64).encode_data());
+    assert_eq!(Side::Sell.encode_data(), U256::from(3u64).encode_data());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn enum_from_json() {
+    use serde_json::json;
+
+    assert_eq!(Side::from_json(&json!(3)).unwrap(), Side::Sell);
+    assert_eq!(Side::from_json(&json!("0")).unwrap(), Side::Buy);
+    assert!(matches!(
+        Side::from_json(&json!(1)),
+        Err(Error::InvalidTypedData(_))
+    ));
+}
+
+trait Envelope: MemberType {
+    const ENVELOPE: &'static str;
+}
+
+impl Envelope for Person {
+    const ENVELOPE: &'static str = "PersonEnvelope";
+}
+
+impl Envelope for Side {
+    const ENVELOPE: &'static str = "SideEnvelope";
+}
+
+#[derive(StructType)]
+#[eip712(type_name = T::ENVELOPE)]
+struct Signed<T: Envelope> {
+    nonce: U256,
+    payload: T,
+}
+
+#[derive(StructType)]
+struct Batch {
+    person: Signed<Person>,
+    side: Signed<Side>,
+}
+
+#[test]
+fn generic_members() {
+    let batch = Batch {
+        person: Signed {
+            nonce: U256::from(1u64),
+            payload: Person {
+                name: "Cow".to_owned(),
+                wallet: Address([0xcd; 20]),
+            },
+        },
+        side: Signed {
+            nonce: U256::from(2u64),
+            payload: Side::Buy,
+        },
+    };
+    assert_eq!(
+        encode_type(&batch),
+        "Batch(PersonEnvelope person,SideEnvelope side)\
+         Person(string name,address wallet)\
+         PersonEnvelope(uint256 nonce,Person payload)\
+         SideEnvelope(uint256 nonce,uint8 payload)"
+    );
+}
+
+#[test]
+fn type_without_value() {
+    assert_eq!(
+        encode_type_of::<Mail>(),
+        "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+    );
+    assert_eq!(
+        encode_type_of::<Batch>(),
+        "Batch(PersonEnvelope person,SideEnvelope side)\
+         Person(string name,address wallet)\
+         PersonEnvelope(uint256 nonce,Person payload)\
+         SideEnvelope(uint256 nonce,uint8 payload)"
+    );
+    assert_eq!(
+        encode_type_of::<Box<Order>>(),
+        "Order(address maker,uint256 amount)"
+    );
+    assert_eq!(
+        type_hash_of::<Eip712Domain>(),
+        type_hash(&Eip712Domain::default())
+    );
+    assert!(matches!(
+        try_type_hash_of::<PartialDomain>(),
+        Err(Error::TypeNeedsValue(_))
+    ));
+}
+
+#[test]
+fn type_descriptor() {
+    let descriptor = TypeDescriptor::of::<Mail>().unwrap();
+    assert_eq!(descriptor.primary.name, "Mail");
+    let members: Vec<_> = descriptor
+        .primary
+        .members
+        .iter()
+        .map(|m| (m.name, m.r#type.as_ref()))
+        .collect();
+    assert_eq!(
+        members,
+        [("from", "Person"), ("to", "Person"), ("contents", "string")]
+    );
+    assert_eq!(descriptor.referenced.len(), 1);
+    assert_eq!(
+        descriptor.get("Person").unwrap().to_string(),
+        "Person(string name,address wallet)"
+    );
+    assert!(descriptor.get("Order").is_none());
+    assert_eq!(descriptor.to_string(), encode_type_of::<Mail>());
+
+    let reduced = Eip712Domain::builder().chain_id(1u64).build();
+    let descriptor = TypeDescriptor::of_value(&reduced).unwrap();
+    assert_eq!(descriptor.to_string(), "EIP712Domain(uint256 chainId)");
+    assert!(descriptor.referenced.is_empty());
+}
@@ -0,0 +1,232 @@
+use eip_712_derive::dynamic::*;
+use eip_712_derive::*;
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+
+fn address(s: &str) -> Value {
+    Value::Address(Address(hex::decode(s).unwrap()[..].try_into().unwrap()))
+}
+
+fn value(members: Vec<(&str, Value)>) -> Value {
+    Value::Struct(
+        members
+            .into_iter()
+            .map(|(k, v)| (k.to_owned(), v))
+            .collect::<BTreeMap<_, _>>(),
+    )
+}
+
+fn mail_types() -> Types {
+    let mut types = Types::new();
+    types
+        .insert(
+            TypeDefinition::new("EIP712Domain")
+                .member("name", "string")
+                .member("version", "string")
+                .member("chainId", "uint256")
+                .member("verifyingContract", "address"),
+        )
+        .unwrap();
+    types
+        .insert(
+            TypeDefinition::new("Mail")
+                .member("from", "Person")
+                .member("to", "Person")
+                .member("contents", "string"),
+        )
+        .unwrap();
+    types
+        .insert(
+            TypeDefinition::new("Person")
+                .member("name", "string")
+                .member("wallet", "address"),
+        )
+        .unwrap();
+    types
+}
+
+fn person(name: &str, wallet: &str) -> Value {
+    value(vec![("name", name.into()), ("wallet", address(wallet))])
+}
+
+#[test]
+fn spec_case() {
+    let types = mail_types();
+    let domain = value(vec![
+        ("name", "Ether Mail".into()),
+        ("version", "1".into()),
+        ("chainId", 1u64.into()),
+        (
+            "verifyingContract",
+            address("CcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"),
+        ),
+    ]);
+    let message = value(vec![
+        (
+            "from",
+            person("Cow", "CD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"),
+        ),
+        (
+            "to",
+            person("Bob", "bBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"),
+        ),
+        ("contents", "Hello, Bob!".into()),
+    ]);
+
+    assert_eq!(
+        types.encode_type("Mail").unwrap(),
+        "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+    );
+    assert_eq!(
+        hex::encode(types.hash_struct("Mail", &message).unwrap()),
+        "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+    );
+    let domain_separator = types.domain_separator(&domain).unwrap();
+    assert_eq!(
+        hex::encode(domain_separator.as_bytes()),
+        "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+    );
+    assert_eq!(
+        hex::encode(
+            types
+                .sign_hash(&domain_separator, "Mail", &message)
+                .unwrap()
+        ),
+        "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+    );
+}
+
+#[test]
+fn parse_encode_type() {
+    let encoded = "Mail(Person from,Person to,string contents)Person(string name,address wallet)";
+    let (primary, types) = Types::parse_encode_type(encoded).unwrap();
+    assert_eq!(primary, "Mail");
+    assert_eq!(types.get("Mail"), mail_types().get("Mail"));
+    assert_eq!(types.get("Person"), mail_types().get("Person"));
+    assert_eq!(types.encode_type(&primary).unwrap(), encoded);
+
+    // Dependencies in any order, and structs without members
+    let (primary, types) = Types::parse_encode_type("A(C c,B b)C()B(C[2] c)").unwrap();
+    assert_eq!(primary, "A");
+    assert_eq!(types.encode_type("A").unwrap(), "A(C c,B b)B(C[2] c)C()");
+
+    for malformed in [
+        "",
+        "Mail",
+        "Mail(string",
+        "Mail)string contents(",
+        "Mail(stringcontents)",
+        "Mail(string contents,)",
+        "Mail(string contents)trailing",
+    ] {
+        assert!(
+            Types::parse_encode_type(malformed).is_err(),
+            "{:?}",
+            malformed
+        );
+    }
+    assert_eq!(
+        Types::parse_encode_type("A(uint256 a)A(uint256 a)"),
+        Err(Error::DuplicateTypeName("A".to_owned()))
+    );
+    assert_eq!(
+        Types::parse_encode_type("A(uint256 a b)"),
+        Err(Error::InvalidIdentifier("A.a b".to_owned()))
+    );
+}
+
+#[test]
+fn invalid_values() {
+    let mut types = mail_types();
+    types
+        .insert(
+            TypeDefinition::new("Numbers")
+                .member("small", "uint8")
+                .member("signed", "int8")
+                .member("fixed", "bytes2")
+                .member("pair", "uint8[2]"),
+        )
+        .unwrap();
+    let numbers = |small: u64, signed: i128, fixed: Vec<u8>, pair: Vec<Value>| {
+        value(vec![
+            ("small", small.into()),
+            ("signed", Value::int(signed)),
+            ("fixed", Value::Bytes(fixed)),
+            ("pair", Value::Array(pair)),
+        ])
+    };
+    let ok = numbers(255, -128, vec![1, 2], vec![1u64.into(), 2u64.into()]);
+    assert!(types.hash_struct("Numbers", &ok).is_ok());
+
+    for bad in [
+        numbers(256, 0, vec![1, 2], vec![1u64.into(), 2u64.into()]),
+        numbers(0, -129, vec![1, 2], vec![1u64.into(), 2u64.into()]),
+        numbers(0, 128, vec![1, 2], vec![1u64.into(), 2u64.into()]),
+        numbers(0, 0, vec![1], vec![1u64.into(), 2u64.into()]),
+        numbers(0, 0, vec![1, 2], vec![1u64.into()]),
+        value(vec![("small", 0u64.into())]),
+    ] {
+        assert!(matches!(
+            types.hash_struct("Numbers", &bad),
+            Err(Error::InvalidTypedData(_))
+        ));
+    }
+
+    // Undefined member types are found before any value is looked at
+    types
+        .insert(TypeDefinition::new("Broken").member("x", "Missing[]"))
+        .unwrap();
+    assert!(matches!(
+        types.encode_type("Broken"),
+        Err(Error::InvalidTypedData(_))
+    ));
+
+    assert!(matches!(
+        types.insert(TypeDefinition::new("Person")),
+        Err(Error::DuplicateTypeName(_))
+    ));
+    assert!(matches!(
+        types.insert(
+            TypeDefinition::new("Pair")
+                .member("a", "bool")
+                .member("a", "bool")
+        ),
+        Err(Error::DuplicateMember { .. })
+    ));
+    assert!(matches!(
+        types.insert(TypeDefinition::new("Not a name")),
+        Err(Error::InvalidIdentifier(_))
+    ));
+}
+
+#[test]
+fn typed_data() {
+    let typed_data = TypedData {
+        types: mail_types(),
+        primary_type: "Person".to_owned(),
+        domain: value(vec![
+            ("name", "Ether Mail".into()),
+            ("version", "1".into()),
+            ("chainId", 1u64.into()),
+            ("verifyingContract", Value::Address(Address([0xcc; 20]))),
+        ]),
+        message: person("Cow", "CD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"),
+    };
+    let hashes = typed_data.hashes().unwrap();
+    assert_eq!(
+        hex::encode(hashes.struct_hash),
+        "fc71e5fa27ff56c350aa531bc129ebdf613b772b6604664f5d8dbe21b85eb0c8"
+    );
+    assert_eq!(
+        hex::encode(hashes.domain_separator.as_bytes()),
+        "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+    );
+    assert_eq!(typed_data.digest().unwrap(), hashes.digest);
+
+    #[cfg(feature = "sign")]
+    {
+        let key = SecretKey::from_bytes(&[7u8; 32]).unwrap();
+        let signature = typed_data.sign(&key).unwrap();
+        assert_eq!(typed_data.recover(&signature).unwrap(), key.address());
+    }
+}
@@ -51,37 +51,22 @@ fn spec_case() {
     // as well as the accompanying example here:
     // https://github.com/ethereum/EIPs/blob/master/assets/eip-712/Example.js
 
-    let mut chain_id = U256([0_u8; 32]);
-    chain_id.0[31] = 1;
-
     let domain = DomainStruct {
         name: "Ether Mail".to_owned(),
         version: "1".to_owned(),
-        chain_id,
-        verifying_contract: Address(
-            (&(hex::decode("CcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC").unwrap())[..])
-                .try_into()
-                .unwrap(),
-        ),
+        chain_id: U256::from(1u64),
+        verifying_contract: address!("CcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"),
     };
     let domain_separator = DomainSeparator::new(&domain);
 
     let message = Mail {
         from: Person {
             name: "Cow".to_owned(),
-            wallet: Address(
-                (&(hex::decode("CD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826").unwrap())[..])
-                    .try_into()
-                    .unwrap(),
-            ),
+            wallet: address!("CD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"),
         },
         to: Person {
             name: "Bob".to_owned(),
-            wallet: Address(
-                (&(hex::decode("bBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB").unwrap())[..])
-                    .try_into()
-                    .unwrap(),
-            ),
+            wallet: address!("bBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"),
         },
         contents: "Hello, Bob!".to_owned(),
     };
@@ -99,6 +84,8 @@ fn spec_case() {
             &hex::encode(encode_data(&message)),
             "a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2fc71e5fa27ff56c350aa531bc129ebdf613b772b6604664f5d8dbe21b85eb0c8cd54f074a4af31b4411ff6a60c9719dbd559c221c8ac3492d9d872b041d703d1b5aadf3154a261abdd9086fc627b61efca26ae5702701d05cd2305f7c52a2fc8"
         );
+    // Allocated once, at the exact size
+    assert_eq!(encode_data(&message).capacity(), 32 * 4);
 
     assert_eq!(
         &hex::encode(hash_struct(&message)),
@@ -115,14 +102,285 @@ fn spec_case() {
         "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2",
     );
 
-    let pk = keccak_hash::keccak("cow").to_fixed_bytes();
+    let hashes = encode_eip712(&domain_separator, &message);
+    assert_eq!(hashes.type_hash, type_hash(&message));
+    assert_eq!(hashes.struct_hash, hash_struct(&message));
+    assert_eq!(hashes.domain_separator, domain_separator);
+    assert_eq!(hashes.digest, sign_hash(&domain_separator, &message));
+
+    #[cfg(feature = "sign")]
+    {
+        let pk = SecretKey::from_bytes(&keccak_hash::keccak("cow").to_fixed_bytes()).unwrap();
+
+        let signature = sign_typed(&domain_separator, &message, &pk).unwrap();
+        let result = hex::encode(signature.to_bytes());
+        let expected = "4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b915621c";
+
+        assert_eq!(expected, result);
+
+        assert_eq!(
+            signature
+                .recover(&sign_hash(&domain_separator, &message))
+                .unwrap(),
+            message.from.wallet
+        );
+        assert_eq!(
+            recover_address(&domain_separator, &message, &signature),
+            Ok(message.from.wallet)
+        );
+        assert_eq!(
+            verify_typed(
+                &domain_separator,
+                &message,
+                &signature,
+                &message.from.wallet
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            verify_typed(&domain_separator, &message, &signature, &message.to.wallet),
+            Err(Error::SignerMismatch {
+                expected: message.to.wallet,
+                recovered: message.from.wallet
+            })
+        );
+        let mut malformed = signature;
+        malformed.s = [0xff; 32];
+        assert_eq!(
+            verify_typed(
+                &domain_separator,
+                &message,
+                &malformed,
+                &message.from.wallet
+            ),
+            Err(Error::MalformedSignature)
+        );
+        assert_eq!(
+            Signature::from_bytes(&hex::decode(expected).unwrap()).unwrap(),
+            signature
+        );
+
+        // The high-s form of the signature, n - s with the other parity, recovers
+        // to the same signer with either backend
+        let n = hex::decode("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141")
+            .unwrap();
+        let mut high_s = signature;
+        let mut borrow = 0i16;
+        for i in (0..32).rev() {
+            let diff = n[i] as i16 - signature.s[i] as i16 - borrow;
+            high_s.s[i] = diff.rem_euclid(256) as u8;
+            borrow = (diff < 0) as i16;
+        }
+        high_s.recovery_id = match signature.recovery_id {
+            RecoveryId::Parity0 => RecoveryId::Parity1,
+            RecoveryId::Parity1 => RecoveryId::Parity0,
+        };
+        assert_eq!(
+            recover_address(&domain_separator, &message, &high_s),
+            Ok(message.from.wallet)
+        );
+    }
+}
+
+#[cfg(feature = "sign")]
+#[test]
+fn invalid_private_key() {
+    assert_eq!(
+        SecretKey::from_bytes(&[0; 32]),
+        Err(Error::InvalidPrivateKey)
+    );
+    assert_eq!(
+        SecretKey::from_bytes(&[0xff; 32]),
+        Err(Error::InvalidPrivateKey)
+    );
+}
+
+#[test]
+fn compact_signatures() {
+    // The test vectors of EIP-2098
+    let r = "68a020a209d3d56c46f38cc50a33f704f4a9a10a59377f8dd762ac66910e9b90";
+    let s = "7e865ad05c4035ab5792787d4a0297a43617ae897930a6fe4d822b8faea52064";
+    let signature = Signature::new(
+        &hex::decode(format!("{}{}", r, s)).unwrap()[..]
+            .try_into()
+            .unwrap(),
+        RecoveryId::Parity0,
+    );
+    let compact = signature.to_compact().unwrap();
+    assert_eq!(hex::encode(compact), format!("{}{}", r, s));
+    assert_eq!(Signature::from_compact(&compact), Ok(signature));
+
+    let r = "9328da16089fcba9bececa81663203989f2df5fe1faa6291a45381c81bd17f76";
+    let s = "139c6d6b623b42da56557e5e734a43dc83345ddfadec52cbe24d0cc64f550793";
+    let y_parity_and_s = "939c6d6b623b42da56557e5e734a43dc83345ddfadec52cbe24d0cc64f550793";
+    let signature = Signature::new(
+        &hex::decode(format!("{}{}", r, s)).unwrap()[..]
+            .try_into()
+            .unwrap(),
+        RecoveryId::Parity1,
+    );
+    let compact = signature.to_compact().unwrap();
+    assert_eq!(hex::encode(compact), format!("{}{}", r, y_parity_and_s));
+    assert_eq!(Signature::from_compact(&compact), Ok(signature));
+
+    assert_eq!(
+        Signature::from_compact(&[0; 65]),
+        Err(Error::MalformedSignature)
+    );
+    let high_s = Signature::new(&[0xff; 64], RecoveryId::Parity0);
+    assert_eq!(high_s.to_compact(), None);
+}
+
+#[test]
+fn recovery_id_conversions() {
+    assert_eq!(RecoveryId::Parity0.to_parity(), 0);
+    assert_eq!(RecoveryId::Parity1.to_eth_v(), 28);
+    assert_eq!(RecoveryId::from_eth_v(27), Some(RecoveryId::Parity0));
+    assert_eq!(RecoveryId::from_eth_v(1), None);
+    assert_eq!(RecoveryId::from_parity(1), Some(RecoveryId::Parity1));
+    assert_eq!(RecoveryId::from_parity(27), None);
+}
+
+#[test]
+fn signature_parsing() {
+    let mut bytes = [1u8; 65];
+    bytes[64] = 0;
+    assert_eq!(
+        Signature::from_bytes(&bytes).unwrap().recovery_id,
+        RecoveryId::Parity0
+    );
+    bytes[64] = 28;
+    assert_eq!(
+        Signature::from_bytes(&bytes).unwrap().recovery_id,
+        RecoveryId::Parity1
+    );
+    bytes[64] = 29;
+    assert_eq!(
+        Signature::from_bytes(&bytes),
+        Err(Error::InvalidRecoveryId(29))
+    );
+    assert_eq!(
+        Signature::from_bytes(&bytes[..64]),
+        Err(Error::MalformedSignature)
+    );
+}
+
+#[test]
+fn domain_builder() {
+    let verifying_contract = address!("CcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC");
+    let domain = DomainStruct {
+        name: "Ether Mail".to_owned(),
+        version: "1".to_owned(),
+        chain_id: U256::from(1u64),
+        verifying_contract,
+    };
+    let built = Eip712Domain::builder()
+        .name("Ether Mail")
+        .version("1")
+        .chain_id(1u64)
+        .verifying_contract(verifying_contract)
+        .build();
+    assert_eq!(
+        encode_type(&built),
+        "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
+    );
+    assert_eq!(DomainSeparator::new(&built), DomainSeparator::new(&domain));
+
+    // The type hash follows the fields which are set
+    let reduced = Eip712Domain::builder().name("Ether Mail").build();
+    assert_eq!(encode_type(&reduced), "EIP712Domain(string name)");
+    assert_eq!(
+        type_hash(&reduced),
+        keccak_hash::keccak(b"EIP712Domain(string name)").0
+    );
+    let full = PartialDomain::from(Eip712Domain::default());
+    assert_eq!(
+        DomainSeparator::new(&full),
+        DomainSeparator::new(&Eip712Domain::default())
+    );
+}
+
+// A message which signs over a domain, eg: to approve a contract
+struct Approval {
+    domain: PartialDomain,
+}
+
+impl StructType for Approval {
+    const TYPE_NAME: &'static str = "Approval";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("domain", &self.domain);
+    }
+}
+
+#[test]
+fn nested_partial_domains() {
+    let named = Approval {
+        domain: Eip712Domain::builder().name("Ether Mail").build(),
+    };
+    let chained = Approval {
+        domain: Eip712Domain::builder().chain_id(1u64).build(),
+    };
+    for _ in 0..2 {
+        assert_eq!(
+            type_hash(&named),
+            keccak_hash::keccak(b"Approval(EIP712Domain domain)EIP712Domain(string name)").0
+        );
+        assert_eq!(
+            type_hash(&chained),
+            keccak_hash::keccak(b"Approval(EIP712Domain domain)EIP712Domain(uint256 chainId)").0
+        );
+    }
+}
+
+#[test]
+fn domain_registry() {
+    let registry = DomainRegistry::new();
+    let domain = Eip712Domain {
+        name: "Ether Mail".to_owned(),
+        ..Eip712Domain::default()
+    };
+    assert_eq!(
+        registry.domain_separator(&domain),
+        DomainSeparator::new(&domain)
+    );
+    registry.domain_separator(&domain);
+    registry.domain_separator(&Eip712Domain::default());
+    assert_eq!(registry.len(), 2);
+
+    let global = DomainRegistry::global();
+    assert_eq!(
+        global.domain_separator(&domain),
+        DomainSeparator::new(&domain)
+    );
+    assert!(std::ptr::eq(global, DomainRegistry::global()));
+
+    let partial = DomainRegistry::<PartialDomain>::new();
+    let reduced = Eip712Domain::builder().name("Ether Mail").build();
+    assert_eq!(
+        partial.domain_separator(&reduced),
+        DomainSeparator::new(&reduced)
+    );
+}
+
+#[test]
+fn encode_into_buffers() {
+    let message = Person {
+        name: "Cow".to_owned(),
+        wallet: address!("CD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"),
+    };
+    let mut buffer = Vec::new();
+    for _ in 0..2 {
+        buffer.clear();
+        encode_data_into(&message, &mut buffer).unwrap();
+        assert_eq!(buffer, encode_data(&message));
+    }
 
-    let result = sign_typed(&domain_separator, &message, &pk).unwrap();
-    let mut serialized = Vec::new();
-    serialized.extend_from_slice(&result.0);
-    serialized.push(result.1);
-    let result = hex::encode(&serialized);
-    let expected = "4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b915621c";
+    // Errors from the writer are returned
+    let mut short = [0u8; 40];
+    assert!(encode_data_into(&message, &mut &mut short[..]).is_err());
 
-    assert_eq!(expected, result);
+    let domain_separator = DomainSeparator::new(&Eip712Domain::default());
+    let mut encoded = [0u8; 66];
+    encode_into(&domain_separator, &message, &mut encoded);
+    assert_eq!(encoded, encode(&domain_separator, &message));
 }
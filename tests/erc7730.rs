@@ -0,0 +1,99 @@
+#![cfg(feature = "erc7730")]
+
+use eip_712_derive::erc7730::{descriptor, DisplayHints};
+use eip_712_derive::*;
+use serde_json::json;
+
+struct DomainStruct {
+    name: String,
+    verifying_contract: Address,
+}
+impl StructType for DomainStruct {
+    const TYPE_NAME: &'static str = "EIP712Domain";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("name", &self.name);
+        visitor.visit("verifyingContract", &self.verifying_contract);
+    }
+}
+
+#[derive(Default)]
+struct Person {
+    name: String,
+    wallet: Address,
+}
+impl StructType for Person {
+    const TYPE_NAME: &'static str = "Person";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("name", &self.name);
+        visitor.visit("wallet", &self.wallet);
+    }
+}
+
+#[derive(Default)]
+struct Mail {
+    from: Person,
+    contents: String,
+}
+impl StructType for Mail {
+    const TYPE_NAME: &'static str = "Mail";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("from", &self.from);
+        visitor.visit("contents", &self.contents);
+    }
+}
+
+#[test]
+fn mail_descriptor() {
+    let domain = DomainStruct {
+        name: "Ether Mail".to_owned(),
+        verifying_contract: Address([0xcc; 20]),
+    };
+    let hints = DisplayHints::new()
+        .owner("Ether Mail")
+        .intent("Send mail")
+        .deployment(1, Address([0xcc; 20]))
+        .field("from.wallet", "Sender", "addressName");
+
+    let expected = json!({
+        "$schema": "https://eips.ethereum.org/assets/eip-7730/erc7730-v1.schema.json",
+        "context": {
+            "eip712": {
+                "deployments": [
+                    { "chainId": 1, "address": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC" }
+                ],
+                "schemas": [{
+                    "primaryType": "Mail",
+                    "types": {
+                        "EIP712Domain": [
+                            { "name": "name", "type": "string" },
+                            { "name": "verifyingContract", "type": "address" }
+                        ],
+                        "Mail": [
+                            { "name": "from", "type": "Person" },
+                            { "name": "contents", "type": "string" }
+                        ],
+                        "Person": [
+                            { "name": "name", "type": "string" },
+                            { "name": "wallet", "type": "address" }
+                        ]
+                    }
+                }]
+            }
+        },
+        "metadata": { "owner": "Ether Mail" },
+        "display": {
+            "formats": {
+                "Mail(Person from,string contents)Person(string name,address wallet)": {
+                    "intent": "Send mail",
+                    "fields": [
+                        { "path": "from.name", "label": "name", "format": "raw" },
+                        { "path": "from.wallet", "label": "Sender", "format": "addressName" },
+                        { "path": "contents", "label": "contents", "format": "raw" }
+                    ]
+                }
+            }
+        }
+    });
+
+    assert_eq!(descriptor(&domain, &Mail::default(), &hints), expected);
+}
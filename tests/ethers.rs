@@ -0,0 +1,98 @@
+#![cfg(feature = "ethers")]
+
+use eip_712_derive::ethers::{typed_data, Eip712Message};
+use eip_712_derive::*;
+use ethers_core::types::transaction::eip712::{EIP712Domain, Eip712};
+
+#[derive(Default)]
+struct Person {
+    name: String,
+    wallet: Address,
+}
+
+impl StructType for Person {
+    const TYPE_NAME: &'static str = "Person";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("name", &self.name);
+        visitor.visit("wallet", &self.wallet);
+    }
+}
+
+#[derive(Default)]
+struct Mail {
+    from: Person,
+    to: Person,
+    contents: String,
+    urgent: bool,
+    tags: Vec<String>,
+}
+
+impl StructType for Mail {
+    const TYPE_NAME: &'static str = "Mail";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("from", &self.from);
+        visitor.visit("to", &self.to);
+        visitor.visit("contents", &self.contents);
+        visitor.visit("urgent", &self.urgent);
+        visitor.visit("tags", &self.tags);
+    }
+}
+
+fn mail() -> Mail {
+    Mail {
+        from: Person {
+            name: "Cow".to_owned(),
+            wallet: Address([0xcd; 20]),
+        },
+        to: Person {
+            name: "Bob".to_owned(),
+            wallet: Address([0xbb; 20]),
+        },
+        contents: "Hello, Bob!".to_owned(),
+        urgent: true,
+        tags: vec!["greeting".to_owned()],
+    }
+}
+
+#[test]
+fn typed_data_hashes_the_same() {
+    let domain = Eip712Domain {
+        name: "Ether Mail".to_owned(),
+        version: "1".to_owned(),
+        chain_id: U256::from(1u64),
+        verifying_contract: Address([0xcc; 20]),
+        salt: [7; 32],
+    };
+    let mail = mail();
+    let typed_data = typed_data(&domain, &mail).unwrap();
+    assert_eq!(typed_data.primary_type, "Mail");
+    assert_eq!(
+        DomainSeparator::from(&typed_data.domain),
+        DomainSeparator::new(&domain)
+    );
+    assert_eq!(
+        typed_data.encode_eip712().unwrap(),
+        sign_hash(&DomainSeparator::new(&domain), &mail)
+    );
+}
+
+#[test]
+fn eip712_trait() {
+    let domain = EIP712Domain {
+        name: Some("Ether Mail".to_owned()),
+        chain_id: Some(1.into()),
+        ..Default::default()
+    };
+    let message = Eip712Message {
+        domain: domain.clone(),
+        message: mail(),
+    };
+    assert_eq!(
+        Eip712Message::<Mail>::type_hash().unwrap(),
+        type_hash(&message.message)
+    );
+    assert_eq!(
+        message.encode_eip712().unwrap(),
+        sign_hash(&DomainSeparator::from(&domain), &message.message)
+    );
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "fuzzing")]
+
+use eip_712_derive::fuzzing;
+
+// Smoke test the harnesses so that they stay runnable without cargo-fuzz.
+#[test]
+fn harnesses_accept_arbitrary_input() {
+    let inputs: &[&[u8]] = &[
+        b"",
+        &[0u8; 32],
+        &[0xffu8; 64],
+        b"\xf0\x28\x8c\xbc not utf-8",
+        &[0x1bu8; 65],
+        b"struct S { uint[ x; } struct T { mapping(",
+        br#"[{"inputs":[{"type":"tuple[]","components":[{"name":"a","type":"tuple"}]}]}]"#,
+        b"Mail(Person from,string contents)Person(string name,Mail[] sent)",
+        b"A(B b)B(A[] a,uint256 x)C(",
+        br#"{"types":{"EIP712Domain":[{"name":"a","type":"uint["}]},"primaryType":"EIP712Domain","domain":{"a":[]}}"#,
+    ];
+    for input in inputs {
+        fuzzing::hash_struct(input);
+        fuzzing::encode_type(input);
+        fuzzing::sign_typed(input);
+        fuzzing::recover(input);
+        fuzzing::verify_typed_json(input);
+        fuzzing::hash_typed_json(input);
+        fuzzing::codegen_solidity(input);
+        fuzzing::codegen_abi(input);
+    }
+}
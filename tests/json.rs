@@ -0,0 +1,456 @@
+#![cfg(feature = "json")]
+
+use eip_712_derive::*;
+use serde_json::json;
+
+const MAIL: &str = r#"{
+    "types": {
+        "EIP712Domain": [
+            { "name": "name", "type": "string" },
+            { "name": "version", "type": "string" },
+            { "name": "chainId", "type": "uint256" },
+            { "name": "verifyingContract", "type": "address" }
+        ],
+        "Person": [
+            { "name": "name", "type": "string" },
+            { "name": "wallet", "type": "address" }
+        ],
+        "Mail": [
+            { "name": "from", "type": "Person" },
+            { "name": "to", "type": "Person" },
+            { "name": "contents", "type": "string" }
+        ]
+    },
+    "primaryType": "Mail",
+    "domain": {
+        "name": "Ether Mail",
+        "version": "1",
+        "chainId": 1,
+        "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+    },
+    "message": {
+        "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+        "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+        "contents": "Hello, Bob!"
+    }
+}"#;
+
+const MAIL_SIGNATURE: &str = "0x4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b915621c";
+
+#[test]
+fn verify_spec_example() {
+    assert_eq!(
+        verify_typed_json(
+            MAIL,
+            MAIL_SIGNATURE,
+            "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"
+        ),
+        Ok(true)
+    );
+    assert_eq!(
+        verify_typed_json(
+            MAIL,
+            MAIL_SIGNATURE,
+            "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"
+        ),
+        Ok(false)
+    );
+}
+
+#[test]
+fn hash_spec_example() {
+    let hashes = hash_typed_json(MAIL).unwrap();
+    assert_eq!(
+        hex::encode(hashes.type_hash),
+        "a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2"
+    );
+    assert_eq!(
+        hex::encode(hashes.struct_hash),
+        "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+    );
+    assert_eq!(
+        hex::encode(hashes.domain_separator.as_bytes()),
+        "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+    );
+    assert_eq!(
+        hex::encode(hashes.digest),
+        "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+    );
+    assert!(matches!(
+        hash_typed_json("{}"),
+        Err(Error::InvalidTypedData(_))
+    ));
+}
+
+#[test]
+fn malformed_inputs() {
+    let wallet = "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826";
+    assert!(matches!(
+        verify_typed_json("{", MAIL_SIGNATURE, wallet),
+        Err(Error::InvalidTypedData(_))
+    ));
+    assert_eq!(
+        verify_typed_json(MAIL, "0x1234", wallet),
+        Err(Error::MalformedSignature)
+    );
+    assert!(matches!(
+        verify_typed_json(MAIL, MAIL_SIGNATURE, "0x1234"),
+        Err(Error::InvalidAddress(_))
+    ));
+
+    let mut missing = serde_json::from_str::<serde_json::Value>(MAIL).unwrap();
+    missing["message"]["to"]
+        .as_object_mut()
+        .unwrap()
+        .remove("wallet");
+    assert!(matches!(
+        verify_typed_json(&missing.to_string(), MAIL_SIGNATURE, wallet),
+        Err(Error::InvalidTypedData(_))
+    ));
+}
+
+fn typed_data(member_type: &str, value: serde_json::Value) -> String {
+    json!({
+        "types": {
+            "EIP712Domain": [{ "name": "name", "type": "string" }],
+            "Value": [{ "name": "value", "type": member_type }],
+        },
+        "primaryType": "Value",
+        "domain": { "name": "Test" },
+        "message": { "value": value },
+    })
+    .to_string()
+}
+
+#[test]
+fn integer_ranges() {
+    let wallet = "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826";
+    let accepted = [
+        ("uint8", json!(255)),
+        ("uint8", json!("0xff")),
+        ("int8", json!(-128)),
+        ("int8", json!("127")),
+        (
+            "uint256",
+            json!("115792089237316195423570985008687907853269984665640564039457584007913129639935"),
+        ),
+        (
+            "int256",
+            json!("-57896044618658097711785492504343953926634992332820282019728792003956564819968"),
+        ),
+    ];
+    for (member_type, value) in accepted.iter() {
+        let json = typed_data(member_type, value.clone());
+        assert_eq!(verify_typed_json(&json, MAIL_SIGNATURE, wallet), Ok(false));
+    }
+    let rejected = [
+        ("uint8", json!(256)),
+        ("uint8", json!(-1)),
+        ("int8", json!(128)),
+        ("int8", json!("-129")),
+        (
+            "uint256",
+            json!("115792089237316195423570985008687907853269984665640564039457584007913129639936"),
+        ),
+        ("uint7", json!(1)),
+        ("uint", json!(1)),
+        ("uint+8", json!(1)),
+        ("bytes33", json!("0x00")),
+        ("bytes+4", json!("0x00000000")),
+        ("uint8[+1]", json!([1])),
+    ];
+    for (member_type, value) in rejected.iter() {
+        let json = typed_data(member_type, value.clone());
+        assert!(
+            matches!(
+                verify_typed_json(&json, MAIL_SIGNATURE, wallet),
+                Err(Error::InvalidTypedData(_))
+            ),
+            "{} {}",
+            member_type,
+            value
+        );
+    }
+}
+
+struct Domain {
+    name: String,
+}
+impl StructType for Domain {
+    const TYPE_NAME: &'static str = "EIP712Domain";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("name", &self.name);
+    }
+}
+
+struct Order {
+    maker: Address,
+    amount: U256,
+    selector: Bytes4,
+    salt: Bytes32,
+    note: String,
+}
+impl StructType for Order {
+    const TYPE_NAME: &'static str = "Order";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("maker", &self.maker);
+        visitor.visit("amount", &self.amount);
+        visitor.visit("selector", &self.selector);
+        visitor.visit("salt", &self.salt);
+        visitor.visit("note", &self.note);
+    }
+}
+
+#[cfg(feature = "sign")]
+#[test]
+fn agrees_with_static_types() {
+    let key = SecretKey::from_bytes(&[7u8; 32]).unwrap();
+    let domain = Domain {
+        name: "Exchange".to_owned(),
+    };
+    let mut amount = U256::default();
+    amount.0[31] = 100;
+    let order = Order {
+        maker: Address([0x11; 20]),
+        amount,
+        selector: [0xa9, 0x05, 0x9c, 0xbb],
+        salt: [0x22; 32],
+        note: "gm".to_owned(),
+    };
+    let signature = sign_typed(&DomainSeparator::new(&domain), &order, &key).unwrap();
+    let signer = signature
+        .recover(&sign_hash(&DomainSeparator::new(&domain), &order))
+        .unwrap();
+
+    let json = json!({
+        "types": {
+            "EIP712Domain": [{ "name": "name", "type": "string" }],
+            "Order": [
+                { "name": "maker", "type": "address" },
+                { "name": "amount", "type": "uint256" },
+                { "name": "selector", "type": "bytes4" },
+                { "name": "salt", "type": "bytes32" },
+                { "name": "note", "type": "string" }
+            ],
+        },
+        "primaryType": "Order",
+        "domain": { "name": "Exchange" },
+        "message": {
+            "maker": format!("0x{}", "11".repeat(20)),
+            "amount": "100",
+            "selector": "0xa9059cbb",
+            "salt": format!("0x{}", "22".repeat(32)),
+            "note": "gm",
+        },
+    });
+    assert_eq!(
+        verify_typed_json(
+            &json.to_string(),
+            &format!("0x{}", hex::encode(signature.to_bytes())),
+            &format!("0x{}", hex::encode(signer.0)),
+        ),
+        Ok(true)
+    );
+}
+
+// bytes1 to bytes31 are padded at the end, and address at the start, as by
+// eth_signTypedData_v4. Versions before 0.5 padded bytesN at the start.
+#[test]
+fn bytes_n_spec_vector() {
+    let json = json!({
+        "types": {
+            "EIP712Domain": [{ "name": "name", "type": "string" }],
+            "Order": [
+                { "name": "maker", "type": "address" },
+                { "name": "amount", "type": "uint256" },
+                { "name": "selector", "type": "bytes4" },
+                { "name": "salt", "type": "bytes32" },
+                { "name": "note", "type": "string" }
+            ],
+        },
+        "primaryType": "Order",
+        "domain": { "name": "Exchange" },
+        "message": {
+            "maker": format!("0x{}", "11".repeat(20)),
+            "amount": "100",
+            "selector": "0xa9059cbb",
+            "salt": format!("0x{}", "22".repeat(32)),
+            "note": "gm",
+        },
+    });
+    let struct_hash = "f19cca24dbfb56c6b0c43fbfac98ff1036e05bff434f370c5569c046b66b6b33";
+    let digest = "e61378a2ef2a0b40a34429fb89c2959e6d4d68cea3834b037456d92abd578172";
+
+    let hashes = hash_typed_json(&json.to_string()).unwrap();
+    assert_eq!(hex::encode(hashes.struct_hash), struct_hash);
+    assert_eq!(hex::encode(hashes.digest), digest);
+
+    let selector: Bytes4 = [0xa9, 0x05, 0x9c, 0xbb];
+    assert_eq!(
+        hex::encode(selector.encode_data()),
+        format!("a9059cbb{}", "00".repeat(28))
+    );
+    assert_eq!(
+        hex::encode(Address([0x11; 20]).encode_data()),
+        format!("{}{}", "00".repeat(12), "11".repeat(20))
+    );
+    let mut amount = U256::default();
+    amount.0[31] = 100;
+    let order = Order {
+        maker: Address([0x11; 20]),
+        amount,
+        selector,
+        salt: [0x22; 32],
+        note: "gm".to_owned(),
+    };
+    assert_eq!(hex::encode(hash_struct(&order)), struct_hash);
+}
+
+#[test]
+fn typed_data_json_round_trip() {
+    let domain = Domain {
+        name: "Exchange".to_owned(),
+    };
+    let order = Order {
+        maker: Address([0x11; 20]),
+        amount: U256::from(100u64),
+        selector: [0xa9, 0x05, 0x9c, 0xbb],
+        salt: [0x22; 32],
+        note: "gm".to_owned(),
+    };
+    let json = typed_data_json(&domain, &order).unwrap();
+    assert_eq!(json["primaryType"], "Order");
+    assert_eq!(
+        json["types"]["Order"][2],
+        json!({ "name": "selector", "type": "bytes4" })
+    );
+    assert_eq!(json["message"]["selector"], "0xa9059cbb");
+
+    let hashes = hash_typed_json(&json.to_string()).unwrap();
+    assert_eq!(
+        hashes,
+        encode_eip712(&DomainSeparator::new(&domain), &order)
+    );
+}
+
+#[test]
+fn typed_data_json_values() {
+    struct Ledger {
+        balance: i64,
+        open: bool,
+        owners: Vec<Address>,
+        limits: [u64; 2],
+        memo: Vec<u8>,
+        order: Order,
+    }
+    impl StructType for Ledger {
+        const TYPE_NAME: &'static str = "Ledger";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("balance", &self.balance);
+            visitor.visit("open", &self.open);
+            visitor.visit("owners", &self.owners);
+            visitor.visit("limits", &self.limits);
+            visitor.visit("memo", &self.memo);
+            visitor.visit("order", &self.order);
+        }
+    }
+    let domain = Domain {
+        name: "Exchange".to_owned(),
+    };
+    let ledger = Ledger {
+        balance: -300,
+        open: true,
+        owners: vec![Address([0xab; 20])],
+        limits: [1, u64::MAX],
+        memo: vec![1, 2, 3],
+        order: Order {
+            maker: Address([0x11; 20]),
+            amount: U256::from(100u64),
+            selector: [0xa9, 0x05, 0x9c, 0xbb],
+            salt: [0x22; 32],
+            note: "\"quoted\"".to_owned(),
+        },
+    };
+    let json = typed_data_json(&domain, &ledger).unwrap();
+    assert_eq!(
+        json["message"],
+        json!({
+            "balance": "-300",
+            "open": true,
+            "owners": ["0xABaBaBaBABabABabAbAbABAbABabababaBaBABaB"],
+            "limits": ["1", "18446744073709551615"],
+            "memo": "0x010203",
+            "order": {
+                "maker": "0x1111111111111111111111111111111111111111",
+                "amount": "100",
+                "selector": "0xa9059cbb",
+                "salt": format!("0x{}", "22".repeat(32)),
+                "note": "\"quoted\"",
+            },
+        })
+    );
+    assert_eq!(
+        hash_typed_json(&json.to_string()).unwrap(),
+        encode_eip712(&DomainSeparator::new(&domain), &ledger)
+    );
+}
+
+#[test]
+fn typed_data_json_rejects_hashed_values() {
+    struct Upload {
+        data: HashedBytes,
+    }
+    impl StructType for Upload {
+        const TYPE_NAME: &'static str = "Upload";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("data", &self.data);
+        }
+    }
+    let domain = Domain {
+        name: "Storage".to_owned(),
+    };
+    let upload = Upload {
+        data: HashedBytes::from_reader(&b"contents"[..]).unwrap(),
+    };
+    assert!(matches!(
+        typed_data_json(&domain, &upload),
+        Err(Error::InvalidTypedData(_))
+    ));
+}
+
+#[test]
+fn typed_data_round_trip() {
+    let mail = dynamic::TypedData::from_json(MAIL).unwrap();
+    assert_eq!(mail.primary_type, "Mail");
+    assert_eq!(
+        hex::encode(mail.digest().unwrap()),
+        "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+    );
+    let json = mail.to_json();
+    assert_eq!(
+        json["domain"]["verifyingContract"],
+        "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+    );
+    assert_eq!(json["domain"]["chainId"], "1");
+    assert_eq!(dynamic::TypedData::from_json_value(&json).unwrap(), mail);
+
+    let negative = typed_data("int16", json!(-300));
+    let negative = dynamic::TypedData::from_json(&negative).unwrap();
+    assert_eq!(negative.to_json()["message"]["value"], "-300");
+}
+
+#[test]
+fn domain_separator_of_partial_domain() {
+    let domain = Eip712Domain::builder()
+        .name("Ether Mail")
+        .chain_id(1u64)
+        .build();
+    assert_eq!(
+        domain_separator_json(r#"{ "chainId": 1, "name": "Ether Mail" }"#),
+        Ok(DomainSeparator::new(&domain))
+    );
+    assert!(matches!(
+        domain_separator_json(r#"{ "chain": 1 }"#),
+        Err(Error::InvalidTypedData(_))
+    ));
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "json-schema")]
+
+use eip_712_derive::json_schema::json_schema;
+use eip_712_derive::*;
+use serde_json::json;
+
+#[derive(Default)]
+struct Person {
+    name: String,
+    wallet: Address,
+}
+
+impl StructType for Person {
+    const TYPE_NAME: &'static str = "Person";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("name", &self.name);
+        visitor.visit("wallet", &self.wallet);
+    }
+}
+
+#[derive(Default)]
+struct Payment {
+    to: Person,
+    amount: U256,
+    memo: Bytes4,
+}
+
+impl StructType for Payment {
+    const TYPE_NAME: &'static str = "Payment";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("to", &self.to);
+        visitor.visit("amount", &self.amount);
+        visitor.visit("memo", &self.memo);
+    }
+}
+
+#[test]
+fn payment_schema() {
+    let schema = json_schema(&Payment::default());
+    assert_eq!(schema["$ref"], "#/$defs/Payment");
+
+    let payment = &schema["$defs"]["Payment"];
+    assert_eq!(payment["required"], json!(["to", "amount", "memo"]));
+    assert_eq!(payment["additionalProperties"], false);
+    assert_eq!(
+        payment["properties"]["to"],
+        json!({ "$ref": "#/$defs/Person" })
+    );
+    assert_eq!(
+        payment["properties"]["memo"],
+        json!({ "type": "string", "pattern": "^0x[0-9a-fA-F]{8}$" })
+    );
+    assert_eq!(
+        payment["properties"]["amount"],
+        json!({ "anyOf": [
+            { "type": "integer", "minimum": 0 },
+            { "type": "string", "pattern": "^([0-9]{1,78}|0x[0-9a-fA-F]{1,64})$" },
+        ] })
+    );
+    assert_eq!(
+        schema["$defs"]["Person"]["properties"]["wallet"]["pattern"],
+        "^0x[0-9a-fA-F]{40}$"
+    );
+}
@@ -0,0 +1,160 @@
+#![cfg(feature = "keychain")]
+
+use eip_712_derive::*;
+use keyring::credential::{Credential, CredentialApi, CredentialBuilder, CredentialBuilderApi};
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+// The credential store is global, so tests which set it take turns
+static STORE: Mutex<()> = Mutex::new(());
+
+fn use_store(builder: Box<CredentialBuilder>) -> MutexGuard<'static, ()> {
+    let guard = STORE.lock().unwrap_or_else(|e| e.into_inner());
+    keyring::set_default_credential_builder(builder);
+    guard
+}
+
+type Secrets = Arc<Mutex<HashMap<(String, String), Vec<u8>>>>;
+
+/// A credential store in memory, shared by every entry it builds
+#[derive(Debug, Default)]
+struct MemoryStore(Secrets);
+
+#[derive(Debug)]
+struct MemoryCredential {
+    secrets: Secrets,
+    id: (String, String),
+}
+
+impl CredentialBuilderApi for MemoryStore {
+    fn build(
+        &self,
+        _target: Option<&str>,
+        service: &str,
+        user: &str,
+    ) -> keyring::Result<Box<Credential>> {
+        Ok(Box::new(MemoryCredential {
+            secrets: self.0.clone(),
+            id: (service.to_owned(), user.to_owned()),
+        }))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl CredentialApi for MemoryCredential {
+    fn set_secret(&self, secret: &[u8]) -> keyring::Result<()> {
+        self.secrets
+            .lock()
+            .unwrap()
+            .insert(self.id.clone(), secret.to_vec());
+        Ok(())
+    }
+
+    fn get_secret(&self) -> keyring::Result<Vec<u8>> {
+        let secrets = self.secrets.lock().unwrap();
+        secrets
+            .get(&self.id)
+            .cloned()
+            .ok_or(keyring::Error::NoEntry)
+    }
+
+    fn delete_credential(&self) -> keyring::Result<()> {
+        let mut secrets = self.secrets.lock().unwrap();
+        secrets
+            .remove(&self.id)
+            .map(drop)
+            .ok_or(keyring::Error::NoEntry)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[test]
+fn store_load_delete() {
+    let store = MemoryStore::default();
+    let secrets = store.0.clone();
+    let _store = use_store(Box::new(store));
+    let service = "eip-712-derive-test";
+    let key = SecretKey::from_bytes(&[7u8; 32]).unwrap();
+
+    assert_eq!(keychain::load(service, "alice"), Ok(None));
+    keychain::store(service, "alice", &key).unwrap();
+    assert_eq!(keychain::load(service, "alice"), Ok(Some(key.clone())));
+    assert_eq!(keychain::load(service, "bob"), Ok(None));
+
+    // Storing again replaces the key
+    let other = SecretKey::from_bytes(&[8u8; 32]).unwrap();
+    keychain::store(service, "alice", &other).unwrap();
+    assert_eq!(keychain::load(service, "alice"), Ok(Some(other)));
+
+    assert_eq!(keychain::delete(service, "alice"), Ok(true));
+    assert_eq!(keychain::load(service, "alice"), Ok(None));
+    assert_eq!(keychain::delete(service, "alice"), Ok(false));
+
+    // Entries written by something else
+    let id = (service.to_owned(), "carol".to_owned());
+    secrets.lock().unwrap().insert(id.clone(), vec![7u8; 31]);
+    assert_eq!(
+        keychain::load(service, "carol"),
+        Err(Error::InvalidPrivateKey)
+    );
+    secrets.lock().unwrap().insert(id, vec![0u8; 32]);
+    assert_eq!(
+        keychain::load(service, "carol"),
+        Err(Error::InvalidPrivateKey)
+    );
+}
+
+/// A credential store which refuses access, like a locked one
+#[derive(Debug)]
+struct LockedStore;
+
+impl CredentialBuilderApi for LockedStore {
+    fn build(
+        &self,
+        _target: Option<&str>,
+        _service: &str,
+        _user: &str,
+    ) -> keyring::Result<Box<Credential>> {
+        Err(keyring::Error::NoStorageAccess("locked".into()))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[test]
+fn store_errors() {
+    let _store = use_store(Box::new(LockedStore));
+    let key = SecretKey::from_bytes(&[7u8; 32]).unwrap();
+    let locked = |result: Result<_, Error>| match result {
+        Err(Error::Keychain(message)) => assert!(message.contains("locked")),
+        _ => panic!("Expected a keychain error"),
+    };
+    locked(keychain::store("eip-712-derive-test", "alice", &key));
+    locked(keychain::load("eip-712-derive-test", "alice").map(drop));
+    locked(keychain::delete("eip-712-derive-test", "alice").map(drop));
+}
+
+// Needs an unlocked credential store, which CI machines typically lack.
+#[test]
+#[ignore]
+fn platform_store_load_delete() {
+    let _store = use_store(keyring::default::default_credential_builder());
+    let service = "eip-712-derive-test";
+    let account = "store_load_delete";
+    let key = SecretKey::from_bytes(&[7u8; 32]).unwrap();
+
+    keychain::store(service, account, &key).unwrap();
+    assert_eq!(keychain::load(service, account), Ok(Some(key)));
+    assert_eq!(keychain::delete(service, account), Ok(true));
+    assert_eq!(keychain::load(service, account), Ok(None));
+    assert_eq!(keychain::delete(service, account), Ok(false));
+}
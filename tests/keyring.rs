@@ -0,0 +1,87 @@
+#![cfg(feature = "sign")]
+
+use eip_712_derive::*;
+
+fn domain() -> Eip712Domain {
+    Eip712Domain {
+        name: "Keyring".to_owned(),
+        version: "1".to_owned(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn signs_as_each_key() {
+    let key = |byte| SecretKey::from_bytes(&[byte; 32]).unwrap();
+    let mut keyring = Keyring::new();
+    let alice = keyring.insert(key(1));
+    let bob = keyring.insert(key(2));
+    assert_eq!(keyring.len(), 2);
+    assert_eq!(keyring.insert(key(1)), alice);
+    assert_eq!(keyring.len(), 2);
+
+    let domain_separator = DomainSeparator::new(&domain());
+    let digest = sign_hash(&domain_separator, &domain());
+    for address in [alice, bob] {
+        let signature = keyring
+            .sign_typed_as(&address, &domain_separator, &domain())
+            .unwrap();
+        assert_eq!(signature.recover(&digest), Ok(address));
+    }
+}
+
+#[test]
+fn unknown_signer() {
+    let mut keyring = Keyring::new();
+    let alice = keyring.insert(SecretKey::from_bytes(&[1u8; 32]).unwrap());
+    assert!(keyring.remove(&alice));
+    assert!(!keyring.remove(&alice));
+    assert_eq!(
+        keyring.sign_typed_as(&alice, &DomainSeparator::new(&domain()), &domain()),
+        Err(Error::UnknownSigner(alice))
+    );
+}
+
+#[test]
+fn secret_key() {
+    let key = SecretKey::from_bytes(&[1u8; 32]).unwrap();
+    assert_eq!(
+        Signer::address(&key),
+        signer_address(&PrivateKey::from([1u8; 32])).unwrap()
+    );
+    assert!(!format!("{:?}", key).contains("0101"));
+
+    let domain_separator = DomainSeparator::new(&domain());
+    let signature = key.sign_typed(&domain_separator, &domain()).unwrap();
+    assert_eq!(
+        signature.recover(&sign_hash(&domain_separator, &domain())),
+        Ok(key.address())
+    );
+    assert_eq!(
+        SecretKey::from_bytes(&[0u8; 32]),
+        Err(Error::InvalidPrivateKey)
+    );
+}
+
+#[test]
+fn private_key() {
+    let hex = "0x27a9023c854932b5a4f4ba8a7df6c71244c8f395a7bc7626343fcda2181737b0";
+    let key = PrivateKey::from_hex(hex).unwrap();
+    assert_eq!(PrivateKey::from_hex(&hex[2..]), Ok(key.clone()));
+    assert_eq!(format!("{:?}", key), "PrivateKey(<redacted>)");
+    assert_eq!(SecretKey::from_hex(hex).unwrap().as_bytes(), key.as_bytes());
+    assert_eq!(
+        PrivateKey::from_fn(|bytes| bytes.copy_from_slice(key.as_bytes())),
+        key
+    );
+
+    // Errors do not echo the key
+    assert_eq!(
+        PrivateKey::from_hex(&hex[..65]),
+        Err(Error::InvalidPrivateKey)
+    );
+    assert_eq!(
+        PrivateKey::from_hex(&hex.replace('7', "g")),
+        Err(Error::InvalidPrivateKey)
+    );
+}
@@ -0,0 +1,117 @@
+#![cfg(feature = "ledger")]
+
+use eip_712_derive::ledger::{LedgerSigner, HARDENED};
+use eip_712_derive::*;
+use k256::ecdsa::SigningKey;
+use ledger_transport::{async_trait, APDUAnswer, APDUCommand, Exchange};
+use std::future::Future;
+use std::ops::Deref;
+use std::pin::pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll, Waker};
+
+fn block_on<F: Future>(f: F) -> F::Output {
+    let mut f = pin!(f);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = f.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+const PATH: [u32; 5] = [44 + HARDENED, 60 + HARDENED, HARDENED, 0, 0];
+
+/// Answers like the Ethereum app, recording every APDU
+struct Device {
+    key: SigningKey,
+    status: u16,
+    commands: Mutex<Vec<Vec<u8>>>,
+}
+
+impl Device {
+    fn new(status: u16) -> Self {
+        Self {
+            key: SigningKey::from_bytes(&keccak_hash::keccak("cow").0.into()).unwrap(),
+            status,
+            commands: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl Exchange for Device {
+    type Error = String;
+    type AnswerType = Vec<u8>;
+
+    async fn exchange<I>(&self, command: &APDUCommand<I>) -> Result<APDUAnswer<Vec<u8>>, String>
+    where
+        I: Deref<Target = [u8]> + Send + Sync,
+    {
+        self.commands.lock().unwrap().push(command.serialize());
+        let path_len = 1 + 4 * command.data[0] as usize;
+        let mut response = match command.ins {
+            0x02 => {
+                let public_key = self.key.verifying_key().to_encoded_point(false);
+                let mut response = vec![65];
+                response.extend_from_slice(public_key.as_bytes());
+                response.push(40);
+                response.extend_from_slice(&[b'0'; 40]);
+                response
+            }
+            0x0c => {
+                let mut message = b"\x19\x01".to_vec();
+                message.extend_from_slice(&command.data[path_len..]);
+                let digest = keccak_hash::keccak(message).0;
+                let (signature, recovery_id) = self.key.sign_prehash_recoverable(&digest).unwrap();
+                let mut response = vec![27 + recovery_id.to_byte()];
+                response.extend_from_slice(&signature.to_bytes());
+                response
+            }
+            _ => return Err("unexpected instruction".to_owned()),
+        };
+        if self.status != 0x9000 && command.ins == 0x0c {
+            response.clear();
+        }
+        let status = if command.ins == 0x0c {
+            self.status
+        } else {
+            0x9000
+        };
+        response.extend_from_slice(&status.to_be_bytes());
+        Ok(APDUAnswer::from_answer(response).unwrap())
+    }
+}
+
+#[test]
+fn signs_hashes() {
+    let signer = block_on(LedgerSigner::new(Device::new(0x9000), &PATH)).unwrap();
+    let cow = SecretKey::from_bytes(&keccak_hash::keccak("cow").0).unwrap();
+    assert_eq!(signer.address(), cow.address());
+
+    let domain = Eip712Domain::default();
+    let domain_separator = DomainSeparator::new(&domain);
+    let signature = block_on(signer.sign_typed_async(&domain_separator, &domain)).unwrap();
+    assert_eq!(
+        signature,
+        sign_typed(&domain_separator, &domain, &cow).unwrap()
+    );
+
+    let device = signer.into_transport();
+    let commands = device.commands.lock().unwrap();
+    let sign = &commands[1];
+    assert_eq!(sign[..5], [0xe0, 0x0c, 0x00, 0x00, 1 + 4 * 5 + 64]);
+    assert_eq!(sign[5], 5);
+    assert_eq!(sign[6..10], (44 + HARDENED).to_be_bytes());
+    assert_eq!(&sign[26..58], domain_separator.as_bytes());
+    assert_eq!(sign[58..], hash_struct(&domain));
+}
+
+#[test]
+fn rejection() {
+    let signer = block_on(LedgerSigner::new(Device::new(0x6985), &PATH)).unwrap();
+    let domain = Eip712Domain::default();
+    let result = block_on(signer.sign_typed_async(&DomainSeparator::new(&domain), &domain));
+    assert_eq!(result, Err(Error::Rejected));
+    assert!(!Error::Rejected.is_transient());
+}
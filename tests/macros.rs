@@ -0,0 +1,69 @@
+use eip_712_derive::*;
+
+eip712_struct! {
+    #[derive(Clone, Default)]
+    pub Person {
+        pub name: String,
+        pub wallet: Address,
+    }
+
+    Mail {
+        from: Person,
+        to: Person,
+        contents: String,
+    }
+
+    Tagged {
+        r#type: U256,
+        tags: Vec<String>,
+    }
+}
+
+#[test]
+fn declared_structs() {
+    let person = |name: &str, byte| Person {
+        name: name.to_owned(),
+        wallet: Address([byte; 20]),
+    };
+    let mail = Mail {
+        from: person("Cow", 0xcd),
+        to: person("Bob", 0xbb).clone(),
+        contents: "Hello, Bob!".to_owned(),
+    };
+    assert_eq!(
+        encode_type(&mail),
+        "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+    );
+    let tagged = Tagged {
+        r#type: U256::default(),
+        tags: Vec::new(),
+    };
+    assert_eq!(encode_type(&tagged), "Tagged(uint256 type,string[] tags)");
+    assert_eq!(type_hash_of::<Mail>(), type_hash(&mail));
+    assert_eq!(encode_type_of::<Tagged>(), encode_type(&tagged));
+}
+
+const CONTRACT: Address = address!("0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC");
+
+#[test]
+fn literals() {
+    assert_eq!(CONTRACT, Address([0xcc; 20]));
+    assert_eq!(
+        address!("bBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"),
+        Address([0xbb; 20])
+    );
+    assert_eq!(u256!("0"), U256::ZERO);
+    assert_eq!(
+        u256!("1000000000000000000"),
+        U256::from(1_000_000_000_000_000_000u64)
+    );
+    assert_eq!(u256!("0xff"), U256::from(255u64));
+    assert_eq!(
+        u256!("115792089237316195423570985008687907853269984665640564039457584007913129639935"),
+        U256::MAX
+    );
+    assert_eq!(
+        bytes32!("0x0101010101010101010101010101010101010101010101010101010101010101"),
+        [1u8; 32]
+    );
+}
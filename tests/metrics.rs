@@ -0,0 +1,98 @@
+use eip_712_derive::metrics::{set_metrics, Metrics};
+use eip_712_derive::*;
+use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+use std::sync::Arc;
+use std::time::Duration;
+
+// Metrics are process-wide, so everything is kept in a single test to avoid
+// interference between tests running in parallel.
+
+#[derive(Default)]
+struct Counts {
+    signs: AtomicUsize,
+    verifies: AtomicUsize,
+    hashes: AtomicUsize,
+    hashed_bytes: AtomicUsize,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+struct Recorder(Arc<Counts>);
+
+impl Metrics for Recorder {
+    fn sign(&self, _duration: Duration) {
+        self.0.signs.fetch_add(1, SeqCst);
+    }
+    fn verify(&self, _duration: Duration) {
+        self.0.verifies.fetch_add(1, SeqCst);
+    }
+    fn hash_struct(&self, encoded_len: usize, _duration: Duration) {
+        self.0.hashes.fetch_add(1, SeqCst);
+        self.0.hashed_bytes.fetch_add(encoded_len, SeqCst);
+    }
+    fn type_hash_cache(&self, hit: bool) {
+        if hit {
+            self.0.hits.fetch_add(1, SeqCst);
+        } else {
+            self.0.misses.fetch_add(1, SeqCst);
+        }
+    }
+}
+
+struct Ping {
+    sequence: U256,
+}
+impl StructType for Ping {
+    const TYPE_NAME: &'static str = "Ping";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("sequence", &self.sequence);
+    }
+}
+
+#[test]
+fn reports_metrics() {
+    let counts = Arc::new(Counts::default());
+    set_metrics(Recorder(counts.clone()));
+
+    let domain_separator = DomainSeparator::from_bytes(&[1u8; 32]);
+    let ping = Ping {
+        sequence: U256::default(),
+    };
+    hash_struct(&ping);
+    sign_hash(&domain_separator, &ping);
+
+    assert_eq!(counts.hashes.load(SeqCst), 2);
+    assert_eq!(counts.hashed_bytes.load(SeqCst), 128);
+    assert_eq!(counts.misses.load(SeqCst), 1);
+    assert_eq!(counts.hits.load(SeqCst), 1);
+
+    // encodeType is only built once per type, including through pointers
+    for sequence in 0..1000u64 {
+        type_hash(&Ping {
+            sequence: U256::from(sequence),
+        });
+    }
+    type_hash(&Box::new(Ping {
+        sequence: U256::default(),
+    }));
+    assert_eq!(counts.misses.load(SeqCst), 1);
+    assert_eq!(counts.hits.load(SeqCst), 1002);
+
+    #[cfg(feature = "sign")]
+    {
+        let key = SecretKey::from_bytes(&[1u8; 32]).unwrap();
+        sign_typed(&domain_separator, &ping, &key).unwrap();
+        assert_eq!(counts.signs.load(SeqCst), 1);
+    }
+
+    // Including failed verifications
+    #[cfg(feature = "verify")]
+    {
+        let digest = [1u8; 32];
+        let valid = Signature::new(&[1u8; 64], RecoveryId::Parity0);
+        let malformed = Signature::new(&[0xff; 64], RecoveryId::Parity0);
+        assert!(valid.recover(&digest).is_ok());
+        assert_eq!(malformed.recover(&digest), Err(Error::MalformedSignature));
+        assert_eq!(counts.verifies.load(SeqCst), 2);
+    }
+}
@@ -0,0 +1,244 @@
+#![cfg(feature = "async")]
+
+use eip_712_derive::middleware::{CircuitBreaker, Retry, Timeout};
+use eip_712_derive::*;
+use std::future::{pending, ready, Future};
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+fn block_on<F: Future>(f: F) -> F::Output {
+    let mut f = pin!(f);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = f.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+fn no_sleep(_: Duration) -> impl Future<Output = ()> {
+    ready(())
+}
+
+/// Fails with a transient error a number of times before succeeding,
+/// recording the digest of every attempt.
+struct Flaky {
+    failures: Mutex<u32>,
+    digests: Mutex<Vec<Bytes32>>,
+}
+
+impl Flaky {
+    fn new(failures: u32) -> Self {
+        Self {
+            failures: Mutex::new(failures),
+            digests: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl AsyncSigner for Flaky {
+    fn address(&self) -> Address {
+        Address::default()
+    }
+    async fn sign(&self, request: &SigningRequest) -> Result<Signature, Error> {
+        self.digests.lock().unwrap().push(*request.digest());
+        let mut failures = self.failures.lock().unwrap();
+        if *failures > 0 {
+            *failures -= 1;
+            return Err(Error::Signer("unavailable".to_owned()));
+        }
+        Ok(Signature::new(&[1u8; 64], RecoveryId::Parity0))
+    }
+}
+
+struct Hang;
+
+impl AsyncSigner for Hang {
+    fn address(&self) -> Address {
+        Address::default()
+    }
+    async fn sign(&self, _request: &SigningRequest) -> Result<Signature, Error> {
+        pending().await
+    }
+}
+
+fn request() -> SigningRequest {
+    let domain = Eip712Domain::default();
+    SigningRequest::new(&DomainSeparator::new(&domain), &domain)
+}
+
+#[test]
+fn sign_typed_async() {
+    let flaky = Flaky::new(0);
+    let domain = Eip712Domain::default();
+    let domain_separator = DomainSeparator::new(&domain);
+    assert!(block_on(flaky.sign_typed_async(&domain_separator, &domain)).is_ok());
+    assert_eq!(*flaky.digests.lock().unwrap(), vec![*request().digest()]);
+}
+
+/// With the bounds of tokio::spawn
+fn spawn<F: Future + Send + 'static>(f: F) -> F::Output {
+    block_on(f)
+}
+
+async fn sign_shared<S: AsyncSigner>(signer: Arc<S>) -> Result<Signature, Error> {
+    signer.sign(&request()).await
+}
+
+#[test]
+fn futures_are_send() {
+    let signer = CircuitBreaker::new(
+        Retry::new(
+            Timeout::new(Flaky::new(1), Duration::from_secs(1), no_sleep),
+            3,
+            Duration::from_millis(1),
+            no_sleep,
+        ),
+        5,
+        Duration::from_secs(30),
+    );
+    assert!(spawn(sign_shared(Arc::new(signer))).is_ok());
+}
+
+#[test]
+fn retry_uses_identical_digest() {
+    let flaky = Flaky::new(2);
+    let signer = Retry::new(&flaky, 3, Duration::from_millis(1), no_sleep);
+    let request = request();
+    assert!(block_on(signer.sign(&request)).is_ok());
+
+    let digests = flaky.digests.lock().unwrap();
+    assert_eq!(digests.len(), 3);
+    assert!(digests.iter().all(|d| d == request.digest()));
+}
+
+#[test]
+fn retry_gives_up() {
+    let signer = Retry::new(Flaky::new(5), 2, Duration::from_millis(1), no_sleep);
+    assert!(matches!(
+        block_on(signer.sign(&request())),
+        Err(Error::Signer(_))
+    ));
+}
+
+#[test]
+fn retry_caps_backoff() {
+    let delays = Mutex::new(Vec::new());
+    let sleep = |delay| {
+        delays.lock().unwrap().push(delay);
+        ready(())
+    };
+    let signer = Retry::new(Flaky::new(5), 5, Duration::from_millis(1), sleep)
+        .max_backoff(Duration::from_millis(4));
+    assert!(block_on(signer.sign(&request())).is_ok());
+    assert_eq!(
+        *delays.lock().unwrap(),
+        [1, 2, 4, 4, 4].map(Duration::from_millis)
+    );
+
+    // Doubling saturates instead of overflowing
+    let signer = Retry::new(Flaky::new(100), 100, Duration::MAX / 4, no_sleep);
+    assert!(block_on(signer.sign(&request())).is_ok());
+}
+
+#[test]
+fn timeout() {
+    let signer = Timeout::new(Hang, Duration::from_secs(1), no_sleep);
+    assert_eq!(block_on(signer.sign(&request())), Err(Error::Timeout));
+}
+
+#[test]
+fn circuit_breaker_opens() {
+    let flaky = Flaky::new(3);
+    let signer = CircuitBreaker::new(&flaky, 2, Duration::from_secs(60));
+    let request = request();
+    assert!(block_on(signer.sign(&request)).is_err());
+    assert!(block_on(signer.sign(&request)).is_err());
+    assert_eq!(block_on(signer.sign(&request)), Err(Error::CircuitOpen));
+    // The inner signer was not called while open
+    assert_eq!(flaky.digests.lock().unwrap().len(), 2);
+}
+
+/// Fails with a transient error once, then never completes
+struct FailThenHang(Mutex<bool>);
+
+impl AsyncSigner for FailThenHang {
+    fn address(&self) -> Address {
+        Address::default()
+    }
+    async fn sign(&self, _request: &SigningRequest) -> Result<Signature, Error> {
+        if !std::mem::replace(&mut *self.0.lock().unwrap(), true) {
+            return Err(Error::Signer("unavailable".to_owned()));
+        }
+        pending().await
+    }
+}
+
+#[test]
+fn circuit_breaker_admits_one_probe() {
+    let signer = CircuitBreaker::new(FailThenHang(Mutex::new(false)), 1, Duration::ZERO);
+    let request = request();
+    assert!(matches!(
+        block_on(signer.sign(&request)),
+        Err(Error::Signer(_))
+    ));
+
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut probe = Box::pin(signer.sign(&request));
+    assert!(probe.as_mut().poll(&mut cx).is_pending());
+    // Others fail fast while the probe is in flight
+    assert_eq!(block_on(signer.sign(&request)), Err(Error::CircuitOpen));
+
+    // A dropped probe lets the next attempt through
+    drop(probe);
+    let mut probe = pin!(signer.sign(&request));
+    assert!(probe.as_mut().poll(&mut cx).is_pending());
+}
+
+/// Fails with a transient error after a while, counting calls
+struct SlowFailure(Arc<AtomicUsize>);
+
+impl AsyncSigner for SlowFailure {
+    fn address(&self) -> Address {
+        Address::default()
+    }
+    async fn sign(&self, _request: &SigningRequest) -> Result<Signature, Error> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(2));
+        Err(Error::Signer("unavailable".to_owned()))
+    }
+}
+
+#[test]
+fn circuit_breaker_concurrent_probes() {
+    let cooldown = Duration::from_millis(100);
+    for _ in 0..20 {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let signer = CircuitBreaker::new(SlowFailure(calls.clone()), 1, cooldown);
+        let request = request();
+        assert!(block_on(signer.sign(&request)).is_err());
+        std::thread::sleep(cooldown);
+
+        // Half-open: attempts from many threads until the trial fails and
+        // the circuit reopens
+        let done = AtomicBool::new(false);
+        std::thread::scope(|s| {
+            for _ in 0..16 {
+                s.spawn(|| {
+                    while !done.load(Ordering::SeqCst) {
+                        let _ = block_on(signer.sign(&request));
+                    }
+                });
+            }
+            while calls.load(Ordering::SeqCst) < 2 {
+                std::thread::yield_now();
+            }
+            std::thread::sleep(Duration::from_millis(10));
+            done.store(true, Ordering::SeqCst);
+        });
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
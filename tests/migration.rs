@@ -0,0 +1,57 @@
+use eip_712_derive::summary::SummaryValue;
+use eip_712_derive::*;
+
+fn domain(version: &str) -> Eip712Domain {
+    Eip712Domain {
+        name: "Example".to_owned(),
+        version: version.to_owned(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn version_bump() {
+    let migration = DomainMigration::new(&domain("1"), &domain("2"));
+    assert!(!migration.signatures_remain_valid());
+    assert_eq!(
+        migration.changes(),
+        &[DomainChange {
+            member: "version".to_owned(),
+            old: Some(SummaryValue::Text("1".to_owned())),
+            new: Some(SummaryValue::Text("2".to_owned())),
+        }]
+    );
+
+    let message = domain("message");
+    let digests = migration.sign_hashes(&message);
+    assert_eq!(
+        digests.old,
+        sign_hash(migration.domain_separator(DomainEra::Old), &message)
+    );
+    assert_eq!(
+        digests.new,
+        sign_hash(&DomainSeparator::new(&domain("2")), &message)
+    );
+
+    let unchanged = DomainMigration::new(&domain("1"), &domain("1"));
+    assert!(unchanged.signatures_remain_valid());
+    assert!(unchanged.changes().is_empty());
+}
+
+#[cfg(feature = "sign")]
+#[test]
+fn verify_under_either_domain() {
+    let key = SecretKey::from_bytes(&[1u8; 32]).unwrap();
+    let signer = key.address();
+    let migration = DomainMigration::new(&domain("1"), &domain("2"));
+    let message = domain("message");
+
+    for era in [DomainEra::Old, DomainEra::New] {
+        let signature = sign_typed(migration.domain_separator(era), &message, &key).unwrap();
+        assert_eq!(migration.verify(&message, &signature, &signer), Some(era));
+        assert_eq!(
+            migration.verify(&message, &signature, &Address::default()),
+            None
+        );
+    }
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "uniffi")]
+
+use eip_712_derive::mobile::*;
+use eip_712_derive::Error;
+
+const MAIL: &str = r#"{
+    "types": {
+        "EIP712Domain": [
+            { "name": "name", "type": "string" },
+            { "name": "version", "type": "string" },
+            { "name": "chainId", "type": "uint256" },
+            { "name": "verifyingContract", "type": "address" }
+        ],
+        "Person": [
+            { "name": "name", "type": "string" },
+            { "name": "wallet", "type": "address" }
+        ],
+        "Mail": [
+            { "name": "from", "type": "Person" },
+            { "name": "to", "type": "Person" },
+            { "name": "contents", "type": "string" }
+        ]
+    },
+    "primaryType": "Mail",
+    "domain": {
+        "name": "Ether Mail",
+        "version": "1",
+        "chainId": 1,
+        "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+    },
+    "message": {
+        "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+        "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+        "contents": "Hello, Bob!"
+    }
+}"#;
+
+const MAIL_SIGNATURE: &str = "0x4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b915621c";
+
+#[test]
+fn hashes() {
+    assert_eq!(
+        hex::encode(hash_typed_data(MAIL).unwrap()),
+        "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+    );
+    assert_eq!(
+        hex::encode(hash_struct(MAIL).unwrap()),
+        "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+    );
+    assert_eq!(
+        hex::encode(domain_separator(r#"{ "name": "Ether Mail", "version": "1", "chainId": 1, "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC" }"#).unwrap()),
+        "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+    );
+    assert!(matches!(
+        hash_typed_data("{}"),
+        Err(Error::InvalidTypedData(_))
+    ));
+}
+
+#[test]
+fn verification() {
+    let signature = hex::decode(&MAIL_SIGNATURE[2..]).unwrap();
+    assert_eq!(
+        recover_typed_data(MAIL, &signature).unwrap(),
+        "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"
+    );
+    assert_eq!(
+        verify_typed_data(
+            MAIL,
+            MAIL_SIGNATURE,
+            "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"
+        ),
+        Ok(true)
+    );
+    assert_eq!(
+        recover_typed_data(MAIL, &signature[..64]),
+        Err(Error::MalformedSignature)
+    );
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "sign")]
+
+use eip_712_derive::*;
+
+fn domain() -> Eip712Domain {
+    Eip712Domain {
+        name: "Multisig".to_owned(),
+        version: "1".to_owned(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn threshold() {
+    let domain_separator = DomainSeparator::new(&domain());
+    let mut multi = MultiSigned::new(&domain_separator, domain());
+    let keys: Vec<SecretKey> = [[1u8; 32], [2u8; 32], [3u8; 32]]
+        .iter()
+        .map(|k| SecretKey::from_bytes(k).unwrap())
+        .collect();
+    let mut owners: Vec<Address> = keys.iter().map(SecretKey::address).collect();
+
+    for key in &keys[..2] {
+        let signature = sign_typed(&domain_separator, multi.message(), key).unwrap();
+        multi.add(signature).unwrap();
+        // Duplicates are ignored
+        multi.add(signature).unwrap();
+    }
+    // A signer who is not an owner
+    let outsider = SecretKey::from_bytes(&[4u8; 32]).unwrap();
+    let outsider = sign_typed(&domain_separator, multi.message(), &outsider).unwrap();
+    multi.add(outsider).unwrap();
+    assert_eq!(multi.signers().count(), 3);
+
+    assert_eq!(
+        multi.check_threshold(&owners, 3),
+        Err(Error::ThresholdNotMet {
+            approvals: 2,
+            threshold: 3
+        })
+    );
+    let packed = multi.check_threshold(&owners, 2).unwrap();
+    assert_eq!(packed.len(), 130);
+
+    // Packed in ascending order of signer
+    owners.truncate(2);
+    owners.sort();
+    for (i, owner) in owners.iter().enumerate() {
+        let signature = Signature::from_bytes(&packed[i * 65..(i + 1) * 65]).unwrap();
+        assert_eq!(signature.recover(multi.digest()), Ok(*owner));
+    }
+}
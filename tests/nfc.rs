@@ -0,0 +1,18 @@
+#![cfg(feature = "nfc")]
+
+use eip_712_derive::*;
+
+#[test]
+fn composed_and_decomposed_hash_equally() {
+    let composed = "caf\u{e9}".to_owned();
+    let decomposed = "cafe\u{301}".to_owned();
+    assert_ne!(composed.encode_data(), decomposed.encode_data());
+
+    let composed = NfcString(composed);
+    let decomposed = NfcString(decomposed);
+    assert_eq!(composed.encode_data(), decomposed.encode_data());
+    assert_eq!(
+        decomposed.encode_data(),
+        "caf\u{e9}".to_owned().encode_data()
+    );
+}
@@ -0,0 +1,32 @@
+#![cfg(all(feature = "parallel", feature = "sign"))]
+
+use eip_712_derive::*;
+
+fn receipts() -> Vec<Eip712Domain> {
+    (0..100u64)
+        .map(|i| Eip712Domain {
+            name: "Receipt".to_owned(),
+            chain_id: U256::from(i),
+            ..Default::default()
+        })
+        .collect()
+}
+
+#[test]
+fn batches_match_sequential() {
+    let receipts = receipts();
+    let hashes = hash_struct_batch(&receipts);
+    let expected: Vec<_> = receipts.iter().map(hash_struct).collect();
+    assert_eq!(hashes, expected);
+
+    let domain_separator = DomainSeparator::new(&Eip712Domain::default());
+    let key = SecretKey::from_bytes(&[1u8; 32]).unwrap();
+    let signatures = sign_typed_batch(&domain_separator, &receipts, &key).unwrap();
+    assert_eq!(signatures.len(), receipts.len());
+    for (signature, receipt) in signatures.iter().zip(&receipts) {
+        assert_eq!(
+            *signature,
+            sign_typed(&domain_separator, receipt, &key).unwrap()
+        );
+    }
+}
@@ -0,0 +1,145 @@
+#![cfg(feature = "testing")]
+
+use eip_712_derive::testing::*;
+use eip_712_derive::*;
+
+struct Transfer {
+    amount: U256,
+}
+
+impl StructType for Transfer {
+    const TYPE_NAME: &'static str = "Transfer";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("amount", &self.amount);
+    }
+    fn visit_member_types<T: TypeVisitor>(visitor: &mut T) -> Result<(), Error> {
+        visitor.visit::<U256>("amount");
+        Ok(())
+    }
+}
+
+/// A different type of the same name
+struct OtherTransfer {
+    amount: U256,
+    to: Address,
+}
+
+impl StructType for OtherTransfer {
+    const TYPE_NAME: &'static str = "Transfer";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("amount", &self.amount);
+        visitor.visit("to", &self.to);
+    }
+    fn visit_member_types<T: TypeVisitor>(visitor: &mut T) -> Result<(), Error> {
+        visitor.visit::<U256>("amount");
+        visitor.visit::<Address>("to");
+        Ok(())
+    }
+}
+
+fn transfer(amount: u8) -> Transfer {
+    let mut value = U256::default();
+    value.0[31] = amount;
+    Transfer { amount: value }
+}
+
+#[test]
+fn allowlists() {
+    let domain_separator = DomainSeparator::new(&test_domain());
+    let signer = PolicySigner::new(MockSigner::new())
+        .allow_domain(domain_separator)
+        .allow_type::<Eip712Domain>();
+
+    assert!(signer.sign_typed(&domain_separator, &test_domain()).is_ok());
+    let request = SigningRequest::new(&domain_separator, &test_domain());
+    assert!(signer.sign(&request).is_ok());
+
+    let other_domain = DomainSeparator::from_bytes(&[1u8; 32]);
+    assert!(matches!(
+        signer.sign_typed(&other_domain, &test_domain()),
+        Err(Error::PolicyViolation(_))
+    ));
+    assert!(matches!(
+        signer.sign_typed(&domain_separator, &transfer(1)),
+        Err(Error::PolicyViolation(_))
+    ));
+    // Only the allowed requests reached the inner signer
+    assert_eq!(signer.into_inner().requests().len(), 2);
+}
+
+#[test]
+fn types_are_told_apart_by_hash() {
+    let domain_separator = DomainSeparator::new(&test_domain());
+    let signer = PolicySigner::new(MockSigner::new())
+        .allow_domain(domain_separator)
+        .allow_type::<Transfer>();
+
+    assert!(signer.sign_typed(&domain_separator, &transfer(1)).is_ok());
+    let other = OtherTransfer {
+        amount: transfer(1).amount,
+        to: Address::default(),
+    };
+    let request = SigningRequest::new(&domain_separator, &other);
+    assert_eq!(request.type_name(), "Transfer");
+    assert!(matches!(
+        signer.sign(&request),
+        Err(Error::PolicyViolation(_))
+    ));
+    assert!(matches!(
+        signer.sign_typed(&domain_separator, &other),
+        Err(Error::PolicyViolation(_))
+    ));
+}
+
+#[test]
+fn value_constraints() {
+    let domain_separator = DomainSeparator::new(&test_domain());
+    let signer = PolicySigner::new(MockSigner::new())
+        .allow_domain(domain_separator)
+        .allow_type_with(|t: &Transfer| {
+            if t.amount.0[31] <= 100 {
+                Ok(())
+            } else {
+                Err("Amount too large".to_owned())
+            }
+        });
+
+    assert!(signer.sign_typed(&domain_separator, &transfer(100)).is_ok());
+    assert_eq!(
+        signer.sign_typed(&domain_separator, &transfer(101)),
+        Err(Error::PolicyViolation("Amount too large".to_owned()))
+    );
+    // The constraint cannot be checked from hashes alone
+    let request = SigningRequest::new(&domain_separator, &transfer(1));
+    assert!(matches!(
+        signer.sign(&request),
+        Err(Error::PolicyViolation(_))
+    ));
+}
+
+#[test]
+fn forged_requests_are_refused() {
+    // Requests cannot be built from a chosen type name and digest (see the
+    // compile_fail example on SigningRequest), so one made from a disallowed
+    // value keeps its type and is refused
+    let domain_separator = DomainSeparator::new(&test_domain());
+    let signer = PolicySigner::new(MockSigner::new())
+        .allow_domain(domain_separator)
+        .allow_type::<Eip712Domain>();
+    let forged = SigningRequest::new(&domain_separator, &transfer(1));
+    assert_eq!(forged.type_name(), "Transfer");
+    assert!(matches!(
+        signer.sign(&forged),
+        Err(Error::PolicyViolation(_))
+    ));
+
+    // What reaches the inner signer is the digest of the checked value
+    let request = SigningRequest::new(&domain_separator, &test_domain());
+    signer.sign(&request).unwrap();
+    let requests = signer.into_inner().requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        *requests[0].digest(),
+        sign_hash(&domain_separator, &test_domain())
+    );
+}
@@ -0,0 +1,53 @@
+use eip_712_derive::*;
+
+struct Ping {
+    sequence: U256,
+}
+impl StructType for Ping {
+    const TYPE_NAME: &'static str = "Ping";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("sequence", &self.sequence);
+    }
+}
+
+#[test]
+fn default_profile_is_eip_712() {
+    let domain_separator = DomainSeparator::from_bytes(&[1u8; 32]);
+    let ping = Ping {
+        sequence: U256::default(),
+    };
+    let profile = EncodingProfile::default();
+    assert_eq!(profile, EncodingProfile::EIP_712);
+    assert_eq!(
+        &profile.encode(&domain_separator, &ping)[..],
+        &encode(&domain_separator, &ping)[..]
+    );
+    assert_eq!(
+        profile.sign_hash(&domain_separator, &ping),
+        sign_hash(&domain_separator, &ping)
+    );
+    #[cfg(feature = "sign")]
+    {
+        let key = SecretKey::from_bytes(&[1u8; 32]).unwrap();
+        assert_eq!(
+            profile.sign_typed(&domain_separator, &ping, &key).unwrap(),
+            sign_typed(&domain_separator, &ping, &key).unwrap()
+        );
+    }
+}
+
+#[test]
+fn custom_prefix() {
+    let domain_separator = DomainSeparator::from_bytes(&[1u8; 32]);
+    let ping = Ping {
+        sequence: U256::default(),
+    };
+    let profile = EncodingProfile::with_prefix(b"\x19\x02");
+    let encoded = profile.encode(&domain_separator, &ping);
+    assert_eq!(&encoded[..2], b"\x19\x02");
+    assert_eq!(&encoded[2..], &encode(&domain_separator, &ping)[2..]);
+    assert_ne!(
+        profile.sign_hash(&domain_separator, &ping),
+        sign_hash(&domain_separator, &ping)
+    );
+}
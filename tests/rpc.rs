@@ -0,0 +1,362 @@
+#![cfg(feature = "rpc")]
+
+#[cfg(feature = "sign")]
+use eip_712_derive::rpc::verify_signature;
+use eip_712_derive::rpc::{
+    fetch_domain, fetch_domain_separator, is_valid_signature, verify_domain_separator,
+    Erc5267Domain, Provider,
+};
+use eip_712_derive::*;
+use std::future::{ready, Future};
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+fn block_on<F: Future>(f: F) -> F::Output {
+    let mut f = pin!(f);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = f.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+/// A contract with `DOMAIN_SEPARATOR()`, and `eip712Domain()` if
+/// `erc5267` is not empty
+struct FixedResponse {
+    domain_separator: Vec<u8>,
+    erc5267: Vec<u8>,
+}
+
+impl FixedResponse {
+    fn new(domain_separator: &[u8]) -> Self {
+        Self {
+            domain_separator: domain_separator.to_vec(),
+            erc5267: Vec::new(),
+        }
+    }
+}
+
+impl Provider for FixedResponse {
+    type Error = std::convert::Infallible;
+    fn call(
+        &self,
+        _to: &Address,
+        data: &[u8],
+    ) -> impl Future<Output = Result<Vec<u8>, Self::Error>> {
+        match data {
+            // DOMAIN_SEPARATOR()
+            [0x36, 0x44, 0xe5, 0x15] => ready(Ok(self.domain_separator.clone())),
+            // eip712Domain()
+            [0x84, 0xb0, 0x19, 0x6e] => ready(Ok(self.erc5267.clone())),
+            _ => panic!("Unexpected call"),
+        }
+    }
+}
+
+fn mail_domain() -> PartialDomain {
+    Eip712Domain::builder()
+        .name("Ether Mail")
+        .version("1")
+        .chain_id(1u64)
+        .verifying_contract(Address([0xcc; 20]))
+        .build()
+}
+
+#[test]
+fn domain_separator_matches() {
+    let local = DomainSeparator::new(&mail_domain());
+    let provider = FixedResponse::new(local.as_bytes());
+    let result = block_on(verify_domain_separator(
+        &provider,
+        &Address::default(),
+        &mail_domain(),
+    ));
+    assert!(result.is_ok());
+}
+
+/// A provider which cannot reach its node
+struct Offline;
+
+impl Provider for Offline {
+    type Error = String;
+    fn call(
+        &self,
+        _to: &Address,
+        _data: &[u8],
+    ) -> impl Future<Output = Result<Vec<u8>, Self::Error>> + Send {
+        ready(Err("connection refused".to_owned()))
+    }
+}
+
+fn assert_send<T: Send>(value: T) -> T {
+    value
+}
+
+#[test]
+fn provider_errors() {
+    // Usable from multi-threaded executors
+    let (contract, domain) = (Address::default(), mail_domain());
+    let future = assert_send(verify_domain_separator(&Offline, &contract, &domain));
+    assert_eq!(
+        block_on(future),
+        Err(Error::Provider("connection refused".to_owned()))
+    );
+}
+
+#[test]
+fn domain_separator_mismatch() {
+    let provider = FixedResponse::new(&[8u8; 32]);
+    let result = block_on(verify_domain_separator(
+        &provider,
+        &Address::default(),
+        &mail_domain(),
+    ));
+    match result {
+        Err(Error::DomainSeparatorMismatch {
+            on_chain,
+            differences,
+            ..
+        }) => {
+            assert_eq!(on_chain.as_bytes(), &[8u8; 32]);
+            // Without eip712Domain() the fields are unknown
+            assert_eq!(differences, vec![]);
+        }
+        _ => panic!("Expected mismatch"),
+    }
+}
+
+#[test]
+fn domain_separator_mismatch_by_field() {
+    let on_chain = DomainSeparator::new(&mail_domain());
+    let provider = FixedResponse {
+        domain_separator: on_chain.as_bytes().to_vec(),
+        erc5267: erc5267_response(&[]),
+    };
+    let mut domain = mail_domain();
+    domain.chain_id = Some(5u64.into());
+    domain.salt = Some([1; 32]);
+    let error = block_on(verify_domain_separator(
+        &provider,
+        &Address::default(),
+        &domain,
+    ))
+    .unwrap_err();
+    match &error {
+        Error::DomainSeparatorMismatch { differences, .. } => assert_eq!(
+            differences,
+            &vec![
+                FieldDifference {
+                    field: "chainId",
+                    local: Some("5".to_owned()),
+                    on_chain: Some("1".to_owned()),
+                },
+                FieldDifference {
+                    field: "salt",
+                    local: Some(format!("0x{}", "01".repeat(32))),
+                    on_chain: None,
+                },
+            ]
+        ),
+        _ => panic!("Expected mismatch"),
+    }
+    let message = error.to_string();
+    assert!(message.contains("\n  chainId: local 5, on-chain 1"));
+    assert!(message.contains("\n  salt: local 0x0101"));
+    assert!(message.ends_with(", on-chain (absent)"));
+}
+
+#[test]
+fn domain_separator_malformed() {
+    let provider = FixedResponse::new(&[]);
+    let result = block_on(verify_domain_separator(
+        &provider,
+        &Address::default(),
+        &mail_domain(),
+    ));
+    assert!(matches!(result, Err(Error::MalformedResponse(_))));
+}
+
+struct Erc5267(Vec<u8>);
+
+impl Provider for Erc5267 {
+    type Error = std::convert::Infallible;
+    fn call(
+        &self,
+        _to: &Address,
+        data: &[u8],
+    ) -> impl Future<Output = Result<Vec<u8>, Self::Error>> {
+        // eip712Domain()
+        assert_eq!(data, &[0x84, 0xb0, 0x19, 0x6e]);
+        ready(Ok(self.0.clone()))
+    }
+}
+
+fn word(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// The eip712Domain() return value for name "Ether Mail", version "1",
+/// chainId 1 and verifyingContract 0xCcCC...cccC.
+fn erc5267_response(extensions: &[u64]) -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut fields = [0u8; 32];
+    fields[0] = 0x0f;
+    data.extend_from_slice(&fields);
+    data.extend_from_slice(&word(7 * 32));
+    data.extend_from_slice(&word(9 * 32));
+    data.extend_from_slice(&word(1));
+    data.extend_from_slice(&[0u8; 12]);
+    data.extend_from_slice(&[0xcc; 20]);
+    data.extend_from_slice(&[0u8; 32]);
+    data.extend_from_slice(&word(11 * 32));
+    for string in &["Ether Mail", "1"] {
+        data.extend_from_slice(&word(string.len() as u64));
+        let mut padded = [0u8; 32];
+        padded[..string.len()].copy_from_slice(string.as_bytes());
+        data.extend_from_slice(&padded);
+    }
+    data.extend_from_slice(&word(extensions.len() as u64));
+    for &extension in extensions {
+        data.extend_from_slice(&word(extension));
+    }
+    data
+}
+
+#[test]
+fn fetch_erc5267_domain() {
+    let provider = Erc5267(erc5267_response(&[]));
+    let domain = block_on(fetch_domain(&provider, &Address::default())).unwrap();
+    assert_eq!(domain.name.as_deref(), Some("Ether Mail"));
+    assert_eq!(domain.verifying_contract, Some(Address([0xcc; 20])));
+    assert_eq!(domain.salt, None);
+    assert_eq!(domain.to_partial_domain(), Ok(mail_domain()));
+
+    // The domain separator of the Mail example in EIP-712
+    let domain_separator = block_on(fetch_domain_separator(&provider, &Address::default()));
+    assert_eq!(
+        hex::encode(domain_separator.unwrap().as_bytes()),
+        "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+    );
+}
+
+#[test]
+fn erc5267_errors() {
+    let provider = Erc5267(erc5267_response(&[5267]));
+    match block_on(fetch_domain_separator(&provider, &Address::default())) {
+        Err(Error::UnsupportedExtensions(extensions)) => assert_eq!(extensions.len(), 1),
+        _ => panic!("Expected unsupported extensions"),
+    }
+
+    let mut truncated = erc5267_response(&[]);
+    truncated.truncate(7 * 32 + 40);
+    assert_eq!(Erc5267Domain::decode(&truncated), None);
+}
+
+/// A contract account which accepts one signature for one digest
+struct ContractAccount {
+    digest: Bytes32,
+    signature: Vec<u8>,
+}
+
+impl Provider for ContractAccount {
+    type Error = std::convert::Infallible;
+    fn call(
+        &self,
+        _to: &Address,
+        data: &[u8],
+    ) -> impl Future<Output = Result<Vec<u8>, Self::Error>> {
+        // isValidSignature(bytes32,bytes)
+        assert_eq!(data[..4], [0x16, 0x26, 0xba, 0x7e]);
+        assert_eq!(data[36..68], word(64));
+        assert_eq!(data.len() % 32, 4);
+        let len = data[68 + 31] as usize;
+        let valid = data[4..36] == self.digest && data[100..100 + len] == self.signature[..];
+        let mut response = vec![0u8; 32];
+        if valid {
+            response[..4].copy_from_slice(&[0x16, 0x26, 0xba, 0x7e]);
+        }
+        ready(Ok(response))
+    }
+}
+
+/// An externally owned account, or any address without code
+struct NoCode;
+
+impl Provider for NoCode {
+    type Error = std::convert::Infallible;
+    fn call(
+        &self,
+        _to: &Address,
+        _data: &[u8],
+    ) -> impl Future<Output = Result<Vec<u8>, Self::Error>> {
+        ready(Ok(Vec::new()))
+    }
+}
+
+#[test]
+fn erc1271_signatures() {
+    let account = ContractAccount {
+        digest: [3; 32],
+        signature: vec![9; 130],
+    };
+    let signer = Address([5; 20]);
+    let check = |digest: &Bytes32, signature: &[u8]| {
+        block_on(is_valid_signature(&account, &signer, digest, signature)).unwrap()
+    };
+    assert!(check(&[3; 32], &[9; 130]));
+    assert!(!check(&[4; 32], &[9; 130]));
+    assert!(!check(&[3; 32], &[9; 65]));
+
+    assert_eq!(
+        block_on(is_valid_signature(&NoCode, &signer, &[3; 32], &[])).ok(),
+        Some(false)
+    );
+}
+
+#[cfg(feature = "sign")]
+#[test]
+fn eoa_or_contract_signer() {
+    let domain = Eip712Domain::default();
+    let domain_separator = DomainSeparator::new(&domain);
+    let message = Eip712Domain {
+        name: "message".to_owned(),
+        ..Default::default()
+    };
+    let key = SecretKey::from_bytes(&[1u8; 32]).unwrap();
+    let eoa = key.address();
+    let signature = sign_typed(&domain_separator, &message, &key)
+        .unwrap()
+        .to_bytes();
+
+    // Accepted by recovery alone, without asking the account
+    assert!(block_on(verify_signature(
+        &NoCode,
+        &domain_separator,
+        &message,
+        &signature,
+        &eoa
+    ))
+    .unwrap());
+
+    let verify = |provider: &ContractAccount, signature: &[u8], signer: &Address| {
+        block_on(verify_signature(
+            provider,
+            &domain_separator,
+            &message,
+            signature,
+            signer,
+        ))
+        .unwrap()
+    };
+
+    let safe = Address([0x5a; 20]);
+    let account = ContractAccount {
+        digest: sign_hash(&domain_separator, &message),
+        signature: signature.to_vec(),
+    };
+    assert!(verify(&account, &signature, &safe));
+    assert!(!verify(&account, &[1; 65], &safe));
+}
@@ -0,0 +1,36 @@
+use eip_712_derive::*;
+
+#[test]
+fn constructors() {
+    assert_eq!(Salt::new([0u8; 32]), Err(Error::InvalidSalt));
+    assert_eq!(Salt::new([1u8; 32]).unwrap().as_bytes(), &[1u8; 32]);
+
+    let label = Salt::from_label("example-mainnet");
+    assert_eq!(label, Salt::from_label("example-mainnet"));
+    assert_ne!(label, Salt::from_label("example-goerli"));
+
+    let contract = Address([0xcc; 20]);
+    let mainnet = Salt::for_deployment("example", &U256::default(), &contract);
+    let mut chain_id = U256::default();
+    chain_id.0[31] = 5;
+    assert_ne!(
+        mainnet,
+        Salt::for_deployment("example", &chain_id, &contract)
+    );
+}
+
+#[cfg(feature = "random")]
+#[test]
+fn random() {
+    assert_ne!(Salt::random(), Salt::random());
+}
+
+#[test]
+fn encodes_as_bytes32() {
+    let salt = Salt::from_label("example");
+    let domain = Eip712Domain {
+        salt: salt.into(),
+        ..Default::default()
+    };
+    assert_eq!(salt.encode_data(), domain.salt);
+}
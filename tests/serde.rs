@@ -0,0 +1,115 @@
+#![cfg(feature = "serde")]
+
+use eip_712_derive::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+const HEX: &str = "0x4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b915621c";
+
+fn signature() -> Signature {
+    Signature::from_bytes(&hex::decode(&HEX[2..]).unwrap()).unwrap()
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Payload {
+    signature: Signature,
+    #[serde(with = "eip_712_derive::serde_helpers::rsv")]
+    split: Signature,
+}
+
+#[test]
+fn signature_round_trip() {
+    let payload = Payload {
+        signature: signature(),
+        split: signature(),
+    };
+    let json = serde_json::to_value(&payload).unwrap();
+    assert_eq!(
+        json,
+        json!({
+            "signature": HEX,
+            "split": {
+                "r": "0x4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d",
+                "s": "0x07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562",
+                "v": 28,
+            },
+        })
+    );
+    assert_eq!(serde_json::from_value::<Payload>(json).unwrap(), payload);
+}
+
+#[test]
+fn signature_deserialize_either_form() {
+    let from_object: Signature = serde_json::from_value(json!({
+        "r": "0x4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d",
+        "s": "0x07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562",
+        "v": 1,
+    }))
+    .unwrap();
+    assert_eq!(from_object, signature());
+
+    assert!(serde_json::from_value::<Signature>(json!("0x1234")).is_err());
+    assert!(serde_json::from_value::<Signature>(json!({ "r": "0x", "s": "0x", "v": 27 })).is_err());
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Order {
+    maker: Address,
+    amount: U256,
+    nonce: Uint64,
+    #[serde(with = "eip_712_derive::serde_helpers::hex_bytes")]
+    selector: Bytes4,
+    #[serde(with = "eip_712_derive::serde_helpers::hex_bytes")]
+    data: Vec<u8>,
+}
+
+#[test]
+fn atomic_round_trip() {
+    let order = Order {
+        maker: Address([0x11; 20]),
+        amount: U256::from_dec_str("123456789012345678901234567890").unwrap(),
+        nonce: Uint64::new(U256::from(7u64)).unwrap(),
+        selector: [0xa9, 0x05, 0x9c, 0xbb],
+        data: vec![1, 2, 3],
+    };
+    let json = serde_json::to_value(&order).unwrap();
+    assert_eq!(
+        json,
+        json!({
+            "maker": "0x1111111111111111111111111111111111111111",
+            "amount": "123456789012345678901234567890",
+            "nonce": "7",
+            "selector": "0xa9059cbb",
+            "data": "0x010203",
+        })
+    );
+    assert_eq!(serde_json::from_value::<Order>(json).unwrap(), order);
+
+    // Numbers and hex strings are accepted too
+    let from_numbers: Order = serde_json::from_value(json!({
+        "maker": "0x1111111111111111111111111111111111111111",
+        "amount": "0x18ee90ff6c373e0ee4e3f0ad2",
+        "nonce": 7,
+        "selector": "a9059cbb",
+        "data": "0x010203",
+    }))
+    .unwrap();
+    assert_eq!(from_numbers, order);
+}
+
+#[test]
+fn atomic_rejects_out_of_range() {
+    assert!(serde_json::from_value::<Uint8>(json!(256)).is_err());
+    assert!(serde_json::from_value::<Uint8>(json!("255")).is_ok());
+    assert!(serde_json::from_value::<U256>(json!("-1")).is_err());
+    assert!(serde_json::from_value::<U256>(json!(1.5)).is_err());
+
+    let wrong_length = json!({
+        "maker": "0x1111111111111111111111111111111111111111",
+        "amount": "1",
+        "nonce": "1",
+        "selector": "0xa9059c",
+        "data": "0x",
+    });
+    assert!(serde_json::from_value::<Order>(wrong_length).is_err());
+}
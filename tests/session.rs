@@ -0,0 +1,97 @@
+#![cfg(feature = "testing")]
+
+use eip_712_derive::testing::*;
+use eip_712_derive::*;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn scope(valid_after: SystemTime, valid_until: SystemTime) -> SessionScope {
+    SessionScope::new(valid_after, valid_until)
+        .allow_domain(DomainSeparator::new(&test_domain()))
+        .allow_type::<Eip712Domain>()
+        .allow_target(Address([0x11; 20]))
+}
+
+fn session_key() -> SecretKey {
+    SecretKey::new(key_from_seed("session")).unwrap()
+}
+
+#[test]
+fn enforces_scope() {
+    let now = SystemTime::now();
+    let hour = Duration::from_secs(3600);
+    let signer = SessionSigner::new(session_key(), scope(now - hour, now + hour));
+    let domain_separator = DomainSeparator::new(&test_domain());
+
+    assert!(signer.sign_typed(&domain_separator, &test_domain()).is_ok());
+    assert!(matches!(
+        signer.sign_typed(&DomainSeparator::from_bytes(&[1u8; 32]), &test_domain()),
+        Err(Error::PolicyViolation(_))
+    ));
+
+    let expired = SessionSigner::new(session_key(), scope(now - hour * 2, now - hour));
+    assert_eq!(
+        expired.sign_typed(&domain_separator, &test_domain()),
+        Err(Error::SessionExpired)
+    );
+}
+
+#[derive(Default)]
+struct Transfer {
+    amount: U256,
+}
+
+impl StructType for Transfer {
+    const TYPE_NAME: &'static str = "Transfer";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("amount", &self.amount);
+    }
+}
+
+#[test]
+fn forged_requests_are_refused() {
+    let now = SystemTime::now();
+    let hour = Duration::from_secs(3600);
+    let signer = SessionSigner::new(session_key(), scope(now - hour, now + hour));
+    let domain_separator = DomainSeparator::new(&test_domain());
+
+    // A request for an out of scope type cannot be relabelled as an allowed
+    // one, since requests are only made from typed values
+    let forged = SigningRequest::new(&domain_separator, &Transfer::default());
+    assert_eq!(
+        signer.scope().check(&forged, now),
+        Err(Error::PolicyViolation(
+            "Type Transfer not in session scope".to_owned()
+        ))
+    );
+    assert!(matches!(
+        Signer::sign(&signer, &forged),
+        Err(Error::PolicyViolation(_))
+    ));
+
+    // The signature allowed by the scope is over the checked value
+    let request = SigningRequest::new(&domain_separator, &test_domain());
+    let signature = Signer::sign(&signer, &request).unwrap();
+    assert_eq!(
+        signature.recover(&sign_hash(&domain_separator, &test_domain())),
+        Ok(Signer::address(&signer))
+    );
+}
+
+#[test]
+fn export_scope() {
+    let start = UNIX_EPOCH + Duration::from_secs(1000);
+    let end = UNIX_EPOCH + Duration::from_secs(2000);
+    let signer = SessionSigner::new(session_key(), scope(start, end));
+    let encoded = signer.export_scope();
+
+    // 5 head words, 1 domain and 1 target with their lengths
+    assert_eq!(encoded.len(), 9 * 32);
+    assert_eq!(&encoded[12..32], &Signer::address(&signer).0);
+    assert_eq!(&encoded[62..64], &1000u16.to_be_bytes());
+    assert_eq!(&encoded[94..96], &2000u16.to_be_bytes());
+    assert_eq!(
+        &encoded[6 * 32..7 * 32],
+        DomainSeparator::new(&test_domain()).as_bytes()
+    );
+    assert_eq!(&encoded[8 * 32 + 12..], &[0x11; 20]);
+}
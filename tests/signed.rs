@@ -0,0 +1,64 @@
+#![cfg(feature = "testing")]
+
+use eip_712_derive::testing::*;
+use eip_712_derive::*;
+
+#[test]
+fn sign_and_verify() {
+    let signer = MockSigner::new();
+    let domain_separator = DomainSeparator::new(&test_domain());
+    let signed = Signed::sign(&signer, &domain_separator, test_domain()).unwrap();
+    assert_eq!(signed.verify(&domain_separator), Ok(&test_domain()));
+    assert_eq!(signed.signer(), &Signer::address(&signer));
+
+    let other = DomainSeparator::from_bytes(&[1u8; 32]);
+    assert_eq!(signed.verify(&other), Err(Error::DomainMismatch));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+    struct Note {
+        text: String,
+    }
+    impl StructType for Note {
+        const TYPE_NAME: &'static str = "Note";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("text", &self.text);
+        }
+    }
+
+    let domain_separator = DomainSeparator::new(&test_domain());
+    let note = Note {
+        text: "hello".to_owned(),
+    };
+    let signed = Signed::sign(&MockSigner::new(), &domain_separator, note).unwrap();
+    let json = serde_json::to_value(&signed).unwrap();
+    assert_eq!(json["message"]["text"], "hello");
+    assert!(json["domainSeparator"].as_str().unwrap().starts_with("0x"));
+
+    let round_trip: Signed<Note> = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(round_trip, signed);
+
+    // A signature detached from its payload
+    let mut tampered = json;
+    tampered["message"]["text"] = "goodbye".into();
+    let tampered: Signed<Note> = serde_json::from_value(tampered).unwrap();
+    assert!(matches!(
+        tampered.verify(&domain_separator),
+        Err(Error::SignerMismatch { .. })
+    ));
+
+    // An envelope made for a different type with the same shape
+    let mut other_type = serde_json::to_value(&signed).unwrap();
+    other_type["primaryType"] = "Memo".into();
+    let other_type: Signed<Note> = serde_json::from_value(other_type).unwrap();
+    assert_eq!(
+        other_type.verify(&domain_separator),
+        Err(Error::TypeMismatch {
+            expected: "Note".to_owned(),
+            found: "Memo".to_owned()
+        })
+    );
+}
@@ -0,0 +1,110 @@
+use eip_712_derive::__private::TypeHashBuilder;
+use eip_712_derive::summary::{summarize, Hints, SummaryValue};
+use eip_712_derive::*;
+use std::borrow::Cow;
+
+struct Person {
+    name: String,
+    wallet: Address,
+}
+impl StructType for Person {
+    const TYPE_NAME: &'static str = "Person";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("name", &self.name);
+        visitor.visit("wallet", &self.wallet);
+    }
+}
+
+struct Payment {
+    to: Person,
+    amount: U256,
+    fee: U256,
+}
+impl StructType for Payment {
+    const TYPE_NAME: &'static str = "Payment";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("to", &self.to);
+        visitor.visit("amount", &self.amount);
+        visitor.visit("fee", &self.fee);
+    }
+}
+
+#[test]
+fn summarize_payment() {
+    let mut wallet = [0u8; 20];
+    wallet.copy_from_slice(&hex::decode("fb6916095ca1df60bb79ce92ce3ea74c37c5d359").unwrap());
+    // 1.5 * 10^18
+    let mut amount = U256::default();
+    amount.0[24..].copy_from_slice(&1_500_000_000_000_000_000u64.to_be_bytes());
+    let mut fee = U256::default();
+    fee.0[31] = 250;
+
+    let payment = Payment {
+        to: Person {
+            name: "Bob".to_owned(),
+            wallet: Address(wallet),
+        },
+        amount,
+        fee,
+    };
+    let hints = Hints::new()
+        .label("Payment", "to", "Recipient")
+        .token("Payment", "amount", 18, "GRT");
+    let summary = summarize(&payment, &hints);
+
+    assert_eq!(
+        summary.members[1].value,
+        SummaryValue::Uint("1.5 GRT".to_owned())
+    );
+    assert_eq!(
+        summary.members[2].value,
+        SummaryValue::Uint("250".to_owned())
+    );
+    assert_eq!(
+        summary.to_string(),
+        "Payment
+  Recipient: Person
+    name: \"Bob\"
+    wallet: 0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359
+  amount: 1.5 GRT
+  fee: 250
+"
+    );
+}
+
+#[test]
+fn strings_cannot_forge_lines() {
+    let person = Person {
+        name: "Bob\n  amount: 1000000".to_owned(),
+        wallet: Address([0; 20]),
+    };
+    assert_eq!(
+        summarize(&person, &Hints::new()).to_string(),
+        "Person
+  name: \"Bob\\n  amount: 1000000\"
+  wallet: 0x0000000000000000000000000000000000000000
+"
+    );
+}
+
+/// A member type which only implements what encoding needs
+struct Opaque(u8);
+impl MemberType for Opaque {
+    fn type_name() -> Cow<'static, str> {
+        Cow::Borrowed("uint8")
+    }
+    fn encode_data(&self) -> Bytes32 {
+        let mut result = [0u8; 32];
+        result[31] = self.0;
+        result
+    }
+    fn add_members(&self, _builder: &mut TypeHashBuilder) {}
+}
+
+#[test]
+fn summarize_defaults_to_encoded_hex() {
+    assert_eq!(
+        Opaque(1).summarize(&Hints::new()),
+        SummaryValue::Bytes(format!("0x{}01", "00".repeat(31)))
+    );
+}
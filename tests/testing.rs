@@ -0,0 +1,61 @@
+#![cfg(feature = "testing")]
+
+use eip_712_derive::testing::*;
+use eip_712_derive::*;
+
+#[test]
+fn mock_signer_signs_and_records() {
+    let signer = MockSigner::new();
+    let domain_separator = DomainSeparator::new(&test_domain());
+    let message = test_domain();
+
+    let signature = signer.sign_typed(&domain_separator, &message).unwrap();
+    let digest = sign_hash(&domain_separator, &message);
+    assert_eq!(
+        signature.recover(&digest).unwrap(),
+        Signer::address(&signer)
+    );
+
+    let requests = signer.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(*requests[0].digest(), digest);
+    assert_eq!(requests[0].type_name(), "EIP712Domain");
+    // Every field is set, including a salt which separates something
+    assert_eq!(message.chain_id, U256::from(1337u64));
+    assert!(Salt::new(message.salt).is_ok());
+
+    // Deterministic
+    assert_eq!(
+        MockSigner::new().sign_typed(&domain_separator, &message),
+        Ok(signature)
+    );
+}
+
+#[test]
+fn mock_signer_failure() {
+    let signer = MockSigner::new();
+    let domain_separator = DomainSeparator::new(&test_domain());
+    signer.fail_with(Some(Error::Timeout));
+    assert_eq!(
+        signer.sign_typed(&domain_separator, &test_domain()),
+        Err(Error::Timeout)
+    );
+    signer.fail_with(None);
+    assert!(signer.sign_typed(&domain_separator, &test_domain()).is_ok());
+    assert_eq!(signer.requests().len(), 2);
+}
+
+#[test]
+fn keys() {
+    assert_eq!(key_from_seed("alice"), key_from_seed("alice"));
+    assert_ne!(key_from_seed("alice"), key_from_seed("bob"));
+    #[cfg(feature = "random")]
+    {
+        assert_ne!(random_key(), random_key());
+        assert!(signer_address(&random_key()).is_ok());
+    }
+    assert_eq!(
+        signer_address(&PrivateKey::from([0u8; 32])),
+        Err(Error::InvalidPrivateKey)
+    );
+}
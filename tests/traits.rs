@@ -0,0 +1,146 @@
+use eip_712_derive::*;
+use std::collections::{BTreeSet, HashSet};
+
+#[test]
+fn u256_orders_numerically() {
+    let mut small = U256::default();
+    small.0[31] = 0xff;
+    let mut large = U256::default();
+    large.0[30] = 0x01;
+    assert!(small < large);
+    assert_eq!(small.max(large), large);
+}
+
+#[test]
+fn u256_constructors() {
+    let expected = U256::from(0x0102u64);
+    assert_eq!(expected.0[30..], [1, 2]);
+    assert_eq!(U256::from(0x0102u128), expected);
+    assert_eq!(U256::from_dec_str("258"), Some(expected));
+    assert_eq!(U256::from_hex_str("0x102"), Some(expected));
+    assert_eq!(U256::from_hex_str("0102"), Some(expected));
+
+    let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+    assert_eq!(U256::from_dec_str(max), Some(U256([0xff; 32])));
+    assert_eq!(U256::from_hex_str(&"f".repeat(64)), Some(U256([0xff; 32])));
+    // 2^256
+    let overflow = "115792089237316195423570985008687907853269984665640564039457584007913129639936";
+    assert_eq!(U256::from_dec_str(overflow), None);
+    assert_eq!(U256::from_hex_str(&format!("1{}", "0".repeat(64))), None);
+    assert_eq!(U256::from_dec_str(""), None);
+    assert_eq!(U256::from_dec_str("-1"), None);
+    assert_eq!(U256::from_hex_str("0x"), None);
+    assert_eq!(U256::from_hex_str("0xg"), None);
+}
+
+#[test]
+fn u256_arithmetic() {
+    let a = U256::from(u128::MAX);
+    let b = U256::from(3u64);
+    let sum = a.checked_add(b).unwrap();
+    assert_eq!(sum.to_string(), "340282366920938463463374607431768211458");
+    assert_eq!(sum.checked_sub(b), Some(a));
+    assert_eq!(b.checked_sub(a), None);
+    assert_eq!(U256::MAX.checked_add(U256::ONE), None);
+    assert_eq!(U256::MAX.checked_sub(U256::MAX), Some(U256::ZERO));
+
+    let square = a.checked_mul(a).unwrap();
+    assert_eq!(
+        format!("{:x}", square),
+        format!(
+            "{}{}{}",
+            "f".repeat(31),
+            "e",
+            format!("{}1", "0".repeat(31))
+        )
+    );
+    assert_eq!(square.checked_mul(b), None);
+    assert_eq!(U256::MAX.checked_mul(U256::ZERO), Some(U256::ZERO));
+
+    let nonce = U256::from(41u64).checked_add(U256::ONE).unwrap();
+    assert_eq!(nonce.to_string(), "42");
+    assert_eq!(format!("{:>5}", nonce), "   42");
+    assert_eq!(format!("{:#x}", nonce), "0x2a");
+    assert_eq!(format!("{:X}", nonce), "2A");
+    assert_eq!(format!("{:x}", U256::ZERO), "0");
+    assert!(U256::from(7u64) < nonce);
+}
+
+#[test]
+fn usable_as_keys() {
+    let a = Address([1; 20]);
+    let b = Address([2; 20]);
+    let addresses: BTreeSet<_> = vec![b, a, b].into_iter().collect();
+    assert_eq!(addresses.into_iter().collect::<Vec<_>>(), vec![a, b]);
+
+    let domain = Eip712Domain {
+        name: "Test".to_owned(),
+        ..Default::default()
+    };
+    let domains: HashSet<_> = vec![domain.clone(), domain.clone()].into_iter().collect();
+    assert_eq!(domains.len(), 1);
+
+    let separators: BTreeSet<_> = vec![DomainSeparator::new(&domain), DomainSeparator::default()]
+        .into_iter()
+        .collect();
+    assert_eq!(separators.iter().next(), Some(&DomainSeparator::default()));
+}
+
+#[test]
+fn address_display() {
+    // Test vectors from EIP-55
+    for expected in [
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ] {
+        let mut address = Address::default();
+        hex::decode_to_slice(&expected[2..], &mut address.0).unwrap();
+        assert_eq!(address.to_string(), expected);
+        assert_eq!(address.to_checksum_string(), expected);
+    }
+    assert_eq!(
+        format!("{:>44}", Address([0; 20])),
+        "  0x0000000000000000000000000000000000000000"
+    );
+}
+
+#[test]
+fn bytes_hex() {
+    let selector = Bytes4::from_hex("0xa9059cbb").unwrap();
+    assert_eq!(selector, [0xa9, 0x05, 0x9c, 0xbb]);
+    assert_eq!(Bytes4::from_hex("A9059CBB").unwrap(), selector);
+    assert_eq!(selector.to_hex(), "0xa9059cbb");
+
+    let salt = [0x22u8; 32];
+    assert_eq!(Bytes32::from_hex(&salt.to_hex()).unwrap(), salt);
+
+    for invalid in ["0xa9059c", "0xa9059cbb00", "0xa9059cbg", "0x+9059cbb"] {
+        assert_eq!(
+            Bytes4::from_hex(invalid),
+            Err(Error::InvalidHex(invalid.to_owned()))
+        );
+    }
+}
+
+#[test]
+fn fixed_bytes_generic() {
+    fn member<const N: usize>(value: FixedBytes<N>) -> (String, Bytes32) {
+        (
+            FixedBytes::<N>::type_name().into_owned(),
+            value.encode_data(),
+        )
+    }
+    let (name, encoded) = member([0xa9, 0x05, 0x9c, 0xbb]);
+    assert_eq!(name, "bytes4");
+    assert_eq!(encoded[..5], [0xa9, 0x05, 0x9c, 0xbb, 0]);
+
+    let (name, encoded) = member::<32>([7; 32]);
+    assert_eq!(name, "bytes32");
+    assert_eq!(encoded, [7; 32]);
+
+    let selector: Bytes4 = [1, 2, 3, 4];
+    let generic: FixedBytes<4> = selector;
+    assert_eq!(generic.to_hex(), "0x01020304");
+}
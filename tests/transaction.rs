@@ -27,6 +27,11 @@ impl StructType for Person {
         visitor.visit("wallet", &self.wallet);
         visitor.visit("name", &self.name);
     }
+    fn visit_member_types<T: TypeVisitor>(visitor: &mut T) -> Result<(), Error> {
+        visitor.visit::<Address>("wallet");
+        visitor.visit::<String>("name");
+        Ok(())
+    }
 }
 
 #[derive(Default)]
@@ -41,6 +46,11 @@ impl StructType for Asset {
         visitor.visit("token", &self.token);
         visitor.visit("amount", &self.amount);
     }
+    fn visit_member_types<T: TypeVisitor>(visitor: &mut T) -> Result<(), Error> {
+        visitor.visit::<Address>("token");
+        visitor.visit::<U256>("amount");
+        Ok(())
+    }
 }
 
 #[test]
@@ -50,3 +60,676 @@ fn encode_transaction_type() {
     let value: Transaction = Default::default();
     assert_eq!(encode_type(&value), expected);
 }
+
+#[test]
+fn hash_array_of_structs() {
+    let assets = (0..1000u32).map(|i| {
+        let mut amount = U256::default();
+        amount.0[28..].copy_from_slice(&i.to_be_bytes());
+        Asset {
+            token: Address([0xaa; 20]),
+            amount,
+        }
+    });
+    let mut concatenated = Vec::new();
+    for asset in assets.clone() {
+        concatenated.extend_from_slice(&hash_struct(&asset));
+    }
+    let expected = keccak_hash::keccak(&concatenated).to_fixed_bytes();
+    assert_eq!(hash_array_iter(assets), expected);
+}
+
+struct Upload {
+    name: String,
+    contents: HashedBytes,
+}
+
+impl StructType for Upload {
+    const TYPE_NAME: &'static str = "Upload";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("name", &self.name);
+        visitor.visit("contents", &self.contents);
+    }
+}
+
+#[test]
+fn streamed_bytes() {
+    let blob: Vec<u8> = (0..100_000u32).map(|i| i as u8).collect();
+    let from_reader = HashedBytes::from_reader(&blob[..]).unwrap();
+    assert_eq!(from_reader, HashedBytes::from_chunks(blob.chunks(999)));
+    assert_eq!(from_reader.len(), 100_000);
+    assert_eq!(
+        from_reader.hash(),
+        &keccak_hash::keccak(&blob).to_fixed_bytes()
+    );
+
+    let upload = Upload {
+        name: "blob".to_owned(),
+        contents: from_reader,
+    };
+    assert_eq!(encode_type(&upload), "Upload(string name,bytes contents)");
+    assert_eq!(&encode_data(&upload)[64..], from_reader.hash());
+}
+
+struct Payload {
+    data: Vec<u8>,
+    magic: &'static [u8],
+}
+
+impl StructType for Payload {
+    const TYPE_NAME: &'static str = "Payload";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("data", &self.data);
+        visitor.visit("magic", &self.magic);
+    }
+}
+
+#[test]
+fn bytes() {
+    let payload = Payload {
+        data: vec![1, 2, 3],
+        magic: b"\x7fELF",
+    };
+    assert_eq!(encode_type(&payload), "Payload(bytes data,bytes magic)");
+    // The same as streaming the contents
+    let streamed = HashedBytes::from_chunks([&[1u8, 2][..], &[3]]);
+    assert_eq!(&encode_data(&payload)[32..64], streamed.hash());
+    assert_eq!(
+        &encode_data(&payload)[64..],
+        &keccak_hash::keccak(b"\x7fELF").0
+    );
+}
+
+#[test]
+fn borrowed_strings() {
+    use std::borrow::Cow;
+
+    struct Note {
+        title: &'static str,
+        body: Cow<'static, str>,
+        tags: Vec<&'static str>,
+    }
+    impl StructType for Note {
+        const TYPE_NAME: &'static str = "Note";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("title", &self.title);
+            visitor.visit("body", &self.body);
+            visitor.visit("tags", &self.tags);
+        }
+    }
+    struct OwnedNote {
+        title: String,
+        body: String,
+        tags: Vec<String>,
+    }
+    impl StructType for OwnedNote {
+        const TYPE_NAME: &'static str = "Note";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("title", &self.title);
+            visitor.visit("body", &self.body);
+            visitor.visit("tags", &self.tags);
+        }
+    }
+
+    let note = Note {
+        title: "gm",
+        body: Cow::Owned("hello".to_owned()),
+        tags: vec!["a", "b"],
+    };
+    let owned = OwnedNote {
+        title: "gm".to_owned(),
+        body: "hello".to_owned(),
+        tags: vec!["a".to_owned(), "b".to_owned()],
+    };
+    assert_eq!(
+        encode_type(&note),
+        "Note(string title,string body,string[] tags)"
+    );
+    assert_eq!(hash_struct(&note), hash_struct(&owned));
+}
+
+#[test]
+fn pointer_members() {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    struct SharedTransaction {
+        from: Arc<Person>,
+        to: Box<Person>,
+        tx: &'static Asset,
+    }
+    impl StructType for SharedTransaction {
+        const TYPE_NAME: &'static str = "Transaction";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("from", &self.from);
+            visitor.visit("to", &self.to);
+            visitor.visit("tx", &self.tx);
+        }
+    }
+    static ASSET: Asset = Asset {
+        token: Address([1; 20]),
+        amount: U256([2; 32]),
+    };
+
+    let alice = Arc::new(Person {
+        wallet: Address([3; 20]),
+        name: "Alice".to_owned(),
+    });
+    let shared = SharedTransaction {
+        from: alice.clone(),
+        to: Box::new(Person::default()),
+        tx: &ASSET,
+    };
+    let owned = Transaction {
+        from: Person {
+            wallet: alice.wallet,
+            name: alice.name.clone(),
+        },
+        to: Person::default(),
+        tx: Asset {
+            token: ASSET.token,
+            amount: ASSET.amount,
+        },
+    };
+    // Pointers to Person and Person itself are the same struct type
+    assert_eq!(try_encode_type(&shared), Ok(encode_type(&owned)));
+    assert_eq!(hash_struct(&shared), hash_struct(&owned));
+
+    struct Counter {
+        count: Rc<U256>,
+        names: Vec<Arc<String>>,
+    }
+    impl StructType for Counter {
+        const TYPE_NAME: &'static str = "Counter";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("count", &self.count);
+            visitor.visit("names", &self.names);
+        }
+    }
+    let counter = Counter {
+        count: Rc::new(U256::ONE),
+        names: vec![Arc::new("a".to_owned())],
+    };
+    assert_eq!(
+        encode_type(&counter),
+        "Counter(uint256 count,string[] names)"
+    );
+}
+
+#[test]
+fn hashed() {
+    let person = Person {
+        wallet: Address([0xaa; 20]),
+        name: "Cow".to_owned(),
+    };
+    let hashed = Hashed::new(person);
+    assert_eq!(hashed.struct_hash(), &hash_struct(&*hashed));
+
+    let domain_separator = DomainSeparator::from_bytes(&[1u8; 32]);
+    assert_eq!(
+        hashed.sign_hash(&domain_separator),
+        sign_hash(&domain_separator, &*hashed)
+    );
+    #[cfg(feature = "sign")]
+    {
+        let key = SecretKey::from_bytes(&[1u8; 32]).unwrap();
+        let signature = sign_typed(&domain_separator, &*hashed, &key).unwrap();
+        assert_eq!(
+            hashed.recover(&domain_separator, &signature),
+            signature.recover(&sign_hash(&domain_separator, &*hashed))
+        );
+    }
+
+    // Nested, it encodes like the plain struct
+    struct Note {
+        author: Hashed<Person>,
+    }
+    impl StructType for Note {
+        const TYPE_NAME: &'static str = "Note";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("author", &self.author);
+        }
+    }
+    let expected = hash_struct(&*hashed);
+    let note = Note { author: hashed };
+    assert_eq!(
+        encode_type(&note),
+        "Note(Person author)Person(address wallet,string name)"
+    );
+    assert_eq!(&encode_data(&note)[32..], &expected);
+}
+
+#[test]
+fn hashed_alongside_plain() {
+    let person = |name: &str| Person {
+        wallet: Address([0xaa; 20]),
+        name: name.to_owned(),
+    };
+
+    // Hashed<Person> is the same struct type as Person
+    struct Pair {
+        first: Hashed<Person>,
+        second: Hashed<Person>,
+        third: Person,
+    }
+    impl StructType for Pair {
+        const TYPE_NAME: &'static str = "Pair";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("first", &self.first);
+            visitor.visit("second", &self.second);
+            visitor.visit("third", &self.third);
+        }
+    }
+    let pair = Pair {
+        first: Hashed::new(person("a")),
+        second: Hashed::new(person("b")),
+        third: person("c"),
+    };
+    assert_eq!(
+        try_encode_type(&pair),
+        Ok("Pair(Person first,Person second,Person third)\
+             Person(address wallet,string name)"
+            .to_owned())
+    );
+    assert_eq!(&encode_data(&pair)[96..128], &hash_struct(&person("c")));
+}
+
+struct Batch {
+    amounts: [U256; 3],
+    recipients: [Person; 2],
+}
+
+impl StructType for Batch {
+    const TYPE_NAME: &'static str = "Batch";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("amounts", &self.amounts);
+        visitor.visit("recipients", &self.recipients);
+    }
+}
+
+#[test]
+fn fixed_arrays() {
+    let person = |name: &str| Person {
+        wallet: Address([0xbb; 20]),
+        name: name.to_owned(),
+    };
+    let batch = Batch {
+        amounts: [U256([1; 32]), U256([2; 32]), U256([3; 32])],
+        recipients: [person("Bob"), person("Carol")],
+    };
+    assert_eq!(
+        encode_type(&batch),
+        "Batch(uint256[3] amounts,Person[2] recipients)Person(address wallet,string name)"
+    );
+
+    let mut expected = type_hash(&batch).to_vec();
+    expected.extend_from_slice(&keccak_hash::keccak([[1u8; 32], [2; 32], [3; 32]].concat()).0);
+    let recipients = [
+        hash_struct(&batch.recipients[0]),
+        hash_struct(&batch.recipients[1]),
+    ];
+    expected.extend_from_slice(&keccak_hash::keccak(recipients.concat()).0);
+    assert_eq!(encode_data(&batch), expected);
+}
+
+struct NoRecipients {
+    recipients: [Person; 0],
+}
+
+impl StructType for NoRecipients {
+    const TYPE_NAME: &'static str = "NoRecipients";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("recipients", &self.recipients);
+    }
+}
+
+#[test]
+fn empty_fixed_arrays() {
+    // The type hash is memoized by type, so it must not depend on the elements
+    let empty = NoRecipients { recipients: [] };
+    let expected = "NoRecipients(Person[0] recipients)Person(address wallet,string name)";
+    assert_eq!(
+        type_hash(&empty),
+        keccak_hash::keccak(expected).to_fixed_bytes()
+    );
+    assert_eq!(encode_type(&empty), expected);
+}
+
+#[derive(Default)]
+struct Payroll {
+    payments: Vec<Asset>,
+    memos: Vec<String>,
+}
+
+impl StructType for Payroll {
+    const TYPE_NAME: &'static str = "Payroll";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("payments", &self.payments);
+        visitor.visit("memos", &self.memos);
+    }
+}
+
+#[test]
+fn dynamic_arrays() {
+    // The referenced Asset type is known without any elements
+    let empty = Payroll::default();
+    let expected_type =
+        "Payroll(Asset[] payments,string[] memos)Asset(address token,uint256 amount)";
+    assert_eq!(encode_type(&empty), expected_type);
+
+    let mut expected = type_hash(&empty).to_vec();
+    expected.extend_from_slice(&keccak_hash::keccak([]).0);
+    expected.extend_from_slice(&keccak_hash::keccak([]).0);
+    assert_eq!(encode_data(&empty), expected);
+
+    let payroll = Payroll {
+        payments: vec![Asset::default(), Asset::default()],
+        memos: vec!["rent".to_owned()],
+    };
+    assert_eq!(encode_type(&payroll), expected_type);
+    let mut expected = type_hash(&payroll).to_vec();
+    expected.extend_from_slice(&hash_array_iter(vec![Asset::default(), Asset::default()]));
+    expected.extend_from_slice(&keccak_hash::keccak(keccak_hash::keccak("rent").0).0);
+    assert_eq!(encode_data(&payroll), expected);
+}
+
+// Neither type implements Default
+struct Recipient {
+    wallet: Address,
+}
+
+impl StructType for Recipient {
+    const TYPE_NAME: &'static str = "Recipient";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("wallet", &self.wallet);
+    }
+    fn visit_member_types<T: TypeVisitor>(visitor: &mut T) -> Result<(), Error> {
+        visitor.visit::<Address>("wallet");
+        Ok(())
+    }
+}
+
+struct Mailing {
+    to: Vec<Recipient>,
+}
+
+impl StructType for Mailing {
+    const TYPE_NAME: &'static str = "Mailing";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("to", &self.to);
+    }
+}
+
+#[test]
+fn dynamic_arrays_of_any_struct() {
+    let empty = Mailing { to: Vec::new() };
+    assert_eq!(
+        encode_type(&empty),
+        "Mailing(Recipient[] to)Recipient(address wallet)"
+    );
+    let mailing = Mailing {
+        to: vec![Recipient {
+            wallet: Address([0xbb; 20]),
+        }],
+    };
+    assert_eq!(type_hash(&mailing), type_hash(&empty));
+}
+
+// Dai's permit
+#[derive(Default)]
+struct Permit {
+    holder: Address,
+    spender: Address,
+    nonce: U256,
+    expiry: U256,
+    allowed: bool,
+}
+
+impl StructType for Permit {
+    const TYPE_NAME: &'static str = "Permit";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("holder", &self.holder);
+        visitor.visit("spender", &self.spender);
+        visitor.visit("nonce", &self.nonce);
+        visitor.visit("expiry", &self.expiry);
+        visitor.visit("allowed", &self.allowed);
+    }
+}
+
+#[test]
+fn bool_members() {
+    let mut permit = Permit::default();
+    assert_eq!(
+        encode_type(&permit),
+        "Permit(address holder,address spender,uint256 nonce,uint256 expiry,bool allowed)"
+    );
+    assert_eq!(encode_data(&permit)[160..], [0u8; 32]);
+    permit.allowed = true;
+    let mut one = [0u8; 32];
+    one[31] = 1;
+    assert_eq!(encode_data(&permit)[160..], one);
+}
+
+struct Order {
+    nonce: Uint64,
+    expiry: Uint40,
+}
+
+impl StructType for Order {
+    const TYPE_NAME: &'static str = "Order";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("nonce", &self.nonce);
+        visitor.visit("expiry", &self.expiry);
+    }
+}
+
+#[test]
+fn narrow_uints() {
+    let mut value = U256::default();
+    value.0[24] = 0xff;
+    let nonce = Uint64::new(value).unwrap();
+    value.0[23] = 1;
+    assert_eq!(Uint64::new(value), None);
+    assert_eq!(Uint72::new(value).unwrap().value(), value);
+
+    let order = Order {
+        nonce,
+        expiry: Uint40::default(),
+    };
+    assert_eq!(encode_type(&order), "Order(uint64 nonce,uint40 expiry)");
+    assert_eq!(encode_data(&order)[32..64], U256::from(nonce).0);
+}
+
+struct Position {
+    id: u64,
+    size: i128,
+    leverage: i8,
+}
+
+impl StructType for Position {
+    const TYPE_NAME: &'static str = "Position";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("id", &self.id);
+        visitor.visit("size", &self.size);
+        visitor.visit("leverage", &self.leverage);
+    }
+}
+
+#[test]
+fn primitive_ints() {
+    let position = Position {
+        id: 0x0102,
+        size: -2,
+        leverage: 5,
+    };
+    assert_eq!(
+        encode_type(&position),
+        "Position(uint64 id,int128 size,int8 leverage)"
+    );
+    let data = encode_data(&position);
+    let mut id = [0u8; 32];
+    id[30..].copy_from_slice(&[1, 2]);
+    assert_eq!(data[32..64], id);
+    let mut size = [0xffu8; 32];
+    size[31] = 0xfe;
+    assert_eq!(data[64..96], size);
+    let mut leverage = [0u8; 32];
+    leverage[31] = 5;
+    assert_eq!(data[96..], leverage);
+}
+
+mod other {
+    use eip_712_derive::*;
+
+    /// Not the same type as the outer Person
+    #[derive(Default)]
+    pub struct Person {
+        pub name: String,
+    }
+
+    impl StructType for Person {
+        const TYPE_NAME: &'static str = "Person";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("name", &self.name);
+        }
+    }
+}
+
+#[derive(Default)]
+struct Introduction {
+    from: Person,
+    to: other::Person,
+}
+
+impl StructType for Introduction {
+    const TYPE_NAME: &'static str = "Introduction";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("from", &self.from);
+        visitor.visit("to", &self.to);
+    }
+}
+
+#[test]
+fn duplicated_type_names() {
+    let value = Introduction::default();
+    let expected = Err(Error::DuplicateTypeName("Person".to_owned()));
+    assert_eq!(try_encode_type(&value).map(|_| ()), expected);
+    assert_eq!(try_type_hash(&value).map(|_| ()), expected);
+    assert_eq!(try_hash_struct(&value).map(|_| ()), expected);
+    #[cfg(feature = "sign")]
+    assert_eq!(
+        try_sign_typed(
+            &DomainSeparator::default(),
+            &value,
+            &SecretKey::from_bytes(&[1; 32]).unwrap()
+        )
+        .map(|_| ()),
+        expected
+    );
+    assert!(std::panic::catch_unwind(|| encode_type(&value)).is_err());
+
+    assert_eq!(
+        try_hash_struct(&Person::default()),
+        Ok(hash_struct(&Person::default()))
+    );
+}
+
+/// Misreports the name of the struct type it adds
+struct Misnamed(Person);
+
+impl MemberType for Misnamed {
+    fn type_name() -> std::borrow::Cow<'static, str> {
+        "Human".into()
+    }
+    fn encode_data(&self) -> Bytes32 {
+        self.0.encode_data()
+    }
+    fn add_members(&self, builder: &mut eip_712_derive::__private::TypeHashBuilder) {
+        self.0.add_members(builder)
+    }
+}
+
+#[test]
+fn misreported_type_names() {
+    struct Pair {
+        first: Misnamed,
+        second: Misnamed,
+    }
+    impl StructType for Pair {
+        const TYPE_NAME: &'static str = "Pair";
+        fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+            visitor.visit("first", &self.first);
+            visitor.visit("second", &self.second);
+        }
+    }
+    let pair = Pair {
+        first: Misnamed(Person::default()),
+        second: Misnamed(Person::default()),
+    };
+    let expected = Err(Error::DuplicateTypeName("Person".to_owned()));
+    assert_eq!(try_encode_type(&pair), expected);
+    assert_eq!(try_type_hash(&pair).map(|_| ()), expected.map(|_| ()));
+}
+
+struct Swap {
+    amount_in: U256,
+    amount_out: U256,
+}
+
+impl StructType for Swap {
+    const TYPE_NAME: &'static str = "Swap";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("amount", &self.amount_in);
+        visitor.visit("amount", &self.amount_out);
+    }
+}
+
+#[test]
+fn duplicated_member_names() {
+    let swap = Swap {
+        amount_in: U256::ONE,
+        amount_out: U256::ONE,
+    };
+    assert_eq!(
+        try_encode_type(&swap),
+        Err(Error::DuplicateMember {
+            type_name: "Swap".to_owned(),
+            member: "amount".to_owned()
+        })
+    );
+}
+
+struct BadNames {
+    value: U256,
+}
+
+impl StructType for BadNames {
+    const TYPE_NAME: &'static str = "Bad Names";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        visitor.visit("value", &self.value);
+    }
+}
+
+struct BadMember {
+    value: U256,
+}
+
+impl StructType for BadMember {
+    const TYPE_NAME: &'static str = "BadMember";
+    fn visit_members<T: MemberVisitor>(&self, visitor: &mut T) {
+        // Cyrillic а
+        visitor.visit("vаlue", &self.value);
+    }
+}
+
+#[test]
+fn invalid_identifiers() {
+    assert_eq!(
+        try_encode_type(&BadNames { value: U256::ZERO }),
+        Err(Error::InvalidIdentifier("Bad Names".to_owned()))
+    );
+    assert_eq!(
+        try_encode_type(&BadMember { value: U256::ZERO }),
+        Err(Error::InvalidIdentifier("BadMember.vаlue".to_owned()))
+    );
+}
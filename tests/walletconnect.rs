@@ -0,0 +1,36 @@
+#![cfg(feature = "walletconnect")]
+
+use eip_712_derive::dynamic::TypedData;
+use eip_712_derive::walletconnect::session_request;
+use eip_712_derive::*;
+use serde_json::json;
+
+#[test]
+fn wraps_typed_data() {
+    let typed_data = json!({
+        "types": { "EIP712Domain": [] },
+        "primaryType": "EIP712Domain",
+        "domain": {},
+        "message": {},
+    });
+    let account = Address([0xab; 20]);
+    let request = session_request(
+        7,
+        42161,
+        &account,
+        &TypedData::from_json_value(&typed_data).unwrap(),
+    );
+
+    assert_eq!(request["id"], 7);
+    assert_eq!(request["method"], "wc_sessionRequest");
+    assert_eq!(request["params"]["chainId"], "eip155:42161");
+    let inner = &request["params"]["request"];
+    assert_eq!(inner["method"], "eth_signTypedData_v4");
+    assert_eq!(
+        inner["params"][0],
+        "0xabababababababababababababababababababab"
+    );
+    let reparsed: serde_json::Value =
+        serde_json::from_str(inner["params"][1].as_str().unwrap()).unwrap();
+    assert_eq!(reparsed, typed_data);
+}